@@ -2,6 +2,17 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
 mod m20251028_000002_restructure_major_department;
+mod m20251101_000003_add_wallet_contract_owner;
+mod m20251105_000004_create_chain_deployment;
+mod m20251110_000005_create_chain_job;
+mod m20251115_000006_create_merkle_batch;
+mod m20251120_000007_create_import_job;
+mod m20251122_000008_add_import_job_error_rows;
+mod m20251124_000009_add_import_job_existing_user_mode;
+mod m20251126_000010_create_gas_fee_spend;
+mod m20251128_000011_create_student_migration_job;
+mod m20251130_000012_add_chain_job_tx_hash;
+mod m20251202_000013_create_student_chain_status;
 
 pub struct Migrator;
 
@@ -11,6 +22,17 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20220101_000001_create_table::Migration),
             Box::new(m20251028_000002_restructure_major_department::Migration),
+            Box::new(m20251101_000003_add_wallet_contract_owner::Migration),
+            Box::new(m20251105_000004_create_chain_deployment::Migration),
+            Box::new(m20251110_000005_create_chain_job::Migration),
+            Box::new(m20251115_000006_create_merkle_batch::Migration),
+            Box::new(m20251120_000007_create_import_job::Migration),
+            Box::new(m20251122_000008_add_import_job_error_rows::Migration),
+            Box::new(m20251124_000009_add_import_job_existing_user_mode::Migration),
+            Box::new(m20251126_000010_create_gas_fee_spend::Migration),
+            Box::new(m20251128_000011_create_student_migration_job::Migration),
+            Box::new(m20251130_000012_add_chain_job_tx_hash::Migration),
+            Box::new(m20251202_000013_create_student_chain_status::Migration),
         ]
     }
 }
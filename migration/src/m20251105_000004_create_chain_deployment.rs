@@ -0,0 +1,162 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Registry of named networks / DataStorage deployments
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChainDeployment::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainDeployment::DeploymentId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainDeployment::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainDeployment::ChainId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainDeployment::RpcUrl).string().not_null())
+                    .col(
+                        ColumnDef::new(ChainDeployment::ContractAddress)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainDeployment::IsDefault)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ChainDeployment::IsActive)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(ChainDeployment::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(ChainDeployment::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Per-wallet deployment override
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Wallet::Table)
+                    .add_column(ColumnDef::new(Wallet::ChainDeploymentId).uuid().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_wallet_chain_deployment")
+                            .from_tbl(Wallet::Table)
+                            .from_col(Wallet::ChainDeploymentId)
+                            .to_tbl(ChainDeployment::Table)
+                            .to_col(ChainDeployment::DeploymentId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. Per-department (tenant) deployment
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Department::Table)
+                    .add_column(ColumnDef::new(Department::ChainDeploymentId).uuid().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_department_chain_deployment")
+                            .from_tbl(Department::Table)
+                            .from_col(Department::ChainDeploymentId)
+                            .to_tbl(ChainDeployment::Table)
+                            .to_col(ChainDeployment::DeploymentId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Department::Table)
+                    .drop_foreign_key(Alias::new("fk_department_chain_deployment"))
+                    .drop_column(Department::ChainDeploymentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Wallet::Table)
+                    .drop_foreign_key(Alias::new("fk_wallet_chain_deployment"))
+                    .drop_column(Wallet::ChainDeploymentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ChainDeployment::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChainDeployment {
+    Table,
+    DeploymentId,
+    Name,
+    ChainId,
+    RpcUrl,
+    ContractAddress,
+    IsDefault,
+    IsActive,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    ChainDeploymentId,
+}
+
+#[derive(DeriveIden)]
+enum Department {
+    Table,
+    ChainDeploymentId,
+}
@@ -0,0 +1,125 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Chain writes queued while the RPC node was unavailable
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChainJob::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainJob::JobId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ChainJob::Kind).string().not_null())
+                    .col(ColumnDef::new(ChainJob::Payload).json_binary().not_null())
+                    .col(ColumnDef::new(ChainJob::DeploymentId).uuid().null())
+                    .col(ColumnDef::new(ChainJob::SignerUserId).uuid().null())
+                    .col(
+                        ColumnDef::new(ChainJob::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(
+                        ColumnDef::new(ChainJob::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ChainJob::LastError).text().null())
+                    .col(
+                        ColumnDef::new(ChainJob::NextAttemptAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(ChainJob::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(ChainJob::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chain_job_deployment")
+                            .from(ChainJob::Table, ChainJob::DeploymentId)
+                            .to(ChainDeployment::Table, ChainDeployment::DeploymentId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chain_job_signer_user")
+                            .from(ChainJob::Table, ChainJob::SignerUserId)
+                            .to(User::Table, User::UserId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_chain_job_status_next_attempt")
+                    .table(ChainJob::Table)
+                    .col(ChainJob::Status)
+                    .col(ChainJob::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChainJob::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChainJob {
+    Table,
+    JobId,
+    Kind,
+    Payload,
+    DeploymentId,
+    SignerUserId,
+    Status,
+    Attempts,
+    LastError,
+    NextAttemptAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ChainDeployment {
+    Table,
+    DeploymentId,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
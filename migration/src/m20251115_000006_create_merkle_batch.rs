@@ -0,0 +1,176 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Student imports anchored on-chain as a single Merkle root
+        manager
+            .create_table(
+                Table::create()
+                    .table(MerkleBatch::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MerkleBatch::BatchId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MerkleBatch::DeploymentId).uuid().null())
+                    .col(
+                        ColumnDef::new(MerkleBatch::Root)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(MerkleBatch::LeafCount).integer().not_null())
+                    .col(
+                        ColumnDef::new(MerkleBatch::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(ColumnDef::new(MerkleBatch::TxHash).string().null())
+                    .col(ColumnDef::new(MerkleBatch::AnchoredBy).string().null())
+                    .col(
+                        ColumnDef::new(MerkleBatch::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .col(ColumnDef::new(MerkleBatch::AnchoredAt).timestamp().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_merkle_batch_deployment")
+                            .from(MerkleBatch::Table, MerkleBatch::DeploymentId)
+                            .to(ChainDeployment::Table, ChainDeployment::DeploymentId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One proof per student, together with the leaf data it commits to
+        manager
+            .create_table(
+                Table::create()
+                    .table(MerkleProof::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MerkleProof::UserId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MerkleProof::BatchId).uuid().not_null())
+                    .col(ColumnDef::new(MerkleProof::LeafIndex).integer().not_null())
+                    .col(ColumnDef::new(MerkleProof::LeafHash).string().not_null())
+                    .col(ColumnDef::new(MerkleProof::Proof).json_binary().not_null())
+                    .col(
+                        ColumnDef::new(MerkleProof::WalletAddress)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MerkleProof::StudentCode)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(MerkleProof::FullName).string().not_null())
+                    .col(ColumnDef::new(MerkleProof::Email).string().not_null())
+                    .col(
+                        ColumnDef::new(MerkleProof::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_merkle_proof_user")
+                            .from(MerkleProof::Table, MerkleProof::UserId)
+                            .to(User::Table, User::UserId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_merkle_proof_batch")
+                            .from(MerkleProof::Table, MerkleProof::BatchId)
+                            .to(MerkleBatch::Table, MerkleBatch::BatchId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_merkle_proof_batch_id")
+                    .table(MerkleProof::Table)
+                    .col(MerkleProof::BatchId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MerkleProof::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(MerkleBatch::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum MerkleBatch {
+    Table,
+    BatchId,
+    DeploymentId,
+    Root,
+    LeafCount,
+    Status,
+    TxHash,
+    AnchoredBy,
+    CreatedAt,
+    AnchoredAt,
+}
+
+#[derive(DeriveIden)]
+enum MerkleProof {
+    Table,
+    UserId,
+    BatchId,
+    LeafIndex,
+    LeafHash,
+    Proof,
+    WalletAddress,
+    StudentCode,
+    FullName,
+    Email,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ChainDeployment {
+    Table,
+    DeploymentId,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
@@ -0,0 +1,148 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Bulk user imports processed in the background
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportJob::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportJob::JobId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImportJob::CreatedBy).uuid().null())
+                    .col(ColumnDef::new(ImportJob::FileName).string().null())
+                    .col(
+                        ColumnDef::new(ImportJob::RegistrationMode)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(ColumnDef::new(ImportJob::TotalRows).integer().not_null())
+                    .col(
+                        ColumnDef::new(ImportJob::ProcessedRows)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::Successful)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::Failed)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ImportJob::Results).json_binary().not_null())
+                    .col(
+                        ColumnDef::new(ImportJob::ChainBatches)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::UnmappedColumns)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImportJob::MerkleBatchId).uuid().null())
+                    .col(ColumnDef::new(ImportJob::Error).text().null())
+                    .col(
+                        ColumnDef::new(ImportJob::CancelRequested)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .col(ColumnDef::new(ImportJob::StartedAt).timestamp().null())
+                    .col(ColumnDef::new(ImportJob::FinishedAt).timestamp().null())
+                    .col(
+                        ColumnDef::new(ImportJob::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_import_job_created_by")
+                            .from(ImportJob::Table, ImportJob::CreatedBy)
+                            .to(User::Table, User::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_import_job_status")
+                    .table(ImportJob::Table)
+                    .col(ImportJob::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportJob::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportJob {
+    Table,
+    JobId,
+    CreatedBy,
+    FileName,
+    RegistrationMode,
+    Status,
+    TotalRows,
+    ProcessedRows,
+    Successful,
+    Failed,
+    Results,
+    ChainBatches,
+    UnmappedColumns,
+    MerkleBatchId,
+    Error,
+    CancelRequested,
+    CreatedAt,
+    StartedAt,
+    FinishedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Keep the cells of failed rows so they can be downloaded, fixed and re-uploaded
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ImportJob::Table)
+                    .add_column(
+                        ColumnDef::new(ImportJob::SourceHeader)
+                            .json_binary()
+                            .not_null()
+                            .extra("DEFAULT '[]'::jsonb".to_string()),
+                    )
+                    .add_column(
+                        ColumnDef::new(ImportJob::FailedRows)
+                            .json_binary()
+                            .not_null()
+                            .extra("DEFAULT '{}'::jsonb".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ImportJob::Table)
+                    .drop_column(ImportJob::SourceHeader)
+                    .drop_column(ImportJob::FailedRows)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportJob {
+    Table,
+    SourceHeader,
+    FailedRows,
+}
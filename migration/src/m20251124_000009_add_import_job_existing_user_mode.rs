@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // What an import does with rows for users that already exist
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ImportJob::Table)
+                    .add_column(
+                        ColumnDef::new(ImportJob::ExistingUserMode)
+                            .string()
+                            .not_null()
+                            .default("fail"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ImportJob::Table)
+                    .drop_column(ImportJob::ExistingUserMode)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportJob {
+    Table,
+    ExistingUserMode,
+}
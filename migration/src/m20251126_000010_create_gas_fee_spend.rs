@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Fees reserved or spent per signer per UTC day, shared by every instance
+        manager
+            .create_table(
+                Table::create()
+                    .table(GasFeeSpend::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(GasFeeSpend::Signer).string().not_null())
+                    .col(ColumnDef::new(GasFeeSpend::Day).date().not_null())
+                    .col(
+                        ColumnDef::new(GasFeeSpend::SpentWei)
+                            .decimal_len(78, 0)
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(GasFeeSpend::Signer)
+                            .col(GasFeeSpend::Day),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GasFeeSpend::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GasFeeSpend {
+    Table,
+    Signer,
+    Day,
+    SpentWei,
+}
@@ -0,0 +1,163 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Student registrations copied between deployments in the background
+        manager
+            .create_table(
+                Table::create()
+                    .table(StudentMigrationJob::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::JobId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StudentMigrationJob::CreatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::FromDeploymentId)
+                            .uuid()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::ToDeploymentId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::Reassign)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::RetireSource)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::Report)
+                            .json_binary()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(StudentMigrationJob::Error).text().null())
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::StartedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::FinishedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentMigrationJob::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_student_migration_job_created_by")
+                            .from(StudentMigrationJob::Table, StudentMigrationJob::CreatedBy)
+                            .to(User::Table, User::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_student_migration_job_to_deployment")
+                            .from(
+                                StudentMigrationJob::Table,
+                                StudentMigrationJob::ToDeploymentId,
+                            )
+                            .to(ChainDeployment::Table, ChainDeployment::DeploymentId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // At most one default deployment; keep the most recently updated one
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"UPDATE chain_deployment SET is_default = FALSE
+               WHERE is_default AND deployment_id <> (
+                   SELECT deployment_id FROM chain_deployment
+                   WHERE is_default ORDER BY updated_at DESC LIMIT 1
+               )"#,
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_chain_deployment_single_default \
+             ON chain_deployment (is_default) WHERE is_default",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_chain_deployment_single_default")
+                    .table(ChainDeployment::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(StudentMigrationJob::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum StudentMigrationJob {
+    Table,
+    JobId,
+    CreatedBy,
+    FromDeploymentId,
+    ToDeploymentId,
+    Reassign,
+    RetireSource,
+    Status,
+    Report,
+    Error,
+    CreatedAt,
+    StartedAt,
+    FinishedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum ChainDeployment {
+    Table,
+    DeploymentId,
+}
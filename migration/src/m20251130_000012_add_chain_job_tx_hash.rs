@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Transaction of a job that may already have been broadcast, checked
+        // before the job is sent again
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChainJob::Table)
+                    .add_column(ColumnDef::new(ChainJob::TxHash).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChainJob::Table)
+                    .drop_column(ChainJob::TxHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChainJob {
+    Table,
+    TxHash,
+}
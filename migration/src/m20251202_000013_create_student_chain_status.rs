@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Student code and status of each wallet registered in DataStorage, per
+        // contract, so user lists can filter on them without reading the chain
+        manager
+            .create_table(
+                Table::create()
+                    .table(StudentChainStatus::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StudentChainStatus::ContractAddress)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentChainStatus::WalletAddress)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentChainStatus::StudentCode)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentChainStatus::IsActive)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StudentChainStatus::SyncedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(StudentChainStatus::ContractAddress)
+                            .col(StudentChainStatus::WalletAddress),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_student_chain_status_wallet_address")
+                    .table(StudentChainStatus::Table)
+                    .col(StudentChainStatus::WalletAddress)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StudentChainStatus::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum StudentChainStatus {
+    Table,
+    ContractAddress,
+    WalletAddress,
+    StudentCode,
+    IsActive,
+    SyncedAt,
+}
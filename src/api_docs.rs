@@ -12,10 +12,16 @@ use utoipa::openapi::security::SecurityScheme;
         crate::routes::profile::route::get_profile,
         crate::routes::users::route::create_user,
         crate::routes::users::route::create_users_bulk,
+        crate::routes::users::route::get_import_template,
+        crate::routes::users::route::export_users,
         crate::routes::users::route::get_all_users,
         crate::routes::users::route::get_user_by_id,
         crate::routes::users::route::update_user,
         crate::routes::users::route::delete_user,
+        crate::routes::users::route::batch_users,
+        crate::routes::import_jobs::route::get_import_job,
+        crate::routes::import_jobs::route::cancel_import_job,
+        crate::routes::import_jobs::route::get_import_error_file,
         crate::routes::departments::route::create_department,
         crate::routes::departments::route::get_all_departments,
         crate::routes::departments::route::get_department,
@@ -30,13 +36,42 @@ use utoipa::openapi::security::SecurityScheme;
         crate::routes::managers::route::remove_manager,
         crate::routes::managers::route::get_all_managers,
         crate::routes::managers::route::check_manager,
+        crate::routes::students::route::get_all_students,
         crate::routes::students::route::get_student_by_id,
+        crate::routes::students::route::get_student_by_user_id,
         crate::routes::students::route::get_student_id_by_address,
         crate::routes::students::route::get_student_id_by_code,
         crate::routes::students::route::deactivate_student,
         crate::routes::students::route::activate_student,
         crate::routes::students::route::check_student_active,
+        crate::routes::students::route::get_student_qr,
+        crate::routes::students::route::verify_student_pass,
         crate::routes::students::route::get_system_info,
+        crate::routes::contract::route::get_contract_owner,
+        crate::routes::contract::route::transfer_ownership,
+        crate::routes::contract::route::authorize_contract,
+        crate::routes::contract::route::unauthorize_contract,
+        crate::routes::contract::route::get_authorized_contracts,
+        crate::routes::contract::route::speed_up_transaction,
+        crate::routes::contract::route::cancel_transaction,
+        crate::routes::chain::route::get_all_deployments,
+        crate::routes::chain::route::create_deployment,
+        crate::routes::chain::route::set_default_deployment,
+        crate::routes::chain::route::assign_user_deployment,
+        crate::routes::chain::route::assign_department_deployment,
+        crate::routes::chain::route::migrate_deployment_students,
+        crate::routes::chain::route::get_migration_job,
+        crate::routes::chain::route::get_chain_health,
+        crate::routes::chain::route::get_chain_jobs,
+        crate::routes::chain::route::retry_chain_job,
+        crate::routes::chain::route::get_chain_cache_stats,
+        crate::routes::chain::route::clear_chain_cache,
+        crate::routes::merkle::route::get_merkle_batch,
+        crate::routes::merkle::route::anchor_batch,
+        crate::routes::merkle::route::get_student_proof,
+        crate::routes::merkle::route::verify_student_proof,
+        crate::routes::credentials::route::issue_credential,
+        crate::routes::credentials::route::verify_credential,
     ),
     components(
         schemas(
@@ -48,8 +83,22 @@ use utoipa::openapi::security::SecurityScheme;
             crate::routes::users::dto::UserResponse,
             crate::routes::users::dto::UserDetailResponse,
             crate::routes::users::dto::UserListResponse,
-            crate::routes::users::dto::BulkUserResponse,
-            crate::routes::users::dto::BulkUserError,
+            crate::routes::users::dto::RegistrationMode,
+            crate::routes::users::dto::ExistingUserMode,
+            crate::routes::users::dto::BulkImportReport,
+            crate::routes::users::dto::BulkRowReport,
+            crate::routes::users::dto::BatchOperation,
+            crate::routes::users::dto::BatchUserRequest,
+            crate::routes::users::dto::BatchUserResponse,
+            crate::routes::users::dto::BatchUserResult,
+            crate::routes::users::dto::UserSortColumn,
+            crate::routes::users::dto::SortOrder,
+            crate::routes::import_jobs::dto::ImportJobResponse,
+            crate::routes::users::import_job::ImportRowResult,
+            crate::routes::users::import_job::ImportRowStatus,
+            crate::routes::users::import_job::ImportChainBatch,
+            crate::routes::users::import_job::ChainBatchStatus,
+            crate::utils::spreadsheet::SpreadsheetFormat,
             crate::routes::departments::dto::CreateDepartmentRequest,
             crate::routes::departments::dto::UpdateDepartmentRequest,
             crate::routes::departments::dto::DepartmentResponse,
@@ -67,9 +116,50 @@ use utoipa::openapi::security::SecurityScheme;
             crate::routes::students::dto::StudentAddressRequest,
             crate::routes::students::dto::StudentCodeRequest,
             crate::routes::students::dto::StudentInfoResponse,
+            crate::routes::students::dto::StudentRecordResponse,
+            crate::routes::students::dto::StudentListResponse,
             crate::routes::students::dto::StudentStatusResponse,
+            crate::routes::students::dto::QrFormat,
+            crate::routes::students::dto::VerifyStudentPassRequest,
+            crate::routes::students::dto::VerifyStudentPassResponse,
+            crate::blockchain::StudentPass,
             crate::routes::students::dto::StudentIdResponse,
             crate::routes::students::dto::SystemInfoResponse,
+            crate::routes::contract::dto::ContractOwnerResponse,
+            crate::routes::contract::dto::TransferOwnershipRequest,
+            crate::routes::contract::dto::ContractAddressRequest,
+            crate::routes::contract::dto::AuthorizedContractResponse,
+            crate::routes::contract::dto::AuthorizedContractInfo,
+            crate::routes::contract::dto::AuthorizedContractListResponse,
+            crate::routes::contract::dto::ReplaceTransactionResponse,
+            crate::routes::chain::dto::CreateChainDeploymentRequest,
+            crate::routes::chain::dto::ChainDeploymentResponse,
+            crate::routes::chain::dto::ChainDeploymentListResponse,
+            crate::routes::chain::dto::AssignDeploymentRequest,
+            crate::routes::chain::dto::DeploymentAssignmentResponse,
+            crate::routes::chain::dto::MigrateStudentsRequest,
+            crate::routes::chain::dto::MigrateStudentsResponse,
+            crate::routes::chain::dto::StudentMigrationJobResponse,
+            crate::routes::chain::dto::StudentMigrationFailureInfo,
+            crate::routes::chain::dto::ChainHealthResponse,
+            crate::routes::chain::dto::ChainJobResponse,
+            crate::routes::chain::dto::ChainJobListResponse,
+            crate::routes::chain::dto::ClearChainCacheResponse,
+            crate::blockchain::ChainStatus,
+            crate::blockchain::NodeHealth,
+            crate::blockchain::health::CircuitState,
+            crate::blockchain::CacheStats,
+            crate::blockchain::MethodStats,
+            crate::routes::merkle::dto::MerkleBatchResponse,
+            crate::routes::merkle::dto::AnchorMerkleBatchResponse,
+            crate::routes::merkle::dto::MerkleProofResponse,
+            crate::routes::merkle::dto::VerifyMerkleProofRequest,
+            crate::routes::merkle::dto::VerifyMerkleProofResponse,
+            crate::routes::credentials::dto::IssueCredentialRequest,
+            crate::routes::credentials::dto::CredentialResponse,
+            crate::routes::credentials::dto::VerifyCredentialRequest,
+            crate::routes::credentials::dto::VerifyCredentialResponse,
+            crate::blockchain::StudentCredential,
             crate::entities::sea_orm_active_enums::RoleEnum,
         ),
     ),
@@ -78,11 +168,16 @@ use utoipa::openapi::security::SecurityScheme;
         (name = "Authentication", description = "Login and JWT token endpoints"),
         (name = "Profile", description = "Current user profile with blockchain info"),
         (name = "Users", description = "User management endpoints"),
+        (name = "Import Jobs", description = "Progress, results and cancellation of background bulk user imports"),
         (name = "Departments", description = "Department CRUD endpoints"),
         (name = "Majors", description = "Major CRUD endpoints"),
         (name = "Managers", description = "Manager management endpoints"),
         (name = "Students", description = "Student information endpoints"),
         (name = "System", description = "System information endpoints"),
+        (name = "Contract", description = "Contract ownership and authorization administration"),
+        (name = "Chain", description = "Network and contract deployment registry, node health, queued chain work and view call cache"),
+        (name = "Merkle", description = "Merkle-root student batches, proofs and proof verification"),
+        (name = "Credentials", description = "Signed student identity credentials (EIP-712) and public verification"),
         (name = "health", description = "Health check endpoints")
     ),
 )]
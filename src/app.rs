@@ -16,10 +16,15 @@ pub async fn create_app() -> anyhow::Result<Router> {
         .merge(routes::auth::create_route())
         .merge(routes::profile::create_route())
         .merge(routes::users::create_route())
+        .merge(routes::import_jobs::create_route())
         .merge(routes::departments::create_route())
         .merge(routes::majors::create_route())
         .merge(routes::managers::create_route())
-        .merge(routes::students::create_route());
+        .merge(routes::students::create_route())
+        .merge(routes::contract::create_route())
+        .merge(routes::chain::create_route())
+        .merge(routes::merkle::create_route())
+        .merge(routes::credentials::create_route());
 
     // Add Swagger UI
     if APP_CONFIG.swagger_enabled {
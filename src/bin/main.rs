@@ -1,6 +1,12 @@
 use std::net::SocketAddr;
 
-use auth_service::bootstrap::initialize_admin_user;
+use auth_service::blockchain::{
+    spawn_cache_invalidator, spawn_chain_job_worker, spawn_health_monitor,
+    spawn_student_status_sync,
+};
+use auth_service::bootstrap::{
+    fail_interrupted_import_jobs, fail_interrupted_student_migration_jobs, initialize_admin_user,
+};
 use auth_service::static_service::get_database_connection;
 use auth_service::{app, config::APP_CONFIG, utils::tracing::init_standard_tracing};
 
@@ -22,6 +28,21 @@ async fn main() -> anyhow::Result<()> {
         tracing::warn!("Continuing without admin user initialization...");
     }
 
+    if let Err(e) = fail_interrupted_import_jobs(db_connection).await {
+        tracing::error!("Failed to clean up interrupted import jobs: {}", e);
+    }
+    if let Err(e) = fail_interrupted_student_migration_jobs(db_connection).await {
+        tracing::error!("Failed to clean up interrupted student migrations: {}", e);
+    }
+
+    // Track RPC node health, send chain writes queued while a node was down,
+    // drop cached contract reads changed by other wallets and keep the stored
+    // student statuses in sync
+    spawn_health_monitor(db_connection);
+    spawn_chain_job_worker(db_connection);
+    spawn_cache_invalidator(db_connection);
+    spawn_student_status_sync(db_connection);
+
     let app = app::create_app().await?;
 
     let address = format!("0.0.0.0:{}", APP_CONFIG.port);
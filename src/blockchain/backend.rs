@@ -0,0 +1,230 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::service::{BlockchainService, StudentInfo};
+
+/// What became of a transaction that was possibly broadcast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    /// Mined and succeeded
+    Confirmed,
+    /// Mined and reverted
+    Reverted,
+    /// Known to the node but not mined yet
+    Pending,
+    /// Unknown to the node: never broadcast, or dropped
+    Unknown,
+}
+
+/// DataStorage operations used by the HTTP handlers
+///
+/// Implemented by [`BlockchainService`] (ethers, real RPC node) and by
+/// [`super::memory::InMemoryBackend`] (no node required).
+#[async_trait]
+pub trait ChainBackend: Send + Sync {
+    /// Address of the wallet this backend signs with
+    fn sender_address(&self) -> String;
+
+    /// Register a single student, returning the on-chain student ID
+    async fn register_student(
+        &self,
+        wallet_address: &str,
+        student_code: &str,
+        full_name: &str,
+        email: &str,
+    ) -> Result<u64>;
+
+    /// Register students in a single transaction, returning its hash
+    async fn register_students_batch(
+        &self,
+        wallet_addresses: Vec<String>,
+        student_codes: Vec<String>,
+        full_names: Vec<String>,
+        emails: Vec<String>,
+    ) -> Result<String>;
+
+    async fn assign_role(&self, user_address: &str, role: u8) -> Result<()>;
+
+    async fn add_manager(&self, manager_address: &str) -> Result<()>;
+
+    async fn remove_manager(&self, manager_address: &str) -> Result<()>;
+
+    async fn get_all_managers(&self) -> Result<Vec<String>>;
+
+    async fn get_manager_count(&self) -> Result<u64>;
+
+    async fn is_manager(&self, address: &str) -> Result<bool>;
+
+    async fn deactivate_student(&self, student_id: u64) -> Result<()>;
+
+    async fn activate_student(&self, student_id: u64) -> Result<()>;
+
+    async fn get_student_id_by_address(&self, address: &str) -> Result<u64>;
+
+    async fn get_student_id_by_code(&self, student_code: &str) -> Result<u64>;
+
+    async fn is_active_student(&self, address: &str) -> Result<bool>;
+
+    async fn get_user_role(&self, address: &str) -> Result<u8>;
+
+    async fn has_role(&self, address: &str, role: u8) -> Result<bool>;
+
+    async fn is_teacher_or_admin(&self, address: &str) -> Result<bool>;
+
+    async fn get_total_students(&self) -> Result<u64>;
+
+    /// Returns (owner, student count, manager count)
+    async fn get_contract_info(&self) -> Result<(String, u64, u64)>;
+
+    async fn get_student(&self, student_id: u64) -> Result<StudentInfo>;
+
+    /// Anchor a Merkle root of student records, returning the transaction hash
+    async fn anchor_merkle_root(&self, root: [u8; 32]) -> Result<String>;
+
+    /// Sender of the anchoring transaction `tx_hash` if it succeeded and carries
+    /// `root` for this backend's contract
+    async fn find_root_anchor(&self, tx_hash: &str, root: [u8; 32]) -> Result<Option<String>>;
+
+    /// What became of transaction `tx_hash` sent by this backend's wallet
+    async fn transaction_outcome(&self, tx_hash: &str) -> Result<TransactionOutcome>;
+
+    /// Get a page of students by sequential on-chain ID (IDs start at 1), given
+    /// the `total` from [`ChainBackend::get_total_students`]
+    async fn get_students(
+        &self,
+        start_id: u64,
+        count: u64,
+        total: u64,
+    ) -> Result<Vec<StudentInfo>> {
+        let end_id = std::cmp::min(start_id.saturating_add(count), total + 1);
+
+        let mut students = Vec::new();
+        for student_id in start_id..end_id {
+            students.push(self.get_student(student_id).await?);
+        }
+
+        Ok(students)
+    }
+}
+
+#[async_trait]
+impl ChainBackend for BlockchainService {
+    fn sender_address(&self) -> String {
+        self.signer_address()
+    }
+
+    async fn register_student(
+        &self,
+        wallet_address: &str,
+        student_code: &str,
+        full_name: &str,
+        email: &str,
+    ) -> Result<u64> {
+        let student_id = BlockchainService::register_student(
+            self,
+            wallet_address,
+            student_code,
+            full_name,
+            email,
+        )
+        .await?;
+        Ok(student_id.as_u64())
+    }
+
+    async fn register_students_batch(
+        &self,
+        wallet_addresses: Vec<String>,
+        student_codes: Vec<String>,
+        full_names: Vec<String>,
+        emails: Vec<String>,
+    ) -> Result<String> {
+        BlockchainService::register_students_batch(
+            self,
+            wallet_addresses,
+            student_codes,
+            full_names,
+            emails,
+        )
+        .await
+    }
+
+    async fn assign_role(&self, user_address: &str, role: u8) -> Result<()> {
+        BlockchainService::assign_role(self, user_address, role).await
+    }
+
+    async fn add_manager(&self, manager_address: &str) -> Result<()> {
+        BlockchainService::add_manager(self, manager_address).await
+    }
+
+    async fn remove_manager(&self, manager_address: &str) -> Result<()> {
+        BlockchainService::remove_manager(self, manager_address).await
+    }
+
+    async fn get_all_managers(&self) -> Result<Vec<String>> {
+        BlockchainService::get_all_managers(self).await
+    }
+
+    async fn get_manager_count(&self) -> Result<u64> {
+        BlockchainService::get_manager_count(self).await
+    }
+
+    async fn is_manager(&self, address: &str) -> Result<bool> {
+        BlockchainService::is_manager(self, address).await
+    }
+
+    async fn deactivate_student(&self, student_id: u64) -> Result<()> {
+        BlockchainService::deactivate_student(self, student_id).await
+    }
+
+    async fn activate_student(&self, student_id: u64) -> Result<()> {
+        BlockchainService::activate_student(self, student_id).await
+    }
+
+    async fn get_student_id_by_address(&self, address: &str) -> Result<u64> {
+        BlockchainService::get_student_id_by_address(self, address).await
+    }
+
+    async fn get_student_id_by_code(&self, student_code: &str) -> Result<u64> {
+        BlockchainService::get_student_id_by_code(self, student_code).await
+    }
+
+    async fn is_active_student(&self, address: &str) -> Result<bool> {
+        BlockchainService::is_active_student(self, address).await
+    }
+
+    async fn get_user_role(&self, address: &str) -> Result<u8> {
+        BlockchainService::get_user_role(self, address).await
+    }
+
+    async fn has_role(&self, address: &str, role: u8) -> Result<bool> {
+        BlockchainService::has_role(self, address, role).await
+    }
+
+    async fn is_teacher_or_admin(&self, address: &str) -> Result<bool> {
+        BlockchainService::is_teacher_or_admin(self, address).await
+    }
+
+    async fn get_total_students(&self) -> Result<u64> {
+        BlockchainService::get_total_students(self).await
+    }
+
+    async fn get_contract_info(&self) -> Result<(String, u64, u64)> {
+        BlockchainService::get_contract_info(self).await
+    }
+
+    async fn get_student(&self, student_id: u64) -> Result<StudentInfo> {
+        BlockchainService::get_student(self, student_id).await
+    }
+
+    async fn anchor_merkle_root(&self, root: [u8; 32]) -> Result<String> {
+        BlockchainService::anchor_merkle_root(self, root).await
+    }
+
+    async fn find_root_anchor(&self, tx_hash: &str, root: [u8; 32]) -> Result<Option<String>> {
+        BlockchainService::find_root_anchor(self, tx_hash, root).await
+    }
+
+    async fn transaction_outcome(&self, tx_hash: &str) -> Result<TransactionOutcome> {
+        BlockchainService::transaction_outcome(self, tx_hash).await
+    }
+}
@@ -0,0 +1,643 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::Address;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use super::backend::{ChainBackend, TransactionOutcome};
+use super::contract::{DataStorage, DataStorageEvents};
+use super::deployment::{ChainTarget, get_active_targets};
+use super::health::is_chain_available;
+use super::service::StudentInfo;
+use crate::config::{APP_CONFIG, BlockchainBackend};
+use sea_orm::DatabaseConnection;
+
+/// View calls whose result depends on contract-wide state rather than one address
+const SUMMARY_METHODS: [&str; 4] = [
+    "get_contract_info",
+    "get_total_students",
+    "get_all_managers",
+    "get_manager_count",
+];
+
+/// Largest block range replayed per poll; bigger gaps clear the deployment's cache
+const MAX_EVENT_BLOCK_RANGE: u64 = 2_000;
+
+/// (deployment scope, method, argument)
+type CacheKey = (String, &'static str, String);
+
+struct CacheEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    expires_at: Instant,
+}
+
+static VIEW_CACHE: Lazy<Mutex<HashMap<CacheKey, CacheEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static CACHE_STATS: Lazy<Mutex<BTreeMap<&'static str, MethodStats>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// Hit/miss counters of one cached view call
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+pub struct MethodStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+}
+
+/// Snapshot of the view call cache
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// hits / (hits + misses), 0 when nothing was looked up yet
+    pub hit_ratio: f64,
+    pub methods: BTreeMap<String, MethodStats>,
+}
+
+fn record_stats(method: &'static str, update: impl FnOnce(&mut MethodStats)) {
+    let mut stats = CACHE_STATS.lock().unwrap_or_else(|e| e.into_inner());
+    update(stats.entry(method).or_default());
+}
+
+pub fn cache_stats() -> CacheStats {
+    let entries = VIEW_CACHE.lock().unwrap_or_else(|e| e.into_inner()).len();
+    let methods = CACHE_STATS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    let hits: u64 = methods.values().map(|m| m.hits).sum();
+    let misses: u64 = methods.values().map(|m| m.misses).sum();
+
+    CacheStats {
+        entries,
+        hits,
+        misses,
+        hit_ratio: if hits + misses == 0 {
+            0.0
+        } else {
+            hits as f64 / (hits + misses) as f64
+        },
+        methods: methods
+            .into_iter()
+            .map(|(method, stats)| (method.to_string(), stats))
+            .collect(),
+    }
+}
+
+/// Drop every cached view result, returning how many were removed
+pub fn clear_cache() -> usize {
+    let mut cache = VIEW_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let removed = cache.len();
+    cache.clear();
+    removed
+}
+
+fn purge_expired() {
+    let now = Instant::now();
+    VIEW_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|_, entry| entry.expires_at > now);
+}
+
+/// Cached view results of one deployment's contract
+#[derive(Debug, Clone)]
+pub struct ViewCache {
+    scope: String,
+}
+
+impl ViewCache {
+    pub fn for_target(target: &ChainTarget) -> Self {
+        Self {
+            scope: format!(
+                "{}@{}",
+                target.contract_address.to_lowercase(),
+                target.rpc_url
+            ),
+        }
+    }
+
+    fn get<T: Clone + 'static>(&self, method: &'static str, arg: &str) -> Option<T> {
+        let key = (self.scope.clone(), method, arg.to_string());
+        let mut cache = VIEW_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+
+        match cache.get(&key) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                entry.value.downcast_ref::<T>().cloned()
+            }
+            Some(_) => {
+                cache.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put<T: Send + Sync + 'static>(
+        &self,
+        method: &'static str,
+        arg: &str,
+        value: T,
+        ttl: Duration,
+    ) {
+        VIEW_CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            (self.scope.clone(), method, arg.to_string()),
+            CacheEntry {
+                value: Arc::new(value),
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+
+    /// Return the cached result of `method(arg)`, or load and cache it
+    async fn get_or_load<T>(
+        &self,
+        method: &'static str,
+        arg: &str,
+        ttl: Duration,
+        load: impl Future<Output = Result<T>>,
+    ) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if ttl.is_zero() {
+            return load.await;
+        }
+
+        if let Some(value) = self.get::<T>(method, arg) {
+            record_stats(method, |stats| stats.hits += 1);
+            return Ok(value);
+        }
+        record_stats(method, |stats| stats.misses += 1);
+
+        let value = load.await?;
+        self.put(method, arg, value.clone(), ttl);
+        Ok(value)
+    }
+
+    fn invalidate_where(&self, matches: impl Fn(&'static str, &str) -> bool) {
+        let mut cache = VIEW_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        cache.retain(|(scope, method, arg), _| {
+            let remove = *scope == self.scope && matches(method, arg);
+            if remove {
+                record_stats(method, |stats| stats.invalidations += 1);
+            }
+            !remove
+        });
+    }
+
+    /// Forget every per-address result for `address` (role, manager, student lookups)
+    pub fn invalidate_address(&self, address: &str) {
+        let address = address.to_lowercase();
+        let role_prefix = format!("{}:", address);
+        self.invalidate_where(|_, arg| arg == address || arg.starts_with(&role_prefix));
+    }
+
+    pub fn invalidate_student_code(&self, student_code: &str) {
+        self.invalidate_where(|method, arg| {
+            method == "get_student_id_by_code" && arg == student_code
+        });
+    }
+
+    pub fn invalidate_student(&self, student_id: u64) {
+        let student_id = student_id.to_string();
+        self.invalidate_where(|method, arg| method == "get_student" && arg == student_id);
+    }
+
+    /// Forget the record of `student_id` and whether its wallet is active
+    ///
+    /// The wallet is found through cached lookups of the student; if none are
+    /// cached, every `is_active_student` result of the deployment is dropped.
+    pub fn invalidate_student_status(&self, student_id: u64) {
+        let mut addresses: Vec<String> = Vec::new();
+        if let Some(student) = self.get::<StudentInfo>("get_student", &student_id.to_string()) {
+            addresses.push(student.wallet_address.to_lowercase());
+        }
+        {
+            let cache = VIEW_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+            for ((scope, method, arg), entry) in cache.iter() {
+                if *scope == self.scope
+                    && *method == "get_student_id_by_address"
+                    && entry.value.downcast_ref::<u64>() == Some(&student_id)
+                {
+                    addresses.push(arg.clone());
+                }
+            }
+        }
+
+        self.invalidate_student(student_id);
+        if addresses.is_empty() {
+            self.invalidate_method("is_active_student");
+        } else {
+            self.invalidate_where(|method, arg| {
+                method == "is_active_student" && addresses.iter().any(|a| a == arg)
+            });
+        }
+    }
+
+    pub fn invalidate_method(&self, method: &'static str) {
+        self.invalidate_where(|m, _| m == method);
+    }
+
+    /// Forget contract-wide results (owner, counts, manager list)
+    pub fn invalidate_summary(&self) {
+        self.invalidate_where(|method, _| SUMMARY_METHODS.contains(&method));
+    }
+
+    pub fn invalidate_all(&self) {
+        self.invalidate_where(|_, _| true);
+    }
+
+    /// Apply a contract event emitted by any wallet, not only this service's
+    ///
+    /// DataStorage emits no event when a student is activated, so an activation
+    /// sent by another wallet shows up only once the student's cached results
+    /// expire, after at most `CHAIN_CACHE_TTL_SECS`. Activations sent through
+    /// this service invalidate them right away.
+    pub fn apply_event(&self, event: &DataStorageEvents) {
+        match event {
+            DataStorageEvents::OwnerChangedFilter(e) => {
+                self.invalidate_address(&format!("{:?}", e.old_owner));
+                self.invalidate_address(&format!("{:?}", e.new_owner));
+                self.invalidate_summary();
+            }
+            DataStorageEvents::ManagerAddedFilter(e) => {
+                self.invalidate_address(&format!("{:?}", e.manager));
+                self.invalidate_summary();
+            }
+            DataStorageEvents::ManagerRemovedFilter(e) => {
+                self.invalidate_address(&format!("{:?}", e.manager));
+                self.invalidate_summary();
+            }
+            DataStorageEvents::StudentRegisteredFilter(e) => {
+                self.invalidate_address(&format!("{:?}", e.student_address));
+                self.invalidate_student_code(&e.student_code);
+                self.invalidate_student(e.student_id.as_u64());
+                self.invalidate_summary();
+            }
+            DataStorageEvents::StudentDeactivatedFilter(e) => {
+                self.invalidate_student_status(e.student_id.as_u64());
+            }
+            DataStorageEvents::RoleAssignedFilter(e) => {
+                self.invalidate_address(&format!("{:?}", e.user));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Ethers backend with a read-through cache for view calls
+///
+/// Writes go straight to `inner` and invalidate the entries they affect; changes
+/// made by other wallets are picked up from contract events (see
+/// [`spawn_cache_invalidator`]) or when the TTL expires.
+pub struct CachedBackend {
+    inner: Arc<dyn ChainBackend>,
+    cache: ViewCache,
+}
+
+impl CachedBackend {
+    pub fn new(inner: Arc<dyn ChainBackend>, target: &ChainTarget) -> Self {
+        Self {
+            inner,
+            cache: ViewCache::for_target(target),
+        }
+    }
+
+    fn address_ttl() -> Duration {
+        Duration::from_secs(APP_CONFIG.chain_cache_ttl_secs)
+    }
+
+    fn summary_ttl() -> Duration {
+        Duration::from_secs(APP_CONFIG.chain_cache_summary_ttl_secs)
+    }
+}
+
+#[async_trait]
+impl ChainBackend for CachedBackend {
+    fn sender_address(&self) -> String {
+        self.inner.sender_address()
+    }
+
+    async fn register_student(
+        &self,
+        wallet_address: &str,
+        student_code: &str,
+        full_name: &str,
+        email: &str,
+    ) -> Result<u64> {
+        let student_id = self
+            .inner
+            .register_student(wallet_address, student_code, full_name, email)
+            .await?;
+
+        self.cache.invalidate_address(wallet_address);
+        self.cache.invalidate_student_code(student_code);
+        self.cache.invalidate_summary();
+        Ok(student_id)
+    }
+
+    async fn register_students_batch(
+        &self,
+        wallet_addresses: Vec<String>,
+        student_codes: Vec<String>,
+        full_names: Vec<String>,
+        emails: Vec<String>,
+    ) -> Result<String> {
+        let tx_hash = self
+            .inner
+            .register_students_batch(
+                wallet_addresses.clone(),
+                student_codes.clone(),
+                full_names,
+                emails,
+            )
+            .await?;
+
+        for wallet_address in &wallet_addresses {
+            self.cache.invalidate_address(wallet_address);
+        }
+        for student_code in &student_codes {
+            self.cache.invalidate_student_code(student_code);
+        }
+        self.cache.invalidate_summary();
+        Ok(tx_hash)
+    }
+
+    async fn assign_role(&self, user_address: &str, role: u8) -> Result<()> {
+        self.inner.assign_role(user_address, role).await?;
+        self.cache.invalidate_address(user_address);
+        Ok(())
+    }
+
+    async fn add_manager(&self, manager_address: &str) -> Result<()> {
+        self.inner.add_manager(manager_address).await?;
+        self.cache.invalidate_address(manager_address);
+        self.cache.invalidate_summary();
+        Ok(())
+    }
+
+    async fn remove_manager(&self, manager_address: &str) -> Result<()> {
+        self.inner.remove_manager(manager_address).await?;
+        self.cache.invalidate_address(manager_address);
+        self.cache.invalidate_summary();
+        Ok(())
+    }
+
+    async fn get_all_managers(&self) -> Result<Vec<String>> {
+        self.cache
+            .get_or_load(
+                "get_all_managers",
+                "",
+                Self::summary_ttl(),
+                self.inner.get_all_managers(),
+            )
+            .await
+    }
+
+    async fn get_manager_count(&self) -> Result<u64> {
+        self.cache
+            .get_or_load(
+                "get_manager_count",
+                "",
+                Self::summary_ttl(),
+                self.inner.get_manager_count(),
+            )
+            .await
+    }
+
+    async fn is_manager(&self, address: &str) -> Result<bool> {
+        self.cache
+            .get_or_load(
+                "is_manager",
+                &address.to_lowercase(),
+                Self::address_ttl(),
+                self.inner.is_manager(address),
+            )
+            .await
+    }
+
+    async fn deactivate_student(&self, student_id: u64) -> Result<()> {
+        self.inner.deactivate_student(student_id).await?;
+        self.cache.invalidate_student_status(student_id);
+        Ok(())
+    }
+
+    async fn activate_student(&self, student_id: u64) -> Result<()> {
+        self.inner.activate_student(student_id).await?;
+        self.cache.invalidate_student_status(student_id);
+        Ok(())
+    }
+
+    async fn get_student_id_by_address(&self, address: &str) -> Result<u64> {
+        self.cache
+            .get_or_load(
+                "get_student_id_by_address",
+                &address.to_lowercase(),
+                Self::address_ttl(),
+                self.inner.get_student_id_by_address(address),
+            )
+            .await
+    }
+
+    async fn get_student_id_by_code(&self, student_code: &str) -> Result<u64> {
+        self.cache
+            .get_or_load(
+                "get_student_id_by_code",
+                student_code,
+                Self::address_ttl(),
+                self.inner.get_student_id_by_code(student_code),
+            )
+            .await
+    }
+
+    async fn is_active_student(&self, address: &str) -> Result<bool> {
+        self.cache
+            .get_or_load(
+                "is_active_student",
+                &address.to_lowercase(),
+                Self::address_ttl(),
+                self.inner.is_active_student(address),
+            )
+            .await
+    }
+
+    async fn get_user_role(&self, address: &str) -> Result<u8> {
+        self.cache
+            .get_or_load(
+                "get_user_role",
+                &address.to_lowercase(),
+                Self::address_ttl(),
+                self.inner.get_user_role(address),
+            )
+            .await
+    }
+
+    async fn has_role(&self, address: &str, role: u8) -> Result<bool> {
+        self.cache
+            .get_or_load(
+                "has_role",
+                &format!("{}:{}", address.to_lowercase(), role),
+                Self::address_ttl(),
+                self.inner.has_role(address, role),
+            )
+            .await
+    }
+
+    async fn is_teacher_or_admin(&self, address: &str) -> Result<bool> {
+        self.cache
+            .get_or_load(
+                "is_teacher_or_admin",
+                &address.to_lowercase(),
+                Self::address_ttl(),
+                self.inner.is_teacher_or_admin(address),
+            )
+            .await
+    }
+
+    async fn get_total_students(&self) -> Result<u64> {
+        self.cache
+            .get_or_load(
+                "get_total_students",
+                "",
+                Self::summary_ttl(),
+                self.inner.get_total_students(),
+            )
+            .await
+    }
+
+    async fn get_contract_info(&self) -> Result<(String, u64, u64)> {
+        self.cache
+            .get_or_load(
+                "get_contract_info",
+                "",
+                Self::summary_ttl(),
+                self.inner.get_contract_info(),
+            )
+            .await
+    }
+
+    async fn get_student(&self, student_id: u64) -> Result<StudentInfo> {
+        self.cache
+            .get_or_load(
+                "get_student",
+                &student_id.to_string(),
+                Self::address_ttl(),
+                self.inner.get_student(student_id),
+            )
+            .await
+    }
+
+    async fn anchor_merkle_root(&self, root: [u8; 32]) -> Result<String> {
+        self.inner.anchor_merkle_root(root).await
+    }
+
+    async fn find_root_anchor(&self, tx_hash: &str, root: [u8; 32]) -> Result<Option<String>> {
+        self.inner.find_root_anchor(tx_hash, root).await
+    }
+
+    async fn transaction_outcome(&self, tx_hash: &str) -> Result<TransactionOutcome> {
+        self.inner.transaction_outcome(tx_hash).await
+    }
+}
+
+/// Replay contract events since `last_block` into the deployment's cache,
+/// returning the new high-water mark
+async fn invalidate_from_events(target: &ChainTarget, last_block: Option<u64>) -> Result<u64> {
+    let provider =
+        Provider::<Http>::try_from(target.rpc_url.as_str()).context("Failed to create provider")?;
+    let latest = provider
+        .get_block_number()
+        .await
+        .context("Failed to get block number")?
+        .as_u64();
+
+    let cache = ViewCache::for_target(target);
+    let Some(last_block) = last_block else {
+        // Nothing was cached before we started watching
+        return Ok(latest);
+    };
+    if latest <= last_block {
+        return Ok(last_block);
+    }
+    if latest - last_block > MAX_EVENT_BLOCK_RANGE {
+        cache.invalidate_all();
+        return Ok(latest);
+    }
+
+    let contract_address: Address = target
+        .contract_address
+        .parse()
+        .context("Failed to parse contract address")?;
+    let contract = DataStorage::new(contract_address, Arc::new(provider));
+    let events = contract
+        .events()
+        .from_block(last_block + 1)
+        .to_block(latest)
+        .query()
+        .await
+        .context("Failed to query DataStorage events")?;
+
+    for event in &events {
+        cache.apply_event(event);
+    }
+
+    Ok(latest)
+}
+
+/// Watch contract events of every active deployment and invalidate the view cache
+/// for changes made outside this service (no-op for the in-memory backend)
+pub fn spawn_cache_invalidator(db: &'static DatabaseConnection) {
+    if APP_CONFIG.blockchain_backend == BlockchainBackend::Memory
+        || (APP_CONFIG.chain_cache_ttl_secs == 0 && APP_CONFIG.chain_cache_summary_ttl_secs == 0)
+    {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut last_blocks: HashMap<String, u64> = HashMap::new();
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            APP_CONFIG.chain_event_poll_interval_secs,
+        ));
+        loop {
+            interval.tick().await;
+            purge_expired();
+
+            let targets = match get_active_targets(db).await {
+                Ok(targets) => targets,
+                Err(e) => {
+                    tracing::error!("Failed to load chain deployments: {}", e);
+                    continue;
+                }
+            };
+
+            for target in targets.iter().filter(|t| is_chain_available(t)) {
+                let scope = ViewCache::for_target(target).scope;
+                match invalidate_from_events(target, last_blocks.get(&scope).copied()).await {
+                    Ok(block) => {
+                        last_blocks.insert(scope, block);
+                    }
+                    Err(e) => {
+                        // Events may have been missed; start over from the next head
+                        ViewCache::for_target(target).invalidate_all();
+                        last_blocks.remove(&scope);
+                        tracing::warn!(
+                            "Failed to read events for deployment '{}': {}",
+                            target.name,
+                            e
+                        );
+                    }
+                }
+            }
+        }
+    });
+}
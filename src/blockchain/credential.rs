@@ -0,0 +1,197 @@
+use anyhow::{Context, Result};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712DomainType, TypedData, Types};
+use ethers::types::{Address, Signature};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+use super::deployment::resolve_wallet_deployment;
+use super::error::is_chain_unavailable;
+use super::helpers::get_admin_chain_backend;
+use super::merkle::is_enrolled_student;
+use crate::config::APP_CONFIG;
+
+/// EIP-712 primary type of student credentials
+pub const CREDENTIAL_TYPE: &str = "StudentCredential";
+
+/// Bump when the credential fields change; older signatures stop verifying
+const CREDENTIAL_VERSION: &str = "1";
+
+/// Student identity attestation signed by the institution key (EIP-712)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentCredential {
+    pub credential_id: String,
+    /// Student wallet the credential is bound to
+    pub wallet: String,
+    pub student_code: String,
+    pub full_name: String,
+    pub majors: Vec<String>,
+    /// `active` or `inactive` on-chain when issued
+    pub status: String,
+    /// Unix timestamp (seconds)
+    pub issued_at: u64,
+    /// Unix timestamp (seconds)
+    pub expires_at: u64,
+}
+
+fn field(name: &str, r#type: &str) -> Eip712DomainType {
+    Eip712DomainType {
+        name: name.to_string(),
+        r#type: r#type.to_string(),
+    }
+}
+
+fn credential_types() -> Types {
+    BTreeMap::from([
+        (
+            "EIP712Domain".to_string(),
+            vec![field("name", "string"), field("version", "string")],
+        ),
+        (
+            CREDENTIAL_TYPE.to_string(),
+            vec![
+                field("credentialId", "string"),
+                field("wallet", "address"),
+                field("studentCode", "string"),
+                field("fullName", "string"),
+                field("majors", "string[]"),
+                field("status", "string"),
+                field("issuedAt", "uint256"),
+                field("expiresAt", "uint256"),
+            ],
+        ),
+    ])
+}
+
+fn credential_domain() -> EIP712Domain {
+    EIP712Domain {
+        name: Some(APP_CONFIG.credential_domain_name.clone()),
+        version: Some(CREDENTIAL_VERSION.to_string()),
+        ..Default::default()
+    }
+}
+
+impl StudentCredential {
+    /// EIP-712 payload wallets and verifiers hash and sign
+    pub fn typed_data(&self) -> Result<TypedData> {
+        let message = match serde_json::to_value(self).context("Failed to encode credential")? {
+            serde_json::Value::Object(fields) => fields.into_iter().collect(),
+            _ => unreachable!("credentials serialize to JSON objects"),
+        };
+
+        Ok(TypedData {
+            domain: credential_domain(),
+            types: credential_types(),
+            primary_type: CREDENTIAL_TYPE.to_string(),
+            message,
+        })
+    }
+
+    /// Read a credential back from its EIP-712 payload, rejecting payloads whose
+    /// domain or type definition differ from the ones this service signs
+    pub fn from_typed_data(typed_data: &TypedData) -> Result<Self> {
+        anyhow::ensure!(
+            typed_data.primary_type == CREDENTIAL_TYPE,
+            "Unexpected primary type '{}'",
+            typed_data.primary_type
+        );
+        anyhow::ensure!(
+            typed_data.domain == credential_domain(),
+            "Credential was not issued for this domain"
+        );
+        anyhow::ensure!(
+            typed_data.types == credential_types(),
+            "Unexpected credential type definition"
+        );
+
+        let credential: Self = serde_json::from_value(serde_json::Value::Object(
+            typed_data.message.clone().into_iter().collect(),
+        ))
+        .context("Invalid credential message")?;
+        credential
+            .wallet
+            .parse::<Address>()
+            .context("Invalid credential wallet")?;
+
+        Ok(credential)
+    }
+}
+
+/// Load the signing key configured as `name`
+///
+/// Signing keys must be configured separately from ADMIN_PRIVATE_KEY, so a
+/// leaked signing key cannot send contract transactions.
+pub(super) fn signing_wallet(signing_key: Option<&str>, name: &str) -> Result<LocalWallet> {
+    let signing_key = signing_key.with_context(|| format!("{} is not set", name))?;
+    anyhow::ensure!(
+        signing_key.trim_start_matches("0x")
+            != APP_CONFIG.admin_private_key.trim_start_matches("0x"),
+        "{} must differ from ADMIN_PRIVATE_KEY",
+        name
+    );
+
+    signing_key
+        .parse()
+        .with_context(|| format!("Failed to parse {}", name))
+}
+
+/// Institution key credentials are signed with
+fn credential_signer() -> Result<LocalWallet> {
+    signing_wallet(
+        APP_CONFIG.credential_signing_key.as_deref(),
+        "CREDENTIAL_SIGNING_KEY",
+    )
+}
+
+/// Address of the institution key, published so verifiers can pin it
+pub fn credential_issuer() -> Result<String> {
+    Ok(format!("{:?}", credential_signer()?.address()))
+}
+
+/// Sign a credential, returning its EIP-712 payload and the 0x-prefixed signature
+pub async fn sign_credential(credential: &StudentCredential) -> Result<(TypedData, String)> {
+    let typed_data = credential.typed_data()?;
+    let signature = credential_signer()?
+        .sign_typed_data(&typed_data)
+        .await
+        .context("Failed to sign credential")?;
+
+    Ok((typed_data, format!("0x{}", signature)))
+}
+
+/// Address that produced `signature` over the EIP-712 payload
+pub fn recover_credential_signer(typed_data: &TypedData, signature: &str) -> Result<String> {
+    let signature = Signature::from_str(signature.trim_start_matches("0x"))
+        .context("Failed to parse signature")?;
+    let signer = signature
+        .recover_typed_data(typed_data)
+        .context("Failed to recover credential signer")?;
+
+    Ok(format!("{:?}", signer))
+}
+
+/// Whether `wallet_address` is still an enrolled student on its deployment, or
+/// `None` while the node is unavailable
+///
+/// Verifiers fail closed on `None`: there is no off-chain record of activation.
+pub async fn enrollment_status(
+    db: &DatabaseConnection,
+    wallet_address: &str,
+) -> Result<Option<bool>> {
+    let result = async {
+        let target = resolve_wallet_deployment(db, wallet_address).await?;
+        let backend = get_admin_chain_backend(db, &target).await?;
+        is_enrolled_student(db, backend.as_ref(), wallet_address).await
+    }
+    .await;
+
+    match result {
+        Ok(enrolled) => Ok(Some(enrolled)),
+        Err(e) if is_chain_unavailable(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
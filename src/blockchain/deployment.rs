@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use uuid::Uuid;
+
+use super::backend::ChainBackend;
+use super::service::StudentInfo;
+use crate::config::APP_CONFIG;
+use crate::entities::{chain_deployment, department, major, user_major, wallet};
+
+/// Name of the deployment configured through `BLOCKCHAIN_RPC_URL` /
+/// `DATA_STORAGE_CONTRACT_ADDRESS`, used when the registry has no default
+pub const CONFIG_DEPLOYMENT_NAME: &str = "config";
+
+/// Network and DataStorage contract a backend talks to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainTarget {
+    /// Registry row, `None` for the deployment from config
+    pub deployment_id: Option<Uuid>,
+    pub name: String,
+    /// Expected chain ID, checked against the node on connect
+    pub chain_id: Option<u64>,
+    pub rpc_url: String,
+    pub contract_address: String,
+}
+
+impl ChainTarget {
+    pub fn from_config() -> Self {
+        Self {
+            deployment_id: None,
+            name: CONFIG_DEPLOYMENT_NAME.to_string(),
+            chain_id: None,
+            rpc_url: APP_CONFIG.blockchain_rpc_url.clone(),
+            contract_address: APP_CONFIG.data_storage_contract_address.clone(),
+        }
+    }
+}
+
+impl From<chain_deployment::Model> for ChainTarget {
+    fn from(deployment: chain_deployment::Model) -> Self {
+        Self {
+            deployment_id: Some(deployment.deployment_id),
+            name: deployment.name,
+            chain_id: Some(deployment.chain_id as u64),
+            rpc_url: deployment.rpc_url,
+            contract_address: deployment.contract_address,
+        }
+    }
+}
+
+/// Get a registered deployment by ID
+pub async fn get_deployment(db: &DatabaseConnection, deployment_id: &Uuid) -> Result<ChainTarget> {
+    let deployment = chain_deployment::Entity::find_by_id(*deployment_id)
+        .one(db)
+        .await
+        .context("Failed to query chain deployment")?
+        .ok_or_else(|| anyhow::anyhow!("Chain deployment not found"))?;
+
+    Ok(deployment.into())
+}
+
+/// Get the default deployment, falling back to the one from config
+pub async fn get_default_deployment(db: &DatabaseConnection) -> Result<ChainTarget> {
+    let deployment = chain_deployment::Entity::find()
+        .filter(chain_deployment::Column::IsDefault.eq(true))
+        .filter(chain_deployment::Column::IsActive.eq(true))
+        .one(db)
+        .await
+        .context("Failed to query default chain deployment")?;
+
+    Ok(deployment
+        .map(ChainTarget::from)
+        .unwrap_or_else(ChainTarget::from_config))
+}
+
+/// The deployment from config followed by every active registered deployment
+pub async fn get_active_targets(db: &DatabaseConnection) -> Result<Vec<ChainTarget>> {
+    let deployments = chain_deployment::Entity::find()
+        .filter(chain_deployment::Column::IsActive.eq(true))
+        .order_by_asc(chain_deployment::Column::Name)
+        .all(db)
+        .await
+        .context("Failed to query chain deployments")?;
+
+    Ok(std::iter::once(ChainTarget::from_config())
+        .chain(deployments.into_iter().map(ChainTarget::from))
+        .collect())
+}
+
+/// Get the deployment assigned to the departments of the given majors, if any
+pub async fn get_department_deployment(
+    db: &DatabaseConnection,
+    major_ids: &[Uuid],
+) -> Result<Option<ChainTarget>> {
+    if major_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let department_ids: Vec<Uuid> = major::Entity::find()
+        .filter(major::Column::MajorId.is_in(major_ids.to_vec()))
+        .all(db)
+        .await
+        .context("Failed to query majors")?
+        .into_iter()
+        .filter_map(|m| m.department_id)
+        .collect();
+
+    if department_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let deployment = chain_deployment::Entity::find()
+        .inner_join(department::Entity)
+        .filter(department::Column::DepartmentId.is_in(department_ids))
+        .filter(chain_deployment::Column::IsActive.eq(true))
+        .order_by_asc(chain_deployment::Column::Name)
+        .one(db)
+        .await
+        .context("Failed to query department chain deployment")?;
+
+    Ok(deployment.map(ChainTarget::from))
+}
+
+/// Resolve the deployment for new users in the given majors:
+/// department deployment, then the default
+pub async fn resolve_deployment_for_majors(
+    db: &DatabaseConnection,
+    major_ids: &[Uuid],
+) -> Result<ChainTarget> {
+    match get_department_deployment(db, major_ids).await? {
+        Some(target) => Ok(target),
+        None => get_default_deployment(db).await,
+    }
+}
+
+/// Resolve the deployment for an existing user:
+/// wallet override, then department deployment, then the default
+pub async fn resolve_user_deployment(
+    db: &DatabaseConnection,
+    user_id: &Uuid,
+) -> Result<ChainTarget> {
+    let wallet_info = wallet::Entity::find()
+        .filter(wallet::Column::UserId.eq(*user_id))
+        .one(db)
+        .await
+        .context("Failed to query wallet")?;
+
+    if let Some(deployment_id) = wallet_info.and_then(|w| w.chain_deployment_id) {
+        let deployment = chain_deployment::Entity::find_by_id(deployment_id)
+            .filter(chain_deployment::Column::IsActive.eq(true))
+            .one(db)
+            .await
+            .context("Failed to query wallet chain deployment")?;
+
+        if let Some(deployment) = deployment {
+            return Ok(deployment.into());
+        }
+    }
+
+    let major_ids: Vec<Uuid> = user_major::Entity::find()
+        .filter(user_major::Column::UserId.eq(*user_id))
+        .all(db)
+        .await
+        .context("Failed to query user majors")?
+        .into_iter()
+        .map(|um| um.major_id)
+        .collect();
+
+    resolve_deployment_for_majors(db, &major_ids).await
+}
+
+/// Resolve the deployment a wallet address is registered on: the owning user's
+/// deployment if the wallet is managed by this service, else the default one
+pub async fn resolve_wallet_deployment(
+    db: &DatabaseConnection,
+    address: &str,
+) -> Result<ChainTarget> {
+    let wallet_info = wallet::Entity::find()
+        .filter(wallet::Column::Address.eq(address.to_lowercase()))
+        .one(db)
+        .await
+        .context("Failed to query wallet")?;
+
+    match wallet_info {
+        Some(wallet_info) => resolve_user_deployment(db, &wallet_info.user_id).await,
+        None => get_default_deployment(db).await,
+    }
+}
+
+/// Outcome of copying student registrations between two deployments
+#[derive(Debug, Clone, Default)]
+pub struct StudentMigrationReport {
+    pub total: u64,
+    pub migrated: u64,
+    /// Already registered on the target deployment
+    pub skipped: u64,
+    /// Deactivated on the target deployment to match the source deployment
+    pub deactivated: u64,
+    pub failed: Vec<StudentMigrationFailure>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StudentMigrationFailure {
+    pub student_code: String,
+    pub error: String,
+}
+
+/// Copy every student registration from `from` to `to`, preserving the active flag.
+/// Students already registered on `to` are skipped, so a failed run can be repeated;
+/// those still active there while inactive on `from` are deactivated again.
+pub async fn migrate_students(
+    from: &dyn ChainBackend,
+    to: &dyn ChainBackend,
+) -> Result<StudentMigrationReport> {
+    const BATCH_SIZE: u64 = 50;
+
+    let mut report = StudentMigrationReport {
+        total: from.get_total_students().await?,
+        ..Default::default()
+    };
+
+    let mut pending: Vec<StudentInfo> = Vec::new();
+    // Migrated by an earlier run that failed to deactivate them
+    let mut still_active: Vec<StudentInfo> = Vec::new();
+    let mut start_id = 1;
+    while start_id <= report.total {
+        for student in from
+            .get_students(start_id, BATCH_SIZE, report.total)
+            .await?
+        {
+            if to
+                .get_student_id_by_address(&student.wallet_address)
+                .await?
+                == 0
+            {
+                pending.push(student);
+                continue;
+            }
+
+            report.skipped += 1;
+            if !student.is_active && to.is_active_student(&student.wallet_address).await? {
+                still_active.push(student);
+            }
+        }
+        start_id += BATCH_SIZE;
+    }
+
+    for student in &still_active {
+        match deactivate_migrated(to, student).await {
+            Ok(()) => report.deactivated += 1,
+            Err(e) => report.failed.push(StudentMigrationFailure {
+                student_code: student.student_code.clone(),
+                error: format!("Failed to deactivate: {}", e),
+            }),
+        }
+    }
+
+    for batch in pending.chunks(BATCH_SIZE as usize) {
+        let result = to
+            .register_students_batch(
+                batch.iter().map(|s| s.wallet_address.clone()).collect(),
+                batch.iter().map(|s| s.student_code.clone()).collect(),
+                batch.iter().map(|s| s.full_name.clone()).collect(),
+                batch.iter().map(|s| s.email.clone()).collect(),
+            )
+            .await;
+
+        if let Err(e) = result {
+            report
+                .failed
+                .extend(batch.iter().map(|s| StudentMigrationFailure {
+                    student_code: s.student_code.clone(),
+                    error: e.to_string(),
+                }));
+            continue;
+        }
+        report.migrated += batch.len() as u64;
+
+        for student in batch.iter().filter(|s| !s.is_active) {
+            match deactivate_migrated(to, student).await {
+                Ok(()) => report.deactivated += 1,
+                Err(e) => report.failed.push(StudentMigrationFailure {
+                    student_code: student.student_code.clone(),
+                    error: format!("Registered but failed to deactivate: {}", e),
+                }),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Deactivate a migrated student on the target deployment
+async fn deactivate_migrated(to: &dyn ChainBackend, student: &StudentInfo) -> Result<()> {
+    let student_id = to
+        .get_student_id_by_address(&student.wallet_address)
+        .await?;
+    to.deactivate_student(student_id).await
+}
@@ -0,0 +1,213 @@
+use axum::http::StatusCode;
+use ethers::types::{TxHash, U256};
+use ethers::utils::format_units;
+use std::fmt;
+
+/// Revert message of DataStorage's `onlyOwner` modifier
+pub const ONLY_OWNER_REASON: &str = "Only owner can call this function";
+/// Revert message of DataStorage's `onlyManager` modifier
+pub const ONLY_MANAGER_REASON: &str = "Only manager can call this function";
+
+/// Known revert categories of the DataStorage contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertKind {
+    NotOwner,
+    NotManager,
+    AlreadyRegistered,
+    StudentNotFound,
+    Other,
+}
+
+impl RevertKind {
+    /// Classify a decoded `require` message
+    ///
+    /// The access modifiers are matched exactly: other messages mention owners
+    /// and managers too (e.g. "Invalid owner address" is a bad argument).
+    pub fn from_reason(reason: &str) -> Self {
+        match reason {
+            ONLY_OWNER_REASON => return RevertKind::NotOwner,
+            ONLY_MANAGER_REASON => return RevertKind::NotManager,
+            _ => {}
+        }
+
+        let reason = reason.to_lowercase();
+        if reason.contains("already registered") || reason.contains("already exists") {
+            RevertKind::AlreadyRegistered
+        } else if reason.contains("not found")
+            || reason.contains("does not exist")
+            || reason.contains("invalid student")
+        {
+            RevertKind::StudentNotFound
+        } else {
+            RevertKind::Other
+        }
+    }
+}
+
+/// A contract write rejected by the pre-flight `eth_call` simulation, or mined
+/// but reverted
+#[derive(Debug, Clone)]
+pub struct ContractRevertError {
+    pub method: String,
+    pub reason: String,
+    pub kind: RevertKind,
+}
+
+impl ContractRevertError {
+    pub fn new(method: &str, reason: String) -> Self {
+        Self {
+            method: method.to_string(),
+            kind: RevertKind::from_reason(&reason),
+            reason,
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self.kind {
+            RevertKind::NotOwner | RevertKind::NotManager => StatusCode::FORBIDDEN,
+            RevertKind::AlreadyRegistered => StatusCode::CONFLICT,
+            RevertKind::StudentNotFound => StatusCode::NOT_FOUND,
+            RevertKind::Other => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl fmt::Display for ContractRevertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} reverted: {}", self.method, self.reason)
+    }
+}
+
+impl std::error::Error for ContractRevertError {}
+
+/// Which fee limit a transaction would exceed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeeCapKind {
+    MaxFeePerGas,
+    PerTransaction,
+    Daily,
+}
+
+/// A transaction refused because it would exceed a configured fee cap
+#[derive(Debug, Clone)]
+pub struct FeeCapError {
+    pub kind: FeeCapKind,
+    pub required: U256,
+    pub cap: U256,
+}
+
+impl fmt::Display for FeeCapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (limit, unit) = match self.kind {
+            FeeCapKind::MaxFeePerGas => ("max fee per gas", "gwei"),
+            FeeCapKind::PerTransaction => ("per-transaction fee cap", "ether"),
+            FeeCapKind::Daily => ("daily fee cap", "ether"),
+        };
+        let amount = |value: U256| format_units(value, unit).unwrap_or_else(|_| value.to_string());
+
+        write!(
+            f,
+            "transaction exceeds {} ({} > {} {})",
+            limit,
+            amount(self.required),
+            amount(self.cap),
+            unit
+        )
+    }
+}
+
+impl std::error::Error for FeeCapError {}
+
+/// A transaction that was broadcast but not confirmed within the configured timeout
+#[derive(Debug, Clone)]
+pub struct TransactionStuckError {
+    pub tx_hash: TxHash,
+}
+
+impl fmt::Display for TransactionStuckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transaction {:?} is still pending; speed it up or cancel it",
+            self.tx_hash
+        )
+    }
+}
+
+impl std::error::Error for TransactionStuckError {}
+
+/// The node dropped out while a transaction was being broadcast or confirmed, so
+/// it may or may not have been sent; check `tx_hash` before sending it again
+#[derive(Debug, Clone)]
+pub struct TransactionUnconfirmedError {
+    pub tx_hash: TxHash,
+    pub reason: String,
+}
+
+impl fmt::Display for TransactionUnconfirmedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "outcome of transaction {:?} is unknown: {}",
+            self.tx_hash, self.reason
+        )
+    }
+}
+
+impl std::error::Error for TransactionUnconfirmedError {}
+
+/// The RPC node of a deployment is unreachable, or its circuit breaker is open
+#[derive(Debug, Clone)]
+pub struct ChainUnavailableError {
+    pub rpc_url: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ChainUnavailableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "blockchain node {} is unavailable: {}",
+            self.rpc_url, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ChainUnavailableError {}
+
+/// Whether the error means the node could not be reached (as opposed to the
+/// contract rejecting the call), so the operation can be retried later
+pub fn is_chain_unavailable(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<ChainUnavailableError>().is_some()
+}
+
+/// Hash of the transaction an error leaves unsettled: broadcast but not mined in
+/// time, or possibly broadcast before the node dropped out. Sending the write again
+/// before that transaction is settled could land it twice.
+pub fn unsettled_tx_hash(e: &anyhow::Error) -> Option<String> {
+    let tx_hash = match e.downcast_ref::<TransactionStuckError>() {
+        Some(stuck) => stuck.tx_hash,
+        None => e.downcast_ref::<TransactionUnconfirmedError>()?.tx_hash,
+    };
+    Some(format!("{:?}", tx_hash))
+}
+
+/// Map a blockchain error to an HTTP error, using the revert kind when the
+/// pre-flight simulation rejected the transaction
+pub fn map_blockchain_error(context: &str, e: anyhow::Error) -> (StatusCode, String) {
+    let status = if let Some(revert) = e.downcast_ref::<ContractRevertError>() {
+        revert.status_code()
+    } else if e.downcast_ref::<FeeCapError>().is_some() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else if e.downcast_ref::<TransactionStuckError>().is_some()
+        || e.downcast_ref::<TransactionUnconfirmedError>().is_some()
+    {
+        StatusCode::GATEWAY_TIMEOUT
+    } else if is_chain_unavailable(&e) {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    (status, format!("{}: {}", context, e))
+}
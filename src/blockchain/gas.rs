@@ -0,0 +1,220 @@
+use crate::blockchain::error::{FeeCapError, FeeCapKind};
+use crate::config::APP_CONFIG;
+use crate::static_service::get_database_connection;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use ethers::types::{Address, U256};
+use ethers::utils::{parse_ether, parse_units};
+use sea_orm::{ConnectionTrait, DbBackend, QueryResult, Statement};
+use std::time::Duration;
+
+/// EIP-1559 fee limits loaded from config
+#[derive(Debug, Clone)]
+pub struct GasPolicy {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_tx_fee: U256,
+    pub max_daily_fee: U256,
+    pub gas_limit_margin_percent: u64,
+    pub replacement_bump_percent: u64,
+    pub confirmation_timeout: Duration,
+}
+
+impl GasPolicy {
+    pub fn from_config() -> Result<Self> {
+        let gwei = |value: u64| -> Result<U256> {
+            Ok(parse_units(value, "gwei")
+                .context("Failed to parse gwei amount")?
+                .into())
+        };
+
+        Ok(Self {
+            max_fee_per_gas: gwei(APP_CONFIG.gas_max_fee_per_gas_gwei)?,
+            max_priority_fee_per_gas: gwei(APP_CONFIG.gas_max_priority_fee_gwei)?,
+            max_tx_fee: parse_ether(&APP_CONFIG.gas_max_tx_fee_eth)
+                .context("Failed to parse GAS_MAX_TX_FEE_ETH")?,
+            max_daily_fee: parse_ether(&APP_CONFIG.gas_max_daily_fee_eth)
+                .context("Failed to parse GAS_MAX_DAILY_FEE_ETH")?,
+            gas_limit_margin_percent: APP_CONFIG.gas_limit_margin_percent,
+            // Nodes reject replacements bumped by less than 10%
+            replacement_bump_percent: APP_CONFIG.gas_replacement_bump_percent.max(10),
+            confirmation_timeout: Duration::from_secs(APP_CONFIG.gas_confirmation_timeout_secs),
+        })
+    }
+
+    /// Add the configured safety margin to an estimated gas limit
+    pub fn gas_limit_with_margin(&self, estimated: U256) -> U256 {
+        estimated + estimated * self.gas_limit_margin_percent / 100
+    }
+
+    /// Bump a fee for a replacement transaction
+    pub fn bump(&self, fee: U256) -> U256 {
+        fee + fee * self.replacement_bump_percent / 100
+    }
+
+    /// Build a fee quote from network estimates, capping the priority fee and
+    /// rejecting the transaction if the network fee exceeds the caps
+    pub fn quote(
+        &self,
+        gas_limit: U256,
+        estimated_max_fee: U256,
+        estimated_priority_fee: U256,
+    ) -> Result<FeeQuote> {
+        let max_priority_fee_per_gas =
+            std::cmp::min(estimated_priority_fee, self.max_priority_fee_per_gas);
+        let max_fee_per_gas = std::cmp::max(estimated_max_fee, max_priority_fee_per_gas);
+
+        let quote = FeeQuote {
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        };
+        self.check(&quote)?;
+
+        Ok(quote)
+    }
+
+    /// Enforce the per-gas and per-transaction caps
+    pub fn check(&self, quote: &FeeQuote) -> Result<()> {
+        if quote.max_fee_per_gas > self.max_fee_per_gas {
+            return Err(FeeCapError {
+                kind: FeeCapKind::MaxFeePerGas,
+                required: quote.max_fee_per_gas,
+                cap: self.max_fee_per_gas,
+            }
+            .into());
+        }
+
+        if quote.max_total_fee() > self.max_tx_fee {
+            return Err(FeeCapError {
+                kind: FeeCapKind::PerTransaction,
+                required: quote.max_total_fee(),
+                cap: self.max_tx_fee,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Reserve the worst-case fee of a transaction against the signer's daily budget
+    pub async fn reserve_daily_fee(&self, signer: Address, amount: U256) -> Result<()> {
+        self.replace_daily_fee(signer, U256::zero(), amount).await
+    }
+
+    /// Release the reservation of a pending transaction and reserve `reserved`
+    /// for the transaction replacing it, as one update of the daily budget
+    pub async fn replace_daily_fee(
+        &self,
+        signer: Address,
+        released: U256,
+        reserved: U256,
+    ) -> Result<()> {
+        if adjust_daily_fee(signer, released, reserved, Some(self.max_daily_fee))
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let spent = daily_fee_spent(signer).await?;
+        Err(FeeCapError {
+            kind: FeeCapKind::Daily,
+            required: spent.saturating_sub(released) + reserved,
+            cap: self.max_daily_fee,
+        }
+        .into())
+    }
+
+    /// Replace a reservation with the fee actually paid once the receipt is known
+    pub async fn settle_daily_fee(&self, signer: Address, reserved: U256, actual: U256) {
+        if let Err(e) = adjust_daily_fee(signer, reserved, actual, None).await {
+            tracing::error!("Failed to settle daily fees of {:?}: {}", signer, e);
+        }
+    }
+}
+
+/// Subtract `released` from the signer's fees of today (saturating) and add
+/// `added`, unless the result would exceed `cap`; returns the new total, or
+/// `None` when the cap refused it
+///
+/// Runs as a single upsert, so instances sharing the database cannot overspend.
+async fn adjust_daily_fee(
+    signer: Address,
+    released: U256,
+    added: U256,
+    cap: Option<U256>,
+) -> Result<Option<U256>> {
+    if let Some(cap) = cap
+        && added > cap
+    {
+        return Ok(None);
+    }
+
+    let db = get_database_connection().await;
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"INSERT INTO gas_fee_spend (signer, day, spent_wei)
+               VALUES ($1, $2, $3::numeric)
+               ON CONFLICT (signer, day) DO UPDATE
+               SET spent_wei = GREATEST(gas_fee_spend.spent_wei - $4::numeric, 0) + $3::numeric
+               WHERE $5::numeric IS NULL
+                  OR GREATEST(gas_fee_spend.spent_wei - $4::numeric, 0) + $3::numeric <= $5::numeric
+               RETURNING spent_wei::text AS spent_wei"#,
+            [
+                format!("{:?}", signer).into(),
+                Utc::now().date_naive().into(),
+                added.to_string().into(),
+                released.to_string().into(),
+                cap.map(|cap| cap.to_string()).into(),
+            ],
+        ))
+        .await
+        .context("Failed to update daily fees")?;
+
+    row.map(|row| parse_wei(&row)).transpose()
+}
+
+/// Fees reserved or spent by the signer today
+async fn daily_fee_spent(signer: Address) -> Result<U256> {
+    let db = get_database_connection().await;
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT spent_wei::text AS spent_wei FROM gas_fee_spend WHERE signer = $1 AND day = $2",
+            [
+                format!("{:?}", signer).into(),
+                Utc::now().date_naive().into(),
+            ],
+        ))
+        .await
+        .context("Failed to query daily fees")?;
+
+    Ok(row
+        .map(|row| parse_wei(&row))
+        .transpose()?
+        .unwrap_or_default())
+}
+
+fn parse_wei(row: &QueryResult) -> Result<U256> {
+    let spent: String = row
+        .try_get("", "spent_wei")
+        .context("Failed to read daily fees")?;
+    U256::from_dec_str(&spent).context("Failed to parse daily fees")
+}
+
+/// Gas limit and EIP-1559 fees for a single transaction
+#[derive(Debug, Clone)]
+pub struct FeeQuote {
+    pub gas_limit: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl FeeQuote {
+    /// Worst-case fee the transaction can spend
+    pub fn max_total_fee(&self) -> U256 {
+        self.gas_limit * self.max_fee_per_gas
+    }
+}
@@ -0,0 +1,425 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use chrono::{NaiveDateTime, Utc};
+use ethers::providers::{Http, Middleware, Provider};
+use once_cell::sync::Lazy;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use super::backend::{ChainBackend, TransactionOutcome};
+use super::deployment::{ChainTarget, get_active_targets};
+use super::error::{ChainUnavailableError, TransactionUnconfirmedError, is_chain_unavailable};
+use super::service::{StudentInfo, is_transport_error};
+use crate::config::{APP_CONFIG, BlockchainBackend};
+
+/// Whether the chain part of a response is live, missing or still pending
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainStatus {
+    /// Read from / written to the contract
+    Available,
+    /// Node unreachable; on-chain fields are DB data or missing
+    Unavailable,
+    /// Node unreachable; the write was queued and will be sent later
+    Queued,
+}
+
+impl ChainStatus {
+    /// Response status of a chain write: 202 if it was queued instead of sent
+    pub fn write_status_code(self) -> StatusCode {
+        match self {
+            ChainStatus::Queued => StatusCode::ACCEPTED,
+            _ => StatusCode::OK,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through
+    Closed,
+    /// Calls fail fast with `ChainUnavailableError`
+    Open,
+    /// Open period elapsed; the next call decides whether to close or re-open
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    last_error: Option<String>,
+    last_checked_at: Option<NaiveDateTime>,
+}
+
+impl BreakerState {
+    fn circuit_state(&self) -> CircuitState {
+        match self.open_until {
+            Some(until) if Instant::now() < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+            None => CircuitState::Closed,
+        }
+    }
+}
+
+/// Breaker state per RPC URL, shared by every backend talking to the same node
+static BREAKERS: Lazy<Mutex<HashMap<String, BreakerState>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Snapshot of one RPC node's circuit breaker
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NodeHealth {
+    pub rpc_url: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_checked_at: Option<NaiveDateTime>,
+}
+
+/// Circuit breaker for the RPC node at `rpc_url`
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    rpc_url: String,
+}
+
+impl CircuitBreaker {
+    pub fn for_rpc(rpc_url: &str) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+        }
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut BreakerState) -> T) -> T {
+        let mut breakers = BREAKERS.lock().unwrap_or_else(|e| e.into_inner());
+        f(breakers.entry(self.rpc_url.clone()).or_default())
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.with_state(|state| state.circuit_state())
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.state() != CircuitState::Open
+    }
+
+    /// Fail fast while the breaker is open
+    pub fn check(&self) -> Result<(), ChainUnavailableError> {
+        self.with_state(|state| match state.circuit_state() {
+            CircuitState::Open => Err(ChainUnavailableError {
+                rpc_url: self.rpc_url.clone(),
+                reason: format!(
+                    "circuit open after {} failures (last: {})",
+                    state.consecutive_failures,
+                    state.last_error.as_deref().unwrap_or("unknown")
+                ),
+            }),
+            _ => Ok(()),
+        })
+    }
+
+    pub fn record_success(&self) {
+        self.with_state(|state| {
+            if state.open_until.is_some() {
+                tracing::info!("Blockchain node {} is reachable again", self.rpc_url);
+            }
+            state.consecutive_failures = 0;
+            state.open_until = None;
+            state.last_error = None;
+            state.last_checked_at = Some(Utc::now().naive_utc());
+        })
+    }
+
+    pub fn record_failure(&self, reason: &str) {
+        self.with_state(|state| {
+            state.consecutive_failures += 1;
+            state.last_error = Some(reason.to_string());
+            state.last_checked_at = Some(Utc::now().naive_utc());
+
+            if state.consecutive_failures >= APP_CONFIG.chain_failure_threshold {
+                if state.circuit_state() == CircuitState::Closed {
+                    tracing::warn!(
+                        "Blockchain node {} unavailable after {} failures, opening circuit: {}",
+                        self.rpc_url,
+                        state.consecutive_failures,
+                        reason
+                    );
+                }
+                state.open_until =
+                    Some(Instant::now() + Duration::from_secs(APP_CONFIG.chain_circuit_open_secs));
+            }
+        })
+    }
+
+    /// Run an RPC call through the breaker
+    ///
+    /// Transport errors count as node failures and are returned as
+    /// `ChainUnavailableError`, except after a transaction went out, where the
+    /// `TransactionUnconfirmedError` carrying its hash is kept; reverts and other
+    /// errors leave the breaker untouched.
+    pub async fn call<T>(&self, call: impl Future<Output = Result<T>>) -> Result<T> {
+        self.check()?;
+
+        match call.await {
+            Ok(value) => {
+                self.record_success();
+                Ok(value)
+            }
+            Err(e) if is_chain_unavailable(&e) || is_transport_error(&e) => {
+                let reason = match e.downcast_ref::<ChainUnavailableError>() {
+                    Some(unavailable) => unavailable.reason.clone(),
+                    None => format!("{:#}", e),
+                };
+                self.record_failure(&reason);
+                Err(ChainUnavailableError {
+                    rpc_url: self.rpc_url.clone(),
+                    reason,
+                }
+                .into())
+            }
+            Err(e) if e.downcast_ref::<TransactionUnconfirmedError>().is_some() => {
+                self.record_failure(&format!("{:#}", e));
+                Err(e)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Ping the node and update the breaker, ignoring whether it is open
+    pub async fn probe(&self) -> bool {
+        let result = async {
+            let provider = Provider::<Http>::try_from(self.rpc_url.as_str())?;
+            tokio::time::timeout(Duration::from_secs(5), provider.get_block_number())
+                .await
+                .map_err(|_| anyhow::anyhow!("timed out"))??;
+            anyhow::Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.record_success();
+                true
+            }
+            Err(e) => {
+                self.record_failure(&format!("{:#}", e));
+                false
+            }
+        }
+    }
+
+    pub fn health(&self) -> NodeHealth {
+        self.with_state(|state| NodeHealth {
+            rpc_url: self.rpc_url.clone(),
+            state: state.circuit_state(),
+            consecutive_failures: state.consecutive_failures,
+            last_error: state.last_error.clone(),
+            last_checked_at: state.last_checked_at,
+        })
+    }
+}
+
+/// Whether the node behind a deployment is currently considered reachable
+pub fn is_chain_available(target: &ChainTarget) -> bool {
+    APP_CONFIG.blockchain_backend == BlockchainBackend::Memory
+        || CircuitBreaker::for_rpc(&target.rpc_url).is_available()
+}
+
+/// RPC URLs of the deployment from config and every active registered deployment
+async fn monitored_rpc_urls(db: &DatabaseConnection) -> Result<BTreeSet<String>> {
+    Ok(get_active_targets(db)
+        .await?
+        .into_iter()
+        .map(|target| target.rpc_url)
+        .collect())
+}
+
+/// Probe every monitored node once and return their breaker snapshots
+pub async fn check_chain_health(db: &DatabaseConnection) -> Result<Vec<NodeHealth>> {
+    let mut nodes = Vec::new();
+    for rpc_url in monitored_rpc_urls(db).await? {
+        let breaker = CircuitBreaker::for_rpc(&rpc_url);
+        breaker.probe().await;
+        nodes.push(breaker.health());
+    }
+    Ok(nodes)
+}
+
+/// Periodically probe the RPC nodes so breakers close again without waiting for
+/// user traffic (no-op for the in-memory backend)
+pub fn spawn_health_monitor(db: &'static DatabaseConnection) {
+    if APP_CONFIG.blockchain_backend == BlockchainBackend::Memory {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            APP_CONFIG.chain_health_check_interval_secs,
+        ));
+        loop {
+            interval.tick().await;
+            if let Err(e) = check_chain_health(db).await {
+                tracing::error!("Chain health check failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Ethers backend wrapped in its node's circuit breaker
+pub struct GuardedBackend {
+    inner: Arc<dyn ChainBackend>,
+    breaker: CircuitBreaker,
+}
+
+impl GuardedBackend {
+    pub fn new(inner: Arc<dyn ChainBackend>, breaker: CircuitBreaker) -> Self {
+        Self { inner, breaker }
+    }
+}
+
+#[async_trait]
+impl ChainBackend for GuardedBackend {
+    fn sender_address(&self) -> String {
+        self.inner.sender_address()
+    }
+
+    async fn register_student(
+        &self,
+        wallet_address: &str,
+        student_code: &str,
+        full_name: &str,
+        email: &str,
+    ) -> Result<u64> {
+        self.breaker
+            .call(
+                self.inner
+                    .register_student(wallet_address, student_code, full_name, email),
+            )
+            .await
+    }
+
+    async fn register_students_batch(
+        &self,
+        wallet_addresses: Vec<String>,
+        student_codes: Vec<String>,
+        full_names: Vec<String>,
+        emails: Vec<String>,
+    ) -> Result<String> {
+        self.breaker
+            .call(self.inner.register_students_batch(
+                wallet_addresses,
+                student_codes,
+                full_names,
+                emails,
+            ))
+            .await
+    }
+
+    async fn assign_role(&self, user_address: &str, role: u8) -> Result<()> {
+        self.breaker
+            .call(self.inner.assign_role(user_address, role))
+            .await
+    }
+
+    async fn add_manager(&self, manager_address: &str) -> Result<()> {
+        self.breaker
+            .call(self.inner.add_manager(manager_address))
+            .await
+    }
+
+    async fn remove_manager(&self, manager_address: &str) -> Result<()> {
+        self.breaker
+            .call(self.inner.remove_manager(manager_address))
+            .await
+    }
+
+    async fn get_all_managers(&self) -> Result<Vec<String>> {
+        self.breaker.call(self.inner.get_all_managers()).await
+    }
+
+    async fn get_manager_count(&self) -> Result<u64> {
+        self.breaker.call(self.inner.get_manager_count()).await
+    }
+
+    async fn is_manager(&self, address: &str) -> Result<bool> {
+        self.breaker.call(self.inner.is_manager(address)).await
+    }
+
+    async fn deactivate_student(&self, student_id: u64) -> Result<()> {
+        self.breaker
+            .call(self.inner.deactivate_student(student_id))
+            .await
+    }
+
+    async fn activate_student(&self, student_id: u64) -> Result<()> {
+        self.breaker
+            .call(self.inner.activate_student(student_id))
+            .await
+    }
+
+    async fn get_student_id_by_address(&self, address: &str) -> Result<u64> {
+        self.breaker
+            .call(self.inner.get_student_id_by_address(address))
+            .await
+    }
+
+    async fn get_student_id_by_code(&self, student_code: &str) -> Result<u64> {
+        self.breaker
+            .call(self.inner.get_student_id_by_code(student_code))
+            .await
+    }
+
+    async fn is_active_student(&self, address: &str) -> Result<bool> {
+        self.breaker
+            .call(self.inner.is_active_student(address))
+            .await
+    }
+
+    async fn get_user_role(&self, address: &str) -> Result<u8> {
+        self.breaker.call(self.inner.get_user_role(address)).await
+    }
+
+    async fn has_role(&self, address: &str, role: u8) -> Result<bool> {
+        self.breaker.call(self.inner.has_role(address, role)).await
+    }
+
+    async fn is_teacher_or_admin(&self, address: &str) -> Result<bool> {
+        self.breaker
+            .call(self.inner.is_teacher_or_admin(address))
+            .await
+    }
+
+    async fn get_total_students(&self) -> Result<u64> {
+        self.breaker.call(self.inner.get_total_students()).await
+    }
+
+    async fn get_contract_info(&self) -> Result<(String, u64, u64)> {
+        self.breaker.call(self.inner.get_contract_info()).await
+    }
+
+    async fn get_student(&self, student_id: u64) -> Result<StudentInfo> {
+        self.breaker.call(self.inner.get_student(student_id)).await
+    }
+
+    async fn anchor_merkle_root(&self, root: [u8; 32]) -> Result<String> {
+        self.breaker.call(self.inner.anchor_merkle_root(root)).await
+    }
+
+    async fn find_root_anchor(&self, tx_hash: &str, root: [u8; 32]) -> Result<Option<String>> {
+        self.breaker
+            .call(self.inner.find_root_anchor(tx_hash, root))
+            .await
+    }
+
+    async fn transaction_outcome(&self, tx_hash: &str) -> Result<TransactionOutcome> {
+        self.breaker
+            .call(self.inner.transaction_outcome(tx_hash))
+            .await
+    }
+}
@@ -1,8 +1,15 @@
 use anyhow::{Context, Result};
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::sync::Arc;
 use uuid::Uuid;
 
-use super::service::BlockchainService;
+use super::backend::ChainBackend;
+use super::cache::CachedBackend;
+use super::deployment::{ChainTarget, get_default_deployment, resolve_user_deployment};
+use super::health::{CircuitBreaker, GuardedBackend};
+use super::memory::InMemoryBackend;
+use super::service::{BlockchainService, cached_chain_id};
+use crate::config::{APP_CONFIG, BlockchainBackend};
 use crate::entities::wallet;
 
 /// Get user's private key from database
@@ -17,17 +24,120 @@ pub async fn get_user_private_key(db: &DatabaseConnection, user_id: &Uuid) -> Re
     Ok(wallet_info.private_key)
 }
 
-/// Create BlockchainService for a specific user
+/// Create BlockchainService for a specific user, on the user's deployment
 pub async fn get_user_blockchain_service(
     db: &DatabaseConnection,
     user_id: &Uuid,
 ) -> Result<BlockchainService> {
     let private_key = get_user_private_key(db, user_id).await?;
-    BlockchainService::new(&private_key).await
+    let target = resolve_user_deployment(db, user_id).await?;
+    BlockchainService::connect(&private_key, &target).await
 }
 
-/// Create BlockchainService with admin private key (for admin operations)
-pub async fn get_admin_blockchain_service() -> Result<BlockchainService> {
-    use crate::config::APP_CONFIG;
-    BlockchainService::new(&APP_CONFIG.admin_private_key).await
+/// Get the wallet currently holding the contract owner key, if tracked in DB
+pub async fn get_contract_owner_wallet(db: &DatabaseConnection) -> Result<Option<wallet::Model>> {
+    wallet::Entity::find()
+        .filter(wallet::Column::IsContractOwner.eq(true))
+        .one(db)
+        .await
+        .context("Failed to query contract owner wallet")
+}
+
+/// Create BlockchainService with the contract owner key (for admin operations)
+/// on the default deployment
+pub async fn get_admin_blockchain_service(db: &DatabaseConnection) -> Result<BlockchainService> {
+    let target = get_default_deployment(db).await?;
+    BlockchainService::connect(&get_admin_private_key(db).await?, &target).await
+}
+
+/// Contract owner key, falling back to the configured admin private key when no
+/// owner wallet is recorded
+pub async fn get_admin_private_key(db: &DatabaseConnection) -> Result<String> {
+    Ok(match get_contract_owner_wallet(db).await? {
+        Some(owner_wallet) => owner_wallet.private_key,
+        None => APP_CONFIG.admin_private_key.clone(),
+    })
+}
+
+/// Create the configured chain backend signing with the given private key
+///
+/// Ethers backends go through the node's circuit breaker, so both connecting and
+/// later calls fail fast with `ChainUnavailableError` while the node is down.
+/// View calls are served from the read-through cache first; once the chain ID is
+/// known, connecting needs no RPC round trip, so cached reads keep working while
+/// the breaker is open.
+pub async fn get_chain_backend(
+    target: &ChainTarget,
+    private_key: &str,
+) -> Result<Arc<dyn ChainBackend>> {
+    Ok(match APP_CONFIG.blockchain_backend {
+        BlockchainBackend::Ethers => {
+            let breaker = CircuitBreaker::for_rpc(&target.rpc_url);
+            let service = if cached_chain_id(&target.rpc_url).is_some() {
+                BlockchainService::connect(private_key, target).await?
+            } else {
+                breaker
+                    .call(BlockchainService::connect(private_key, target))
+                    .await?
+            };
+            let guarded = GuardedBackend::new(Arc::new(service), breaker);
+            Arc::new(CachedBackend::new(Arc::new(guarded), target))
+        }
+        BlockchainBackend::Memory => Arc::new(InMemoryBackend::shared(target, private_key)?),
+    })
+}
+
+/// Create the configured chain backend for a specific user, on the user's deployment
+pub async fn get_user_chain_backend(
+    db: &DatabaseConnection,
+    user_id: &Uuid,
+) -> Result<Arc<dyn ChainBackend>> {
+    let private_key = get_user_private_key(db, user_id).await?;
+    let target = resolve_user_deployment(db, user_id).await?;
+    get_chain_backend(&target, &private_key).await
+}
+
+/// Wallet a chain write is signed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainSigner {
+    /// The user's own wallet
+    User(Uuid),
+    /// The contract owner wallet
+    Admin,
+}
+
+impl ChainSigner {
+    pub fn user_id(&self) -> Option<Uuid> {
+        match self {
+            ChainSigner::User(user_id) => Some(*user_id),
+            ChainSigner::Admin => None,
+        }
+    }
+}
+
+impl From<Option<Uuid>> for ChainSigner {
+    fn from(user_id: Option<Uuid>) -> Self {
+        user_id.map(ChainSigner::User).unwrap_or(ChainSigner::Admin)
+    }
+}
+
+/// Create the configured chain backend signing as `signer` on `target`
+pub async fn get_signer_chain_backend(
+    db: &DatabaseConnection,
+    signer: &ChainSigner,
+    target: &ChainTarget,
+) -> Result<Arc<dyn ChainBackend>> {
+    let private_key = match signer {
+        ChainSigner::User(user_id) => get_user_private_key(db, user_id).await?,
+        ChainSigner::Admin => get_admin_private_key(db).await?,
+    };
+    get_chain_backend(target, &private_key).await
+}
+
+/// Create the configured chain backend with the contract owner key
+pub async fn get_admin_chain_backend(
+    db: &DatabaseConnection,
+    target: &ChainTarget,
+) -> Result<Arc<dyn ChainBackend>> {
+    get_chain_backend(target, &get_admin_private_key(db).await?).await
 }
@@ -0,0 +1,412 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use ethers::utils::keccak256;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::backend::{ChainBackend, TransactionOutcome};
+use super::deployment::ChainTarget;
+use super::error::{ContractRevertError, ONLY_MANAGER_REASON, ONLY_OWNER_REASON};
+use super::service::StudentInfo;
+use crate::config::APP_CONFIG;
+
+pub const ROLE_NONE: u8 = 0;
+pub const ROLE_STUDENT: u8 = 1;
+pub const ROLE_TEACHER: u8 = 2;
+pub const ROLE_ADMIN: u8 = 3;
+
+/// Process-wide contract state per deployment, used when `BLOCKCHAIN_BACKEND=memory`
+static SHARED_STATES: Lazy<Mutex<HashMap<String, Arc<Mutex<DataStorageState>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Storage layout of the DataStorage contract
+#[derive(Debug, Default, Clone)]
+pub struct DataStorageState {
+    owner: String,
+    managers: Vec<String>,
+    user_roles: HashMap<String, u8>,
+    /// Student ID `n` is stored at index `n - 1`
+    students: Vec<StudentInfo>,
+    student_id_by_address: HashMap<String, u64>,
+    student_id_by_code: HashMap<String, u64>,
+    /// Merkle root anchors by transaction hash, with their sender
+    root_anchors: HashMap<String, ([u8; 32], String)>,
+}
+
+impl DataStorageState {
+    pub fn new(owner: &str) -> Self {
+        Self {
+            owner: owner.to_lowercase(),
+            ..Default::default()
+        }
+    }
+
+    fn is_manager(&self, address: &str) -> bool {
+        self.managers.iter().any(|m| m == address)
+    }
+
+    fn only_owner(&self, sender: &str, method: &str) -> Result<()> {
+        if self.owner != sender {
+            return Err(revert(method, ONLY_OWNER_REASON));
+        }
+        Ok(())
+    }
+
+    fn only_manager(&self, sender: &str, method: &str) -> Result<()> {
+        if self.owner != sender && !self.is_manager(sender) {
+            return Err(revert(method, ONLY_MANAGER_REASON));
+        }
+        Ok(())
+    }
+
+    fn student_mut(&mut self, student_id: u64, method: &str) -> Result<&mut StudentInfo> {
+        student_id
+            .checked_sub(1)
+            .and_then(|index| self.students.get_mut(index as usize))
+            .ok_or_else(|| revert(method, "Student not found"))
+    }
+
+    fn register(
+        &mut self,
+        address: String,
+        student_code: &str,
+        full_name: &str,
+        email: &str,
+        method: &str,
+    ) -> Result<u64> {
+        if self.student_id_by_address.contains_key(&address) {
+            return Err(revert(method, "Student already registered"));
+        }
+        if self.student_id_by_code.contains_key(student_code) {
+            return Err(revert(method, "Student code already exists"));
+        }
+
+        let student_id = self.students.len() as u64 + 1;
+        self.students.push(StudentInfo {
+            id: student_id,
+            wallet_address: address.clone(),
+            student_code: student_code.to_string(),
+            full_name: full_name.to_string(),
+            email: email.to_string(),
+            is_active: true,
+            registered_at: Utc::now().timestamp() as u64,
+        });
+        self.student_id_by_address
+            .insert(address.clone(), student_id);
+        self.student_id_by_code
+            .insert(student_code.to_string(), student_id);
+        self.user_roles.insert(address, ROLE_STUDENT);
+
+        Ok(student_id)
+    }
+}
+
+/// In-memory [`ChainBackend`] mimicking DataStorage semantics, for running
+/// and testing the service without an RPC node
+#[derive(Debug, Clone)]
+pub struct InMemoryBackend {
+    state: Arc<Mutex<DataStorageState>>,
+    sender: String,
+}
+
+impl InMemoryBackend {
+    /// Create a backend signing as `private_key` against the given state
+    pub fn new(state: Arc<Mutex<DataStorageState>>, private_key: &str) -> Result<Self> {
+        let wallet: LocalWallet = private_key.parse().context("Failed to parse private key")?;
+
+        Ok(Self {
+            state,
+            sender: format!("{:?}", wallet.address()),
+        })
+    }
+
+    /// Create a backend against the process-wide state of a deployment's contract,
+    /// owned by the configured admin key
+    pub fn shared(target: &ChainTarget, private_key: &str) -> Result<Self> {
+        let state = {
+            let mut states = SHARED_STATES
+                .lock()
+                .map_err(|_| anyhow::anyhow!("In-memory chain state poisoned"))?;
+
+            states
+                .entry(target.contract_address.to_lowercase())
+                .or_insert_with(|| {
+                    let owner = APP_CONFIG
+                        .admin_private_key
+                        .parse::<LocalWallet>()
+                        .map(|wallet| format!("{:?}", wallet.address()))
+                        .unwrap_or_default();
+                    Arc::new(Mutex::new(DataStorageState::new(&owner)))
+                })
+                .clone()
+        };
+
+        Self::new(state, private_key)
+    }
+
+    /// Drop the process-wide contract states, as if every deployment had just
+    /// been redeployed
+    pub fn reset_shared() {
+        SHARED_STATES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, DataStorageState>> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("In-memory chain state poisoned"))
+    }
+}
+
+fn revert(method: &str, reason: &str) -> anyhow::Error {
+    ContractRevertError::new(method, reason.to_string()).into()
+}
+
+/// Parse and normalize an address the way the ethers backend formats them
+fn normalize(address: &str) -> Result<String> {
+    let address: Address = address.parse().context("Failed to parse address")?;
+    Ok(format!("{:?}", address))
+}
+
+#[async_trait]
+impl ChainBackend for InMemoryBackend {
+    fn sender_address(&self) -> String {
+        self.sender.clone()
+    }
+
+    async fn register_student(
+        &self,
+        wallet_address: &str,
+        student_code: &str,
+        full_name: &str,
+        email: &str,
+    ) -> Result<u64> {
+        let address = normalize(wallet_address)?;
+        let mut state = self.state()?;
+        state.only_manager(&self.sender, "registerStudent")?;
+
+        state.register(address, student_code, full_name, email, "registerStudent")
+    }
+
+    async fn register_students_batch(
+        &self,
+        wallet_addresses: Vec<String>,
+        student_codes: Vec<String>,
+        full_names: Vec<String>,
+        emails: Vec<String>,
+    ) -> Result<String> {
+        let addresses = wallet_addresses
+            .iter()
+            .map(|address| normalize(address))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut state = self.state()?;
+        state.only_manager(&self.sender, "registerStudentsBatch")?;
+
+        if addresses.len() != student_codes.len()
+            || addresses.len() != full_names.len()
+            || addresses.len() != emails.len()
+        {
+            return Err(revert("registerStudentsBatch", "Array lengths mismatch"));
+        }
+
+        // A revert rolls back the whole batch
+        let mut staged = state.clone();
+        for (index, address) in addresses.into_iter().enumerate() {
+            staged.register(
+                address,
+                &student_codes[index],
+                &full_names[index],
+                &emails[index],
+                "registerStudentsBatch",
+            )?;
+        }
+        *state = staged;
+
+        // Fake but stable hash, unique per batch since codes cannot repeat
+        Ok(format!(
+            "0x{}",
+            hex::encode(keccak256(student_codes.join("\n")))
+        ))
+    }
+
+    async fn assign_role(&self, user_address: &str, role: u8) -> Result<()> {
+        let address = normalize(user_address)?;
+        let mut state = self.state()?;
+        state.only_owner(&self.sender, "assignRole")?;
+
+        state.user_roles.insert(address, role);
+        Ok(())
+    }
+
+    async fn add_manager(&self, manager_address: &str) -> Result<()> {
+        let address = normalize(manager_address)?;
+        let mut state = self.state()?;
+        state.only_owner(&self.sender, "addManager")?;
+
+        if state.is_manager(&address) {
+            return Err(revert("addManager", "Manager already exists"));
+        }
+        state.managers.push(address);
+        Ok(())
+    }
+
+    async fn remove_manager(&self, manager_address: &str) -> Result<()> {
+        let address = normalize(manager_address)?;
+        let mut state = self.state()?;
+        state.only_owner(&self.sender, "removeManager")?;
+
+        if !state.is_manager(&address) {
+            return Err(revert("removeManager", "Manager does not exist"));
+        }
+        state.managers.retain(|m| *m != address);
+        Ok(())
+    }
+
+    async fn get_all_managers(&self) -> Result<Vec<String>> {
+        Ok(self.state()?.managers.clone())
+    }
+
+    async fn get_manager_count(&self) -> Result<u64> {
+        Ok(self.state()?.managers.len() as u64)
+    }
+
+    async fn is_manager(&self, address: &str) -> Result<bool> {
+        let address = normalize(address)?;
+        Ok(self.state()?.is_manager(&address))
+    }
+
+    async fn deactivate_student(&self, student_id: u64) -> Result<()> {
+        let mut state = self.state()?;
+        state.only_manager(&self.sender, "deactivateStudent")?;
+
+        state
+            .student_mut(student_id, "deactivateStudent")?
+            .is_active = false;
+        Ok(())
+    }
+
+    async fn activate_student(&self, student_id: u64) -> Result<()> {
+        let mut state = self.state()?;
+        state.only_manager(&self.sender, "activateStudent")?;
+
+        state.student_mut(student_id, "activateStudent")?.is_active = true;
+        Ok(())
+    }
+
+    async fn get_student_id_by_address(&self, address: &str) -> Result<u64> {
+        let address = normalize(address)?;
+        Ok(self
+            .state()?
+            .student_id_by_address
+            .get(&address)
+            .copied()
+            .unwrap_or(0))
+    }
+
+    async fn get_student_id_by_code(&self, student_code: &str) -> Result<u64> {
+        Ok(self
+            .state()?
+            .student_id_by_code
+            .get(student_code)
+            .copied()
+            .unwrap_or(0))
+    }
+
+    async fn is_active_student(&self, address: &str) -> Result<bool> {
+        let address = normalize(address)?;
+        let state = self.state()?;
+
+        Ok(state
+            .student_id_by_address
+            .get(&address)
+            .and_then(|id| state.students.get(*id as usize - 1))
+            .map(|student| student.is_active)
+            .unwrap_or(false))
+    }
+
+    async fn get_user_role(&self, address: &str) -> Result<u8> {
+        let address = normalize(address)?;
+        Ok(self
+            .state()?
+            .user_roles
+            .get(&address)
+            .copied()
+            .unwrap_or(ROLE_NONE))
+    }
+
+    async fn has_role(&self, address: &str, role: u8) -> Result<bool> {
+        Ok(ChainBackend::get_user_role(self, address).await? == role)
+    }
+
+    async fn is_teacher_or_admin(&self, address: &str) -> Result<bool> {
+        let role = ChainBackend::get_user_role(self, address).await?;
+        Ok(role == ROLE_TEACHER || role == ROLE_ADMIN)
+    }
+
+    async fn get_total_students(&self) -> Result<u64> {
+        Ok(self.state()?.students.len() as u64)
+    }
+
+    async fn get_contract_info(&self) -> Result<(String, u64, u64)> {
+        let state = self.state()?;
+        Ok((
+            state.owner.clone(),
+            state.students.len() as u64,
+            state.managers.len() as u64,
+        ))
+    }
+
+    async fn get_student(&self, student_id: u64) -> Result<StudentInfo> {
+        let state = self.state()?;
+        student_id
+            .checked_sub(1)
+            .and_then(|index| state.students.get(index as usize))
+            .cloned()
+            .ok_or_else(|| revert("getStudent", "Student not found"))
+    }
+
+    async fn anchor_merkle_root(&self, root: [u8; 32]) -> Result<String> {
+        let mut state = self.state()?;
+
+        let mut preimage = root.to_vec();
+        preimage.extend_from_slice(&(state.root_anchors.len() as u64).to_be_bytes());
+        let tx_hash = format!("0x{}", hex::encode(keccak256(preimage)));
+
+        state
+            .root_anchors
+            .insert(tx_hash.clone(), (root, self.sender.clone()));
+        Ok(tx_hash)
+    }
+
+    async fn find_root_anchor(&self, tx_hash: &str, root: [u8; 32]) -> Result<Option<String>> {
+        Ok(self
+            .state()?
+            .root_anchors
+            .get(&tx_hash.to_lowercase())
+            .filter(|(anchored_root, _)| *anchored_root == root)
+            .map(|(_, sender)| sender.clone()))
+    }
+
+    /// Writes apply as they are sent, so only hashes this state handed out as
+    /// Merkle anchors are known
+    async fn transaction_outcome(&self, tx_hash: &str) -> Result<TransactionOutcome> {
+        Ok(
+            if self
+                .state()?
+                .root_anchors
+                .contains_key(&tx_hash.to_lowercase())
+            {
+                TransactionOutcome::Confirmed
+            } else {
+                TransactionOutcome::Unknown
+            },
+        )
+    }
+}
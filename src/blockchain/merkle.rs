@@ -0,0 +1,366 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use ethers::abi::{Token, encode};
+use ethers::types::Address;
+use ethers::utils::keccak256;
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+};
+use uuid::Uuid;
+
+use super::backend::ChainBackend;
+use super::deployment::{ChainTarget, get_deployment};
+use super::health::ChainStatus;
+use super::helpers::{ChainSigner, get_admin_chain_backend};
+use super::queue::{ChainOperation, execute_or_enqueue};
+use super::service::StudentInfo;
+use crate::entities::{merkle_batch, merkle_proof};
+
+pub type MerkleHash = [u8; 32];
+
+pub const BATCH_PENDING: &str = "pending";
+pub const BATCH_ANCHORED: &str = "anchored";
+
+/// Student record committed to by a Merkle leaf
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StudentLeaf {
+    pub user_id: Uuid,
+    pub wallet_address: String,
+    pub student_code: String,
+    pub full_name: String,
+    pub email: String,
+}
+
+impl StudentLeaf {
+    pub fn hash(&self) -> Result<MerkleHash> {
+        student_leaf_hash(
+            &self.wallet_address,
+            &self.student_code,
+            &self.full_name,
+            &self.email,
+        )
+    }
+}
+
+/// Leaf hash of a student record, encoded like OpenZeppelin's `StandardMerkleTree`
+/// leaves: `keccak256(bytes.concat(keccak256(abi.encode(wallet, code, name, email))))`.
+/// Proofs of the tree below verify with OpenZeppelin's `MerkleProof.verify`.
+pub fn student_leaf_hash(
+    wallet_address: &str,
+    student_code: &str,
+    full_name: &str,
+    email: &str,
+) -> Result<MerkleHash> {
+    let wallet_address: Address = wallet_address
+        .parse()
+        .context("Failed to parse wallet address")?;
+
+    let encoded = encode(&[
+        Token::Address(wallet_address),
+        Token::String(student_code.to_string()),
+        Token::String(full_name.to_string()),
+        Token::String(email.to_string()),
+    ]);
+
+    Ok(keccak256(keccak256(encoded)))
+}
+
+/// Hash two nodes in sorted order, so proofs need no left/right flags
+fn hash_pair(a: &MerkleHash, b: &MerkleHash) -> MerkleHash {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(first);
+    preimage[32..].copy_from_slice(second);
+    keccak256(preimage)
+}
+
+/// Binary Merkle tree over leaf hashes; an unpaired node is carried up unchanged
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// `layers[0]` are the leaves, the last layer holds only the root
+    layers: Vec<Vec<MerkleHash>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<MerkleHash>) -> Result<Self> {
+        anyhow::ensure!(!leaves.is_empty(), "Merkle tree needs at least one leaf");
+
+        let mut layers = vec![leaves];
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            let next = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks(2) yields one or two nodes"),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Ok(Self { layers })
+    }
+
+    pub fn root(&self) -> MerkleHash {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, mut index: usize) -> Vec<MerkleHash> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = index ^ 1;
+            if let Some(hash) = layer.get(sibling) {
+                proof.push(*hash);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+/// Check that `leaf` is included under `root`
+pub fn verify_proof(leaf: &MerkleHash, proof: &[MerkleHash], root: &MerkleHash) -> bool {
+    proof
+        .iter()
+        .fold(*leaf, |node, sibling| hash_pair(&node, sibling))
+        == *root
+}
+
+pub fn to_hex(hash: &MerkleHash) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+pub fn parse_hash(value: &str) -> Result<MerkleHash> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).context("Invalid hex hash")?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Hash must be 32 bytes"))
+}
+
+/// Build the tree over `students` and store the batch with one proof per student
+///
+/// Runs on any connection so callers can store it in the same transaction as the
+/// users it belongs to. The root is not anchored yet, see [`anchor_merkle_batch`].
+pub async fn create_merkle_batch<C: ConnectionTrait>(
+    db: &C,
+    target: &ChainTarget,
+    students: &[StudentLeaf],
+) -> Result<merkle_batch::Model> {
+    let leaves = students
+        .iter()
+        .map(StudentLeaf::hash)
+        .collect::<Result<Vec<_>>>()?;
+    let tree = MerkleTree::new(leaves.clone())?;
+
+    let batch_id = Uuid::new_v4();
+    let now = Utc::now().naive_utc();
+
+    let batch = merkle_batch::ActiveModel {
+        batch_id: Set(batch_id),
+        deployment_id: Set(target.deployment_id),
+        root: Set(to_hex(&tree.root())),
+        leaf_count: Set(students.len() as i32),
+        status: Set(BATCH_PENDING.to_string()),
+        tx_hash: Set(None),
+        anchored_by: Set(None),
+        created_at: Set(now),
+        anchored_at: Set(None),
+    }
+    .insert(db)
+    .await
+    .context("Failed to create Merkle batch")?;
+
+    let proofs = students
+        .iter()
+        .zip(&leaves)
+        .enumerate()
+        .map(|(index, (student, leaf))| merkle_proof::ActiveModel {
+            user_id: Set(student.user_id),
+            batch_id: Set(batch_id),
+            leaf_index: Set(index as i32),
+            leaf_hash: Set(to_hex(leaf)),
+            proof: Set(serde_json::Value::from(
+                tree.proof(index).iter().map(to_hex).collect::<Vec<_>>(),
+            )),
+            wallet_address: Set(student.wallet_address.clone()),
+            student_code: Set(student.student_code.clone()),
+            full_name: Set(student.full_name.clone()),
+            email: Set(student.email.clone()),
+            created_at: Set(now),
+        })
+        .collect::<Vec<_>>();
+
+    // Keep each insert well below Postgres' bind parameter limit
+    for chunk in proofs.chunks(1000) {
+        merkle_proof::Entity::insert_many(chunk.to_vec())
+            .exec(db)
+            .await
+            .context("Failed to store Merkle proofs")?;
+    }
+
+    Ok(batch)
+}
+
+/// Mark a batch as anchored by `tx_hash`
+pub async fn record_root_anchor(
+    db: &DatabaseConnection,
+    batch_id: &Uuid,
+    tx_hash: &str,
+    anchored_by: &str,
+) -> Result<()> {
+    let batch = merkle_batch::Entity::find_by_id(*batch_id)
+        .one(db)
+        .await
+        .context("Failed to query Merkle batch")?
+        .ok_or_else(|| anyhow::anyhow!("Merkle batch not found"))?;
+
+    let mut batch: merkle_batch::ActiveModel = batch.into();
+    batch.status = Set(BATCH_ANCHORED.to_string());
+    batch.tx_hash = Set(Some(tx_hash.to_string()));
+    batch.anchored_by = Set(Some(anchored_by.to_string()));
+    batch.anchored_at = Set(Some(Utc::now().naive_utc()));
+    batch
+        .update(db)
+        .await
+        .context("Failed to update Merkle batch")?;
+
+    Ok(())
+}
+
+/// Anchor a stored batch's root with the contract owner wallet, or queue the
+/// anchor transaction if the node is unavailable
+pub async fn anchor_merkle_batch(
+    db: &DatabaseConnection,
+    batch: &merkle_batch::Model,
+) -> Result<ChainStatus> {
+    let target = match batch.deployment_id {
+        Some(deployment_id) => get_deployment(db, &deployment_id).await?,
+        None => ChainTarget::from_config(),
+    };
+    let operation = ChainOperation::AnchorMerkleRoot {
+        batch_id: batch.batch_id,
+        root: batch.root.clone(),
+    };
+
+    execute_or_enqueue(db, &target, ChainSigner::Admin, operation).await
+}
+
+/// Whether `batch`'s root is anchored on-chain by the wallet recorded in the batch
+pub async fn is_batch_anchored(
+    backend: &dyn ChainBackend,
+    batch: &merkle_batch::Model,
+) -> Result<bool> {
+    let (Some(tx_hash), Some(anchored_by)) = (&batch.tx_hash, &batch.anchored_by) else {
+        return Ok(false);
+    };
+
+    let sender = backend
+        .find_root_anchor(tx_hash, parse_hash(&batch.root)?)
+        .await?;
+    Ok(sender.is_some_and(|sender| sender.eq_ignore_ascii_case(anchored_by)))
+}
+
+/// Stored proof of `wallet_address` if it verifies against its batch's root and
+/// the root is anchored on the batch's deployment
+///
+/// Students imported with `registration_mode=merkle` are not in DataStorage;
+/// this is their proof of enrollment.
+pub async fn find_anchored_proof(
+    db: &DatabaseConnection,
+    wallet_address: &str,
+) -> Result<Option<merkle_proof::Model>> {
+    let Some((proof, Some(batch))) = merkle_proof::Entity::find()
+        .find_also_related(merkle_batch::Entity)
+        .filter(
+            Expr::expr(Func::lower(Expr::col((
+                merkle_proof::Entity,
+                merkle_proof::Column::WalletAddress,
+            ))))
+            .eq(wallet_address.to_lowercase()),
+        )
+        .one(db)
+        .await
+        .context("Failed to query Merkle proof")?
+    else {
+        return Ok(None);
+    };
+    if batch.status != BATCH_ANCHORED {
+        return Ok(None);
+    }
+
+    // The stored record must be the one committed to by the root
+    let leaf = student_leaf_hash(
+        &proof.wallet_address,
+        &proof.student_code,
+        &proof.full_name,
+        &proof.email,
+    )?;
+    let siblings: Vec<String> =
+        serde_json::from_value(proof.proof.clone()).context("Invalid stored Merkle proof")?;
+    let siblings = siblings
+        .iter()
+        .map(|hash| parse_hash(hash))
+        .collect::<Result<Vec<_>>>()?;
+    if to_hex(&leaf) != proof.leaf_hash
+        || !verify_proof(&leaf, &siblings, &parse_hash(&batch.root)?)
+    {
+        return Ok(None);
+    }
+
+    let target = match batch.deployment_id {
+        Some(deployment_id) => get_deployment(db, &deployment_id).await?,
+        None => ChainTarget::from_config(),
+    };
+    let backend = get_admin_chain_backend(db, &target).await?;
+    if !is_batch_anchored(backend.as_ref(), &batch).await? {
+        return Ok(None);
+    }
+
+    Ok(Some(proof))
+}
+
+/// Whether `wallet_address` is an active student: active in DataStorage on
+/// `backend`'s contract, or, if it is not registered there, enrolled through an
+/// anchored Merkle batch
+pub async fn is_enrolled_student(
+    db: &DatabaseConnection,
+    backend: &dyn ChainBackend,
+    wallet_address: &str,
+) -> Result<bool> {
+    if backend.get_student_id_by_address(wallet_address).await? != 0 {
+        return backend.is_active_student(wallet_address).await;
+    }
+    Ok(find_anchored_proof(db, wallet_address).await?.is_some())
+}
+
+/// Enrollment record of `wallet_address`: its DataStorage record on `backend`'s
+/// contract, or, if it is not registered there, the record committed to by an
+/// anchored Merkle batch (`id` 0, always active)
+pub async fn find_enrolled_student(
+    db: &DatabaseConnection,
+    backend: &dyn ChainBackend,
+    wallet_address: &str,
+) -> Result<Option<StudentInfo>> {
+    let student_id = backend.get_student_id_by_address(wallet_address).await?;
+    if student_id != 0 {
+        return backend.get_student(student_id).await.map(Some);
+    }
+
+    Ok(find_anchored_proof(db, wallet_address)
+        .await?
+        .map(|proof| StudentInfo {
+            id: 0,
+            wallet_address: proof.wallet_address,
+            student_code: proof.student_code,
+            full_name: proof.full_name,
+            email: proof.email,
+            is_active: true,
+            registered_at: proof.created_at.and_utc().timestamp() as u64,
+        }))
+}
@@ -1,8 +1,53 @@
+pub mod backend;
+pub mod cache;
 pub mod contract;
+pub mod credential;
+pub mod deployment;
+pub mod error;
+pub mod gas;
+pub mod health;
 pub mod helpers;
+pub mod memory;
+pub mod merkle;
+pub mod queue;
 pub mod service;
+pub mod student_index;
+pub mod student_pass;
 
+pub use backend::{ChainBackend, TransactionOutcome};
+pub use cache::{CacheStats, MethodStats, cache_stats, clear_cache, spawn_cache_invalidator};
+pub use credential::{
+    StudentCredential, credential_issuer, enrollment_status, recover_credential_signer,
+    sign_credential,
+};
+pub use deployment::{
+    ChainTarget, StudentMigrationReport, get_default_deployment, get_deployment, migrate_students,
+    resolve_deployment_for_majors, resolve_user_deployment, resolve_wallet_deployment,
+};
+pub use error::{
+    ChainUnavailableError, ContractRevertError, FeeCapError, RevertKind, TransactionStuckError,
+    TransactionUnconfirmedError, is_chain_unavailable, map_blockchain_error, unsettled_tx_hash,
+};
+pub use health::{
+    ChainStatus, CircuitBreaker, NodeHealth, check_chain_health, is_chain_available,
+    spawn_health_monitor,
+};
 pub use helpers::{
-    get_admin_blockchain_service, get_user_blockchain_service, get_user_private_key,
+    ChainSigner, get_admin_blockchain_service, get_admin_chain_backend, get_admin_private_key,
+    get_chain_backend, get_contract_owner_wallet, get_signer_chain_backend,
+    get_user_blockchain_service, get_user_chain_backend, get_user_private_key,
+};
+pub use memory::InMemoryBackend;
+pub use merkle::{
+    MerkleTree, StudentLeaf, anchor_merkle_batch, create_merkle_batch, find_anchored_proof,
+    find_enrolled_student, is_batch_anchored, is_enrolled_student, student_leaf_hash, verify_proof,
+};
+pub use queue::{
+    ChainOperation, ChainWrite, enqueue, enqueue_after, enqueue_claimed, execute_or_enqueue,
+    send_claimed, send_or_enqueue, spawn_chain_job_worker,
+};
+pub use service::{AuthorizedContract, BlockchainService};
+pub use student_index::{
+    record_student_statuses, spawn_student_status_sync, sync_student_statuses,
 };
-pub use service::BlockchainService;
+pub use student_pass::{StudentPass, decode_student_pass, sign_student_pass, student_pass_issuer};
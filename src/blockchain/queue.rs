@@ -0,0 +1,584 @@
+use anyhow::{Context, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::backend::{ChainBackend, TransactionOutcome};
+use super::deployment::{ChainTarget, get_deployment};
+use super::error::{is_chain_unavailable, unsettled_tx_hash};
+use super::health::{ChainStatus, is_chain_available};
+use super::helpers::{ChainSigner, get_signer_chain_backend};
+use super::merkle::{parse_hash, record_root_anchor};
+use super::student_index::record_student_statuses;
+use crate::config::APP_CONFIG;
+use crate::entities::chain_job;
+
+pub const JOB_PENDING: &str = "pending";
+pub const JOB_PROCESSING: &str = "processing";
+pub const JOB_DONE: &str = "done";
+pub const JOB_FAILED: &str = "failed";
+
+/// Jobs picked up per worker poll
+const JOB_BATCH_SIZE: u64 = 20;
+
+/// Chain write that can be queued while the RPC node is unavailable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainOperation {
+    RegisterStudent {
+        wallet_address: String,
+        student_code: String,
+        full_name: String,
+        email: String,
+    },
+    RegisterStudentsBatch {
+        wallet_addresses: Vec<String>,
+        student_codes: Vec<String>,
+        full_names: Vec<String>,
+        emails: Vec<String>,
+    },
+    AssignRole {
+        user_address: String,
+        role: u8,
+    },
+    AddManager {
+        manager_address: String,
+    },
+    RemoveManager {
+        manager_address: String,
+    },
+    DeactivateStudent {
+        student_id: u64,
+    },
+    ActivateStudent {
+        student_id: u64,
+    },
+    AnchorMerkleRoot {
+        batch_id: Uuid,
+        root: String,
+    },
+}
+
+impl ChainOperation {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ChainOperation::RegisterStudent { .. } => "register_student",
+            ChainOperation::RegisterStudentsBatch { .. } => "register_students_batch",
+            ChainOperation::AssignRole { .. } => "assign_role",
+            ChainOperation::AddManager { .. } => "add_manager",
+            ChainOperation::RemoveManager { .. } => "remove_manager",
+            ChainOperation::DeactivateStudent { .. } => "deactivate_student",
+            ChainOperation::ActivateStudent { .. } => "activate_student",
+            ChainOperation::AnchorMerkleRoot { .. } => "anchor_merkle_root",
+        }
+    }
+
+    /// Send the operation, returning the transaction hash for operations that report
+    /// one (batch registrations and Merkle anchors, see [`ChainOperation::record_result`])
+    pub async fn execute(&self, backend: &dyn ChainBackend) -> Result<Option<String>> {
+        match self {
+            ChainOperation::RegisterStudent {
+                wallet_address,
+                student_code,
+                full_name,
+                email,
+            } => backend
+                .register_student(wallet_address, student_code, full_name, email)
+                .await
+                .map(|_| None),
+            ChainOperation::RegisterStudentsBatch {
+                wallet_addresses,
+                student_codes,
+                full_names,
+                emails,
+            } => backend
+                .register_students_batch(
+                    wallet_addresses.clone(),
+                    student_codes.clone(),
+                    full_names.clone(),
+                    emails.clone(),
+                )
+                .await
+                .map(Some),
+            ChainOperation::AssignRole { user_address, role } => {
+                backend.assign_role(user_address, *role).await.map(|_| None)
+            }
+            ChainOperation::AddManager { manager_address } => {
+                backend.add_manager(manager_address).await.map(|_| None)
+            }
+            ChainOperation::RemoveManager { manager_address } => {
+                backend.remove_manager(manager_address).await.map(|_| None)
+            }
+            ChainOperation::DeactivateStudent { student_id } => {
+                backend.deactivate_student(*student_id).await.map(|_| None)
+            }
+            ChainOperation::ActivateStudent { student_id } => {
+                backend.activate_student(*student_id).await.map(|_| None)
+            }
+            ChainOperation::AnchorMerkleRoot { root, .. } => backend
+                .anchor_merkle_root(parse_hash(root)?)
+                .await
+                .map(Some),
+        }
+    }
+
+    /// Store the outcome of an operation sent with `backend` where it is tracked
+    ///
+    /// Student statuses are only a copy of the contract that the periodic sync
+    /// also refreshes, so failing to store them does not fail the operation.
+    pub async fn record_result(
+        &self,
+        db: &DatabaseConnection,
+        target: &ChainTarget,
+        backend: &dyn ChainBackend,
+        tx_hash: Option<String>,
+    ) -> Result<()> {
+        let statuses = match self {
+            ChainOperation::AnchorMerkleRoot { batch_id, .. } => {
+                if let Some(tx_hash) = tx_hash {
+                    record_root_anchor(db, batch_id, &tx_hash, &backend.sender_address()).await?;
+                }
+                return Ok(());
+            }
+            ChainOperation::RegisterStudent {
+                wallet_address,
+                student_code,
+                ..
+            } => {
+                record_student_statuses(
+                    db,
+                    &target.contract_address,
+                    [(wallet_address.as_str(), student_code.as_str(), true)],
+                )
+                .await
+            }
+            ChainOperation::RegisterStudentsBatch {
+                wallet_addresses,
+                student_codes,
+                ..
+            } => {
+                record_student_statuses(
+                    db,
+                    &target.contract_address,
+                    wallet_addresses
+                        .iter()
+                        .zip(student_codes)
+                        .map(|(wallet, code)| (wallet.as_str(), code.as_str(), true)),
+                )
+                .await
+            }
+            ChainOperation::DeactivateStudent { student_id }
+            | ChainOperation::ActivateStudent { student_id } => {
+                let is_active = matches!(self, ChainOperation::ActivateStudent { .. });
+                async {
+                    let student = backend.get_student(*student_id).await?;
+                    record_student_statuses(
+                        db,
+                        &target.contract_address,
+                        [(
+                            student.wallet_address.as_str(),
+                            student.student_code.as_str(),
+                            is_active,
+                        )],
+                    )
+                    .await
+                }
+                .await
+            }
+            _ => return Ok(()),
+        };
+
+        if let Err(e) = statuses {
+            tracing::warn!(
+                "Failed to store student status after {}: {}",
+                self.kind(),
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Queue a chain write to be sent once the node is reachable again
+pub async fn enqueue<C: ConnectionTrait>(
+    db: &C,
+    target: &ChainTarget,
+    signer: ChainSigner,
+    operation: &ChainOperation,
+) -> Result<Uuid> {
+    insert_job(db, target, signer, operation, JOB_PENDING, None).await
+}
+
+/// Queue a chain write into `outbox`, the transaction holding the rows it belongs
+/// to, for [`send_claimed`] to send once they are committed
+///
+/// The job starts out claimed, so the worker leaves it alone; if the service stops
+/// before sending it, the worker requeues it on startup.
+pub async fn enqueue_claimed<C: ConnectionTrait>(
+    outbox: &C,
+    target: &ChainTarget,
+    signer: ChainSigner,
+    operation: &ChainOperation,
+) -> Result<Uuid> {
+    insert_job(outbox, target, signer, operation, JOB_PROCESSING, None).await
+}
+
+/// Queue `operation` if `error` means the node became unavailable while sending
+/// it or its transaction is still unsettled, returning the job ID, or `None` for
+/// errors that are not worth retrying
+///
+/// If its transaction was or may have been broadcast, the job keeps the hash and
+/// the worker checks that transaction before sending anything again.
+pub async fn enqueue_after<C: ConnectionTrait>(
+    db: &C,
+    target: &ChainTarget,
+    signer: ChainSigner,
+    operation: &ChainOperation,
+    error: &anyhow::Error,
+) -> Result<Option<Uuid>> {
+    let tx_hash = match unsettled_tx_hash(error) {
+        Some(tx_hash) => Some(tx_hash),
+        None if is_chain_unavailable(error) => None,
+        None => return Ok(None),
+    };
+
+    let job_id = insert_job(db, target, signer, operation, JOB_PENDING, tx_hash).await?;
+    tracing::warn!(
+        "Queued {} as chain job {} on deployment '{}': {}",
+        operation.kind(),
+        job_id,
+        target.name,
+        error
+    );
+    Ok(Some(job_id))
+}
+
+async fn insert_job<C: ConnectionTrait>(
+    db: &C,
+    target: &ChainTarget,
+    signer: ChainSigner,
+    operation: &ChainOperation,
+    status: &str,
+    tx_hash: Option<String>,
+) -> Result<Uuid> {
+    let job_id = Uuid::new_v4();
+    let now = Utc::now().naive_utc();
+
+    chain_job::ActiveModel {
+        job_id: Set(job_id),
+        kind: Set(operation.kind().to_string()),
+        payload: Set(serde_json::to_value(operation).context("Failed to serialize chain job")?),
+        deployment_id: Set(target.deployment_id),
+        signer_user_id: Set(signer.user_id()),
+        status: Set(status.to_string()),
+        attempts: Set(0),
+        last_error: Set(None),
+        next_attempt_at: Set(now),
+        tx_hash: Set(tx_hash),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .context("Failed to queue chain job")?;
+
+    Ok(job_id)
+}
+
+/// Outcome of [`send_or_enqueue`] and [`send_claimed`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainWrite {
+    pub status: ChainStatus,
+    /// Transaction hash, for sent operations that report one and for queued
+    /// operations whose transaction may already have been broadcast
+    pub tx_hash: Option<String>,
+    /// Chain job holding the operation while the node is unavailable
+    pub job_id: Option<Uuid>,
+}
+
+/// Send a chain write now, or queue it if the node is unavailable
+///
+/// Contract reverts and other errors are returned as-is; only unavailability and
+/// unsettled transactions lead to `ChainStatus::Queued`, with the transaction hash
+/// if the write was or may have been broadcast.
+pub async fn send_or_enqueue(
+    db: &DatabaseConnection,
+    target: &ChainTarget,
+    signer: ChainSigner,
+    operation: ChainOperation,
+) -> Result<ChainWrite> {
+    let result = async {
+        let backend = get_signer_chain_backend(db, &signer, target).await?;
+        let tx_hash = operation.execute(backend.as_ref()).await?;
+        operation
+            .record_result(db, target, backend.as_ref(), tx_hash.clone())
+            .await?;
+        anyhow::Ok(tx_hash)
+    }
+    .await;
+
+    let e = match result {
+        Ok(tx_hash) => {
+            return Ok(ChainWrite {
+                status: ChainStatus::Available,
+                tx_hash,
+                job_id: None,
+            });
+        }
+        Err(e) => e,
+    };
+
+    match enqueue_after(db, target, signer, &operation, &e).await? {
+        Some(job_id) => Ok(ChainWrite {
+            status: ChainStatus::Queued,
+            tx_hash: unsettled_tx_hash(&e),
+            job_id: Some(job_id),
+        }),
+        None => Err(e),
+    }
+}
+
+/// [`send_or_enqueue`] for callers that only need the resulting status
+pub async fn execute_or_enqueue(
+    db: &DatabaseConnection,
+    target: &ChainTarget,
+    signer: ChainSigner,
+    operation: ChainOperation,
+) -> Result<ChainStatus> {
+    send_or_enqueue(db, target, signer, operation)
+        .await
+        .map(|write| write.status)
+}
+
+async fn job_target(db: &DatabaseConnection, job: &chain_job::Model) -> Result<ChainTarget> {
+    match job.deployment_id {
+        Some(deployment_id) => get_deployment(db, &deployment_id).await,
+        None => Ok(ChainTarget::from_config()),
+    }
+}
+
+/// Send a job's operation, unless the transaction of an earlier attempt went
+/// through. Returns the hash of a transaction still waiting to be mined, whether
+/// this attempt timed out waiting for it or lost the node after sending it.
+async fn send_job(
+    db: &DatabaseConnection,
+    job: &chain_job::Model,
+    target: &ChainTarget,
+) -> Result<Option<String>> {
+    let operation: ChainOperation =
+        serde_json::from_value(job.payload.clone()).context("Invalid chain job payload")?;
+    let backend =
+        get_signer_chain_backend(db, &ChainSigner::from(job.signer_user_id), target).await?;
+
+    if let Some(tx_hash) = &job.tx_hash {
+        match backend.transaction_outcome(tx_hash).await? {
+            TransactionOutcome::Confirmed => {
+                operation
+                    .record_result(db, target, backend.as_ref(), Some(tx_hash.clone()))
+                    .await?;
+                return Ok(None);
+            }
+            TransactionOutcome::Reverted => {
+                anyhow::bail!("Transaction {} reverted", tx_hash);
+            }
+            TransactionOutcome::Pending => return Ok(Some(tx_hash.clone())),
+            // Never broadcast, or dropped from the mempool: safe to send again
+            TransactionOutcome::Unknown => {}
+        }
+    }
+
+    match operation.execute(backend.as_ref()).await {
+        Ok(tx_hash) => {
+            operation
+                .record_result(db, target, backend.as_ref(), tx_hash)
+                .await?;
+            Ok(None)
+        }
+        Err(e) => match unsettled_tx_hash(&e) {
+            Some(tx_hash) => Ok(Some(tx_hash)),
+            None => Err(e),
+        },
+    }
+}
+
+/// Run a claimed job and record the outcome. Returns false if the node turned out
+/// to be unavailable, so the caller can stop the round.
+async fn run_job(
+    db: &DatabaseConnection,
+    job: chain_job::Model,
+    target: &ChainTarget,
+) -> Result<bool> {
+    let result = send_job(db, &job, target).await;
+    record_job_result(db, job, &result).await?;
+
+    Ok(!matches!(&result, Err(e) if is_chain_unavailable(e)))
+}
+
+/// Store the outcome of an attempt at a job, returning the job's new status
+async fn record_job_result(
+    db: &DatabaseConnection,
+    job: chain_job::Model,
+    result: &Result<Option<String>>,
+) -> Result<&'static str> {
+    let now = Utc::now().naive_utc();
+    let job_id = job.job_id;
+    let attempts = job.attempts + 1;
+    let mut job: chain_job::ActiveModel = job.into();
+    job.attempts = Set(attempts);
+    job.updated_at = Set(now);
+
+    let status = match result {
+        Ok(None) => {
+            job.last_error = Set(None);
+            JOB_DONE
+        }
+        Ok(Some(tx_hash)) => {
+            // Check on the transaction again after the circuit open period
+            job.last_error = Set(Some(format!("Waiting for transaction {}", tx_hash)));
+            job.tx_hash = Set(Some(tx_hash.clone()));
+            job.next_attempt_at =
+                Set(now + ChronoDuration::seconds(APP_CONFIG.chain_circuit_open_secs as i64));
+            JOB_PENDING
+        }
+        Err(e) if is_chain_unavailable(e) && attempts < APP_CONFIG.chain_job_max_attempts => {
+            // Back off exponentially from the circuit open period, capped at an hour
+            let delay = APP_CONFIG
+                .chain_circuit_open_secs
+                .saturating_mul(1 << attempts.min(7))
+                .min(3600);
+            job.last_error = Set(Some(e.to_string()));
+            job.next_attempt_at = Set(now + ChronoDuration::seconds(delay as i64));
+            JOB_PENDING
+        }
+        Err(e) => {
+            tracing::error!("Chain job {} failed: {}", job_id, e);
+            job.last_error = Set(Some(e.to_string()));
+            JOB_FAILED
+        }
+    };
+    job.status = Set(status.to_string());
+
+    job.update(db).await.context("Failed to update chain job")?;
+
+    Ok(status)
+}
+
+/// Send a job queued with [`enqueue_claimed`], once the rows it belongs to are
+/// committed
+///
+/// As with [`send_or_enqueue`], only unavailability and unsettled transactions lead
+/// to `ChainStatus::Queued`, leaving the job to the worker. Reverts and other errors
+/// mean nothing was written on-chain; they mark the job failed and are returned,
+/// for the caller to undo its rows.
+pub async fn send_claimed(db: &DatabaseConnection, job_id: Uuid) -> Result<ChainWrite> {
+    let job = chain_job::Entity::find_by_id(job_id)
+        .one(db)
+        .await
+        .context("Failed to query chain job")?
+        .ok_or_else(|| anyhow::anyhow!("Chain job {} not found", job_id))?;
+    let target = job_target(db, &job).await?;
+
+    let result = send_job(db, &job, &target).await;
+    let status = record_job_result(db, job, &result).await?;
+
+    match result {
+        Ok(None) => Ok(ChainWrite {
+            status: ChainStatus::Available,
+            tx_hash: None,
+            job_id: None,
+        }),
+        Ok(Some(tx_hash)) => Ok(ChainWrite {
+            status: ChainStatus::Queued,
+            tx_hash: Some(tx_hash),
+            job_id: Some(job_id),
+        }),
+        Err(_) if status == JOB_PENDING => Ok(ChainWrite {
+            status: ChainStatus::Queued,
+            tx_hash: None,
+            job_id: Some(job_id),
+        }),
+        Err(e) => Err(e),
+    }
+}
+
+/// Send due jobs in creation order, skipping deployments whose node is down.
+/// Returns the number of jobs that were attempted.
+pub async fn process_due_jobs(db: &DatabaseConnection) -> Result<u64> {
+    let now = Utc::now().naive_utc();
+    let due = chain_job::Entity::find()
+        .filter(chain_job::Column::Status.eq(JOB_PENDING))
+        .filter(chain_job::Column::NextAttemptAt.lte(now))
+        .order_by_asc(chain_job::Column::CreatedAt)
+        .limit(JOB_BATCH_SIZE)
+        .all(db)
+        .await
+        .context("Failed to query chain jobs")?;
+
+    let mut attempted = 0;
+    for job in due {
+        let target = match job_target(db, &job).await {
+            Ok(target) => target,
+            Err(e) => {
+                tracing::error!("Chain job {} has no usable deployment: {}", job.job_id, e);
+                continue;
+            }
+        };
+        if !is_chain_available(&target) {
+            continue;
+        }
+
+        // Claim the job so a concurrent worker does not send it twice
+        let claimed = chain_job::Entity::update_many()
+            .col_expr(chain_job::Column::Status, Expr::value(JOB_PROCESSING))
+            .col_expr(chain_job::Column::UpdatedAt, Expr::value(now))
+            .filter(chain_job::Column::JobId.eq(job.job_id))
+            .filter(chain_job::Column::Status.eq(JOB_PENDING))
+            .exec(db)
+            .await
+            .context("Failed to claim chain job")?;
+        if claimed.rows_affected == 0 {
+            continue;
+        }
+
+        attempted += 1;
+        if !run_job(db, job, &target).await? {
+            break;
+        }
+    }
+
+    Ok(attempted)
+}
+
+/// Poll the chain job table and send queued writes once nodes are back
+pub fn spawn_chain_job_worker(db: &'static DatabaseConnection) {
+    tokio::spawn(async move {
+        // Jobs left in `processing` were interrupted by a restart; their outcome is
+        // unknown, so retry them (reverts such as "already registered" fail cleanly,
+        // and jobs with a transaction hash check it first)
+        if let Err(e) = chain_job::Entity::update_many()
+            .col_expr(chain_job::Column::Status, Expr::value(JOB_PENDING))
+            .filter(chain_job::Column::Status.eq(JOB_PROCESSING))
+            .exec(db)
+            .await
+        {
+            tracing::error!("Failed to requeue interrupted chain jobs: {}", e);
+        }
+
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(APP_CONFIG.chain_job_poll_interval_secs));
+        loop {
+            interval.tick().await;
+            match process_due_jobs(db).await {
+                Ok(0) => {}
+                Ok(attempted) => tracing::info!("Processed {} queued chain jobs", attempted),
+                Err(e) => tracing::error!("Chain job worker failed: {}", e),
+            }
+        }
+    });
+}
@@ -1,38 +1,460 @@
+use crate::blockchain::backend::TransactionOutcome;
 use crate::blockchain::contract::DataStorage;
-use crate::config::APP_CONFIG;
+use crate::blockchain::deployment::ChainTarget;
+use crate::blockchain::error::{
+    ChainUnavailableError, ContractRevertError, TransactionStuckError, TransactionUnconfirmedError,
+};
+use crate::blockchain::gas::{FeeQuote, GasPolicy};
 use anyhow::{Context, Result};
+use ethers::abi::Detokenize;
+use ethers::contract::builders::ContractCall;
 use ethers::prelude::*;
-use ethers::providers::{Http, Provider};
+use ethers::providers::{Http, Provider, ProviderError, RpcError};
 use ethers::signers::{LocalWallet, Signer};
-use std::sync::Arc;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Chain ID per RPC URL, so connecting does not cost a round trip per request
+static CHAIN_IDS: Lazy<Mutex<HashMap<String, u64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Chain ID of the node at `rpc_url`, if a previous connection already fetched it
+pub fn cached_chain_id(rpc_url: &str) -> Option<u64> {
+    CHAIN_IDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(rpc_url)
+        .copied()
+}
 
 #[derive(Clone, Debug)]
 pub struct BlockchainService {
-    contract: DataStorage<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    contract: DataStorage<Client>,
+    gas_policy: GasPolicy,
 }
 
 impl BlockchainService {
     /// Create BlockchainService with private key (no default/hardcoded wallet)
+    /// against the deployment from config
     pub async fn new(private_key: &str) -> Result<Self> {
-        let provider = Provider::<Http>::try_from(&APP_CONFIG.blockchain_rpc_url)
-            .context("Failed to create provider")?;
+        Self::connect(private_key, &ChainTarget::from_config()).await
+    }
+
+    /// Create BlockchainService with private key against a specific deployment
+    pub async fn connect(private_key: &str, target: &ChainTarget) -> Result<Self> {
+        let provider =
+            Provider::<Http>::try_from(&target.rpc_url).context("Failed to create provider")?;
 
         let wallet: LocalWallet = private_key.parse().context("Failed to parse private key")?;
 
-        let chain_id = provider.get_chainid().await?;
-        let wallet = wallet.with_chain_id(chain_id.as_u64());
+        let chain_id = match cached_chain_id(&target.rpc_url) {
+            Some(chain_id) => chain_id,
+            None => {
+                let chain_id = provider
+                    .get_chainid()
+                    .await
+                    .map_err(|e| ChainUnavailableError {
+                        rpc_url: target.rpc_url.clone(),
+                        reason: e.to_string(),
+                    })?
+                    .as_u64();
+                CHAIN_IDS
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(target.rpc_url.clone(), chain_id);
+                chain_id
+            }
+        };
+        if let Some(expected) = target.chain_id {
+            anyhow::ensure!(
+                chain_id == expected,
+                "RPC node for deployment '{}' is on chain {} (expected {})",
+                target.name,
+                chain_id,
+                expected
+            );
+        }
+        let wallet = wallet.with_chain_id(chain_id);
 
         let client = SignerMiddleware::new(provider, wallet);
         let client = Arc::new(client);
 
-        let contract_address: Address = APP_CONFIG
-            .data_storage_contract_address
+        let contract_address: Address = target
+            .contract_address
             .parse()
             .context("Failed to parse contract address")?;
 
         let contract = DataStorage::new(contract_address, client);
+        let gas_policy = GasPolicy::from_config()?;
+
+        Ok(Self {
+            contract,
+            gas_policy,
+        })
+    }
 
-        Ok(Self { contract })
+    /// Address of the wallet signing this service's transactions
+    pub fn signer_address(&self) -> String {
+        format!("{:?}", self.contract.client().address())
+    }
+
+    /// Simulate a contract write with `eth_call` before sending it, so reverts
+    /// are reported with their decoded reason instead of wasting gas
+    async fn simulate<D: Detokenize>(
+        &self,
+        call: &ContractCall<Client, D>,
+        method: &str,
+    ) -> Result<()> {
+        let Err(e) = call.call().await else {
+            return Ok(());
+        };
+
+        if let Some(reason) = e.decode_revert::<String>() {
+            return Err(ContractRevertError::new(method, reason).into());
+        }
+        if e.is_revert() {
+            return Err(ContractRevertError::new(method, "execution reverted".to_string()).into());
+        }
+
+        Err(anyhow::Error::new(e).context(format!("Failed to simulate {} transaction", method)))
+    }
+
+    /// Simulate, price and send a contract write, then wait for its receipt
+    ///
+    /// The gas limit is estimated with a safety margin and EIP-1559 fees are
+    /// checked against the per-transaction and per-day caps before sending.
+    async fn send_call<D: Detokenize>(
+        &self,
+        mut call: ContractCall<Client, D>,
+        method: &str,
+    ) -> Result<TransactionReceipt> {
+        self.simulate(&call, method).await?;
+
+        let client = self.contract.client();
+        let estimated_gas = call
+            .estimate_gas()
+            .await
+            .with_context(|| format!("Failed to estimate gas for {}", method))?;
+        let (max_fee, priority_fee) = client
+            .estimate_eip1559_fees(None)
+            .await
+            .context("Failed to estimate EIP-1559 fees")?;
+
+        let quote = self.gas_policy.quote(
+            self.gas_policy.gas_limit_with_margin(estimated_gas),
+            max_fee,
+            priority_fee,
+        )?;
+
+        call = call.gas(quote.gas_limit);
+        match &mut call.tx {
+            TypedTransaction::Eip1559(tx) => {
+                tx.max_fee_per_gas = Some(quote.max_fee_per_gas);
+                tx.max_priority_fee_per_gas = Some(quote.max_priority_fee_per_gas);
+            }
+            tx => {
+                tx.set_gas_price(quote.max_fee_per_gas);
+            }
+        }
+
+        self.send_and_confirm(call.tx, &quote, method).await
+    }
+
+    /// Sign and broadcast `tx` priced by `quote`, then wait for its receipt,
+    /// failing with a `ContractRevertError` if it reverted once mined
+    ///
+    /// The worst-case fee is reserved against the daily cap first. The hash is
+    /// known before broadcasting, so if the node drops out once the transaction
+    /// may have reached it, a `TransactionUnconfirmedError` carrying the hash is
+    /// returned rather than an unavailability error that would get it sent twice.
+    async fn send_and_confirm(
+        &self,
+        mut tx: TypedTransaction,
+        quote: &FeeQuote,
+        what: &str,
+    ) -> Result<TransactionReceipt> {
+        let client = self.contract.client();
+        let signer = client.address();
+
+        client
+            .fill_transaction(&mut tx, None)
+            .await
+            .with_context(|| format!("Failed to prepare {} transaction", what))?;
+        let signature = client
+            .signer()
+            .sign_transaction(&tx)
+            .await
+            .with_context(|| format!("Failed to sign {} transaction", what))?;
+        let tx_hash = tx.hash(&signature);
+
+        let reserved = quote.max_total_fee();
+        self.gas_policy.reserve_daily_fee(signer, reserved).await?;
+
+        let pending = match client.send_raw_transaction(tx.rlp_signed(&signature)).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                let e = anyhow::Error::new(e);
+                // The node may have taken the transaction before the connection
+                // failed, so its reservation is kept
+                if is_transport_error(&e) {
+                    return Err(unconfirmed(tx_hash, e));
+                }
+                self.gas_policy
+                    .settle_daily_fee(signer, reserved, U256::zero())
+                    .await;
+                return Err(e.context(format!("Failed to send {} transaction", what)));
+            }
+        };
+
+        // Keep the reservation for stuck transactions until they are replaced
+        let confirmation = tokio::time::timeout(self.gas_policy.confirmation_timeout, pending)
+            .await
+            .map_err(|_| TransactionStuckError { tx_hash })?;
+        let receipt = match confirmation {
+            Ok(receipt) => {
+                receipt.ok_or_else(|| anyhow::anyhow!("Transaction receipt not found"))?
+            }
+            Err(e) => {
+                let e = anyhow::Error::new(e);
+                if is_transport_error(&e) {
+                    return Err(unconfirmed(tx_hash, e));
+                }
+                return Err(e.context("Failed to wait for transaction confirmation"));
+            }
+        };
+
+        let actual_fee = receipt.gas_used.unwrap_or(quote.gas_limit)
+            * receipt.effective_gas_price.unwrap_or(quote.max_fee_per_gas);
+        self.gas_policy
+            .settle_daily_fee(signer, reserved, actual_fee)
+            .await;
+
+        // Mined but reverted, e.g. state changed after the simulation
+        if receipt.status != Some(U64::one()) {
+            return Err(ContractRevertError::new(
+                what,
+                format!("transaction {:?} reverted", receipt.transaction_hash),
+            )
+            .into());
+        }
+
+        Ok(receipt)
+    }
+
+    /// Re-send a stuck transaction with the same nonce and bumped fees
+    pub async fn speed_up_transaction(&self, tx_hash: &str) -> Result<String> {
+        self.replace_transaction(tx_hash, false).await
+    }
+
+    /// Cancel a stuck transaction by replacing it with an empty self-transfer
+    pub async fn cancel_transaction(&self, tx_hash: &str) -> Result<String> {
+        self.replace_transaction(tx_hash, true).await
+    }
+
+    async fn replace_transaction(&self, tx_hash: &str, cancel: bool) -> Result<String> {
+        let hash: TxHash = tx_hash
+            .parse()
+            .context("Failed to parse transaction hash")?;
+        let client = self.contract.client();
+        let signer = client.address();
+
+        let original = client
+            .get_transaction(hash)
+            .await
+            .context("Failed to get transaction")?
+            .ok_or_else(|| anyhow::anyhow!("Transaction not found"))?;
+
+        if original.block_number.is_some() {
+            return Err(anyhow::anyhow!("Transaction is already confirmed"));
+        }
+        if original.from != signer {
+            return Err(anyhow::anyhow!("Transaction was not sent by this wallet"));
+        }
+
+        let (estimated_max_fee, estimated_priority_fee) = client
+            .estimate_eip1559_fees(None)
+            .await
+            .context("Failed to estimate EIP-1559 fees")?;
+
+        // The replacement must outbid the original and keep up with the network
+        let old_max_fee = original
+            .max_fee_per_gas
+            .or(original.gas_price)
+            .unwrap_or_default();
+        let old_priority_fee = original.max_priority_fee_per_gas.unwrap_or(old_max_fee);
+        let max_priority_fee_per_gas = std::cmp::max(
+            self.gas_policy.bump(old_priority_fee),
+            estimated_priority_fee,
+        );
+        let max_fee_per_gas = std::cmp::max(self.gas_policy.bump(old_max_fee), estimated_max_fee)
+            .max(max_priority_fee_per_gas);
+
+        let quote = FeeQuote {
+            gas_limit: if cancel {
+                U256::from(21_000)
+            } else {
+                original.gas
+            },
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        };
+        self.gas_policy.check(&quote)?;
+
+        // Only one of the two can be mined, so the original's worst-case fee, as
+        // reserved when it was sent, is released for the replacement's
+        let original_reserved = original.gas * old_max_fee;
+        let reserved = quote.max_total_fee();
+        self.gas_policy
+            .replace_daily_fee(signer, original_reserved, reserved)
+            .await?;
+
+        let replacement = Eip1559TransactionRequest::new()
+            .from(signer)
+            .nonce(original.nonce)
+            .gas(quote.gas_limit)
+            .max_fee_per_gas(quote.max_fee_per_gas)
+            .max_priority_fee_per_gas(quote.max_priority_fee_per_gas);
+
+        let replacement = if cancel {
+            replacement.to(signer).value(U256::zero())
+        } else {
+            let to = original
+                .to
+                .ok_or_else(|| anyhow::anyhow!("Transaction has no recipient"))?;
+            replacement
+                .to(to)
+                .value(original.value)
+                .data(original.input.clone())
+        };
+
+        let pending = match client.send_transaction(replacement, None).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                self.gas_policy
+                    .settle_daily_fee(signer, reserved, original_reserved)
+                    .await;
+                return Err(anyhow::Error::new(e).context("Failed to send replacement transaction"));
+            }
+        };
+
+        Ok(format!("{:?}", *pending))
+    }
+
+    /// Publish a Merkle root as the calldata of a zero-value transaction from the
+    /// signer to itself, returning the transaction hash once it is confirmed
+    ///
+    /// DataStorage has no storage for roots; the transaction itself is the anchor.
+    /// The calldata is the DataStorage address followed by the root, so an anchor
+    /// only counts for the contract it was made for.
+    pub async fn anchor_merkle_root(&self, root: [u8; 32]) -> Result<String> {
+        let client = self.contract.client();
+        let signer = client.address();
+
+        let mut tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(signer)
+            .to(signer)
+            .value(U256::zero())
+            .data(Bytes::from(self.anchor_calldata(root)))
+            .into();
+
+        let estimated_gas = client
+            .estimate_gas(&tx, None)
+            .await
+            .context("Failed to estimate gas for Merkle root anchor")?;
+        let (max_fee, priority_fee) = client
+            .estimate_eip1559_fees(None)
+            .await
+            .context("Failed to estimate EIP-1559 fees")?;
+
+        let quote = self.gas_policy.quote(
+            self.gas_policy.gas_limit_with_margin(estimated_gas),
+            max_fee,
+            priority_fee,
+        )?;
+        tx.set_gas(quote.gas_limit);
+        if let TypedTransaction::Eip1559(tx) = &mut tx {
+            tx.max_fee_per_gas = Some(quote.max_fee_per_gas);
+            tx.max_priority_fee_per_gas = Some(quote.max_priority_fee_per_gas);
+        }
+
+        let receipt = self
+            .send_and_confirm(tx, &quote, "Merkle root anchor")
+            .await?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    /// Calldata anchoring `root` for this service's contract
+    fn anchor_calldata(&self, root: [u8; 32]) -> Vec<u8> {
+        let mut calldata = self.contract.address().as_bytes().to_vec();
+        calldata.extend_from_slice(&root);
+        calldata
+    }
+
+    /// Sender of `tx_hash` if it is a successful self-transaction anchoring `root`
+    /// for this service's contract
+    pub async fn find_root_anchor(&self, tx_hash: &str, root: [u8; 32]) -> Result<Option<String>> {
+        let hash: TxHash = tx_hash
+            .parse()
+            .context("Failed to parse transaction hash")?;
+        let client = self.contract.client();
+
+        let Some(receipt) = client
+            .get_transaction_receipt(hash)
+            .await
+            .context("Failed to get transaction receipt")?
+        else {
+            return Ok(None);
+        };
+        if receipt.status != Some(U64::one()) {
+            return Ok(None);
+        }
+
+        let Some(tx) = client
+            .get_transaction(hash)
+            .await
+            .context("Failed to get transaction")?
+        else {
+            return Ok(None);
+        };
+        if tx.to != Some(tx.from) || tx.input.as_ref() != self.anchor_calldata(root).as_slice() {
+            return Ok(None);
+        }
+
+        Ok(Some(format!("{:?}", tx.from)))
+    }
+
+    /// What became of transaction `tx_hash`
+    pub async fn transaction_outcome(&self, tx_hash: &str) -> Result<TransactionOutcome> {
+        let hash: TxHash = tx_hash
+            .parse()
+            .context("Failed to parse transaction hash")?;
+        let client = self.contract.client();
+
+        if let Some(receipt) = client
+            .get_transaction_receipt(hash)
+            .await
+            .context("Failed to get transaction receipt")?
+        {
+            return Ok(if receipt.status == Some(U64::one()) {
+                TransactionOutcome::Confirmed
+            } else {
+                TransactionOutcome::Reverted
+            });
+        }
+
+        let pending = client
+            .get_transaction(hash)
+            .await
+            .context("Failed to get transaction")?;
+        Ok(if pending.is_some() {
+            TransactionOutcome::Pending
+        } else {
+            TransactionOutcome::Unknown
+        })
     }
 
     /// Generate a new Ethereum wallet
@@ -60,20 +482,13 @@ impl BlockchainService {
             .parse()
             .context("Failed to parse wallet address")?;
 
-        let tx = self
-            .contract
-            .register_student(
-                address,
-                student_code.to_string(),
-                full_name.to_string(),
-                email.to_string(),
-            )
-            .send()
-            .await
-            .context("Failed to send register student transaction")?
-            .await
-            .context("Failed to wait for transaction confirmation")?
-            .ok_or_else(|| anyhow::anyhow!("Transaction receipt not found"))?;
+        let call = self.contract.register_student(
+            address,
+            student_code.to_string(),
+            full_name.to_string(),
+            email.to_string(),
+        );
+        let tx = self.send_call(call, "registerStudent").await?;
 
         // Parse the event to get the student ID
         let logs = tx.logs;
@@ -92,14 +507,14 @@ impl BlockchainService {
         ))
     }
 
-    /// Register multiple students on the blockchain in batch
+    /// Register multiple students on the blockchain in batch, returning the transaction hash
     pub async fn register_students_batch(
         &self,
         wallet_addresses: Vec<String>,
         student_codes: Vec<String>,
         full_names: Vec<String>,
         emails: Vec<String>,
-    ) -> Result<()> {
+    ) -> Result<String> {
         let addresses: Result<Vec<Address>> = wallet_addresses
             .iter()
             .map(|addr| addr.parse().context("Failed to parse wallet address"))
@@ -107,20 +522,12 @@ impl BlockchainService {
 
         let addresses = addresses?;
 
-        let tx = self
-            .contract
-            .register_students_batch(addresses, student_codes, full_names, emails)
-            .send()
-            .await
-            .context("Failed to send batch register transaction")?
-            .await
-            .context("Failed to wait for transaction confirmation")?;
-
-        if tx.is_none() {
-            return Err(anyhow::anyhow!("Transaction receipt not found"));
-        }
+        let call =
+            self.contract
+                .register_students_batch(addresses, student_codes, full_names, emails);
+        let receipt = self.send_call(call, "registerStudentsBatch").await?;
 
-        Ok(())
+        Ok(format!("{:?}", receipt.transaction_hash))
     }
 
     /// Assign role to a user on the blockchain
@@ -129,14 +536,8 @@ impl BlockchainService {
             .parse()
             .context("Failed to parse user address")?;
 
-        let _tx = self
-            .contract
-            .assign_role(address, role)
-            .send()
-            .await
-            .context("Failed to send assign role transaction")?
-            .await
-            .context("Failed to wait for transaction confirmation")?;
+        let call = self.contract.assign_role(address, role);
+        self.send_call(call, "assignRole").await?;
 
         Ok(())
     }
@@ -147,14 +548,8 @@ impl BlockchainService {
             .parse()
             .context("Failed to parse manager address")?;
 
-        let _tx = self
-            .contract
-            .add_manager(address)
-            .send()
-            .await
-            .context("Failed to send add manager transaction")?
-            .await
-            .context("Failed to wait for transaction confirmation")?;
+        let call = self.contract.add_manager(address);
+        self.send_call(call, "addManager").await?;
 
         Ok(())
     }
@@ -165,14 +560,8 @@ impl BlockchainService {
             .parse()
             .context("Failed to parse manager address")?;
 
-        let _tx = self
-            .contract
-            .remove_manager(address)
-            .send()
-            .await
-            .context("Failed to send remove manager transaction")?
-            .await
-            .context("Failed to wait for transaction confirmation")?;
+        let call = self.contract.remove_manager(address);
+        self.send_call(call, "removeManager").await?;
 
         Ok(())
     }
@@ -217,28 +606,16 @@ impl BlockchainService {
 
     /// Deactivate a student
     pub async fn deactivate_student(&self, student_id: u64) -> Result<()> {
-        let _tx = self
-            .contract
-            .deactivate_student(U256::from(student_id))
-            .send()
-            .await
-            .context("Failed to send deactivate student transaction")?
-            .await
-            .context("Failed to wait for transaction confirmation")?;
+        let call = self.contract.deactivate_student(U256::from(student_id));
+        self.send_call(call, "deactivateStudent").await?;
 
         Ok(())
     }
 
     /// Activate a student
     pub async fn activate_student(&self, student_id: u64) -> Result<()> {
-        let _tx = self
-            .contract
-            .activate_student(U256::from(student_id))
-            .send()
-            .await
-            .context("Failed to send activate student transaction")?
-            .await
-            .context("Failed to wait for transaction confirmation")?;
+        let call = self.contract.activate_student(U256::from(student_id));
+        self.send_call(call, "activateStudent").await?;
 
         Ok(())
     }
@@ -353,6 +730,130 @@ impl BlockchainService {
         ))
     }
 
+    /// Get the current contract owner
+    pub async fn get_owner(&self) -> Result<String> {
+        let owner = self
+            .contract
+            .owner()
+            .call()
+            .await
+            .context("Failed to get contract owner")?;
+
+        Ok(format!("{:?}", owner))
+    }
+
+    /// Get the factory contract address
+    pub async fn get_factory_contract(&self) -> Result<String> {
+        let factory = self
+            .contract
+            .factory_contract()
+            .call()
+            .await
+            .context("Failed to get factory contract")?;
+
+        Ok(format!("{:?}", factory))
+    }
+
+    /// Transfer contract ownership to a new address (requires owner)
+    pub async fn change_owner(&self, new_owner: &str) -> Result<()> {
+        let address: Address = new_owner
+            .parse()
+            .context("Failed to parse new owner address")?;
+
+        let call = self.contract.change_owner(address);
+        self.send_call(call, "changeOwner").await?;
+
+        Ok(())
+    }
+
+    /// Authorize a dependent contract (requires owner)
+    pub async fn authorize_contract(&self, contract_address: &str) -> Result<()> {
+        let address: Address = contract_address
+            .parse()
+            .context("Failed to parse contract address")?;
+
+        let call = self.contract.authorize_contract(address);
+        self.send_call(call, "authorizeContract").await?;
+
+        Ok(())
+    }
+
+    /// Revoke authorization of a dependent contract (requires owner)
+    pub async fn unauthorize_contract(&self, contract_address: &str) -> Result<()> {
+        let address: Address = contract_address
+            .parse()
+            .context("Failed to parse contract address")?;
+
+        let call = self.contract.unauthorize_contract(address);
+        self.send_call(call, "unauthorizeContract").await?;
+
+        Ok(())
+    }
+
+    /// Check if a contract is authorized
+    pub async fn is_authorized_contract(&self, contract_address: &str) -> Result<bool> {
+        let addr: Address = contract_address
+            .parse()
+            .context("Failed to parse contract address")?;
+
+        let is_authorized = self
+            .contract
+            .authorized_contracts(addr)
+            .call()
+            .await
+            .context("Failed to check if contract is authorized")?;
+
+        Ok(is_authorized)
+    }
+
+    /// List currently authorized contracts by replaying
+    /// ContractAuthorized / ContractUnauthorized events
+    pub async fn get_authorized_contracts(&self) -> Result<Vec<AuthorizedContract>> {
+        let authorized = self
+            .contract
+            .contract_authorized_filter()
+            .from_block(0u64)
+            .query_with_meta()
+            .await
+            .context("Failed to query ContractAuthorized events")?;
+
+        let unauthorized = self
+            .contract
+            .contract_unauthorized_filter()
+            .from_block(0u64)
+            .query_with_meta()
+            .await
+            .context("Failed to query ContractUnauthorized events")?;
+
+        // Merge both event streams in chain order
+        let mut events: Vec<(bool, Address, LogMeta)> = authorized
+            .into_iter()
+            .map(|(event, meta)| (true, event.contract_address, meta))
+            .chain(
+                unauthorized
+                    .into_iter()
+                    .map(|(event, meta)| (false, event.contract_address, meta)),
+            )
+            .collect();
+        events.sort_by_key(|(_, _, meta)| (meta.block_number, meta.log_index));
+
+        let mut contracts: Vec<AuthorizedContract> = Vec::new();
+        for (is_authorize, address, meta) in events {
+            let address = format!("{:?}", address);
+            contracts.retain(|c| c.contract_address != address);
+
+            if is_authorize {
+                contracts.push(AuthorizedContract {
+                    contract_address: address,
+                    authorized_at_block: meta.block_number.as_u64(),
+                    transaction_hash: format!("{:?}", meta.transaction_hash),
+                });
+            }
+        }
+
+        Ok(contracts)
+    }
+
     /// Get student details by ID
     pub async fn get_student(&self, student_id: u64) -> Result<StudentInfo> {
         let student = self
@@ -374,6 +875,46 @@ impl BlockchainService {
     }
 }
 
+/// Transport error `e` raised once `tx_hash` may have been broadcast
+fn unconfirmed(tx_hash: TxHash, e: anyhow::Error) -> anyhow::Error {
+    TransactionUnconfirmedError {
+        tx_hash,
+        reason: format!("{:#}", e),
+    }
+    .into()
+}
+
+/// Whether an RPC error means the node could not be reached, as opposed to the
+/// node answering with a JSON-RPC error, a revert or undecodable data
+pub fn is_transport_error(e: &anyhow::Error) -> bool {
+    fn provider_unreachable(e: &ProviderError) -> bool {
+        match e {
+            ProviderError::JsonRpcClientError(rpc) => {
+                !rpc.is_error_response() && rpc.as_serde_error().is_none()
+            }
+            ProviderError::HTTPError(_) => true,
+            _ => false,
+        }
+    }
+
+    fn signer_unreachable(e: &SignerMiddlewareError<Provider<Http>, LocalWallet>) -> bool {
+        matches!(e, SignerMiddlewareError::MiddlewareError(p) if provider_unreachable(p))
+    }
+
+    if let Some(e) = e.downcast_ref::<ContractError<Client>>() {
+        return match e {
+            ContractError::MiddlewareError { e } => signer_unreachable(e),
+            ContractError::ProviderError { e } => provider_unreachable(e),
+            _ => false,
+        };
+    }
+    if let Some(e) = e.downcast_ref::<SignerMiddlewareError<Provider<Http>, LocalWallet>>() {
+        return signer_unreachable(e);
+    }
+    e.downcast_ref::<ProviderError>()
+        .is_some_and(provider_unreachable)
+}
+
 /// Student information struct
 #[derive(Debug, Clone)]
 pub struct StudentInfo {
@@ -385,3 +926,11 @@ pub struct StudentInfo {
     pub is_active: bool,
     pub registered_at: u64,
 }
+
+/// Authorized dependent contract, as reconstructed from contract events
+#[derive(Debug, Clone)]
+pub struct AuthorizedContract {
+    pub contract_address: String,
+    pub authorized_at_block: u64,
+    pub transaction_hash: String,
+}
@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use std::time::Duration;
+
+use super::deployment::{ChainTarget, get_active_targets};
+use super::error::is_chain_unavailable;
+use super::helpers::get_admin_chain_backend;
+use crate::config::APP_CONFIG;
+use crate::entities::student_chain_status;
+
+/// Students read per `get_students` call while syncing
+const SYNC_PAGE_SIZE: u64 = 100;
+
+/// Store the code and status of `(wallet, student code, is active)` students
+/// registered on the contract at `contract_address`
+pub async fn record_student_statuses<'a, C: ConnectionTrait>(
+    db: &C,
+    contract_address: &str,
+    students: impl IntoIterator<Item = (&'a str, &'a str, bool)>,
+) -> Result<()> {
+    let now = Utc::now().naive_utc();
+    let contract_address = contract_address.to_lowercase();
+    let models: Vec<student_chain_status::ActiveModel> = students
+        .into_iter()
+        .map(
+            |(wallet_address, student_code, is_active)| student_chain_status::ActiveModel {
+                contract_address: Set(contract_address.clone()),
+                wallet_address: Set(wallet_address.to_lowercase()),
+                student_code: Set(student_code.to_string()),
+                is_active: Set(is_active),
+                synced_at: Set(now),
+            },
+        )
+        .collect();
+    if models.is_empty() {
+        return Ok(());
+    }
+
+    student_chain_status::Entity::insert_many(models)
+        .on_conflict(
+            OnConflict::columns([
+                student_chain_status::Column::ContractAddress,
+                student_chain_status::Column::WalletAddress,
+            ])
+            .update_columns([
+                student_chain_status::Column::StudentCode,
+                student_chain_status::Column::IsActive,
+                student_chain_status::Column::SyncedAt,
+            ])
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await
+        .context("Failed to store student status")?;
+
+    Ok(())
+}
+
+/// Read every student of `target` into the stored statuses, returning how many
+async fn sync_target(db: &DatabaseConnection, target: &ChainTarget) -> Result<u64> {
+    let backend = get_admin_chain_backend(db, target).await?;
+    let total = backend.get_total_students().await?;
+
+    let mut start_id = 1;
+    while start_id <= total {
+        let students = backend
+            .get_students(start_id, SYNC_PAGE_SIZE, total)
+            .await?;
+        record_student_statuses(
+            db,
+            &target.contract_address,
+            students.iter().map(|student| {
+                (
+                    student.wallet_address.as_str(),
+                    student.student_code.as_str(),
+                    student.is_active,
+                )
+            }),
+        )
+        .await?;
+        start_id += SYNC_PAGE_SIZE;
+    }
+
+    Ok(total)
+}
+
+/// Refresh the stored status of the students of every active deployment and drop
+/// those of contracts no longer in use. Deployments whose node is unavailable
+/// keep their last synced statuses. Returns the number of students read.
+pub async fn sync_student_statuses(db: &DatabaseConnection) -> Result<u64> {
+    let targets = get_active_targets(db).await?;
+
+    let mut synced = 0;
+    for target in &targets {
+        match sync_target(db, target).await {
+            Ok(count) => synced += count,
+            Err(e) if is_chain_unavailable(&e) => {
+                tracing::warn!(
+                    "Student status sync skipped deployment '{}': {}",
+                    target.name,
+                    e
+                );
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to sync student statuses of deployment '{}': {}",
+                    target.name,
+                    e
+                );
+            }
+        }
+    }
+
+    let contracts: Vec<String> = targets
+        .iter()
+        .map(|target| target.contract_address.to_lowercase())
+        .collect();
+    student_chain_status::Entity::delete_many()
+        .filter(student_chain_status::Column::ContractAddress.is_not_in(contracts))
+        .exec(db)
+        .await
+        .context("Failed to remove student statuses of retired deployments")?;
+
+    Ok(synced)
+}
+
+/// Sync the stored student statuses on startup and then periodically, picking up
+/// changes made outside this service
+pub fn spawn_student_status_sync(db: &'static DatabaseConnection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            APP_CONFIG.chain_status_sync_interval_secs,
+        ));
+        loop {
+            interval.tick().await;
+            match sync_student_statuses(db).await {
+                Ok(synced) => tracing::debug!("Synced the status of {} students", synced),
+                Err(e) => tracing::error!("Student status sync failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Signature};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::credential::signing_wallet;
+use crate::config::APP_CONFIG;
+
+/// Short-lived student pass shown as a QR code at campus gates and exam halls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentPass {
+    /// Student wallet the pass is bound to
+    pub wallet: String,
+    pub student_code: String,
+    pub full_name: String,
+    /// Unix timestamp (seconds)
+    pub issued_at: u64,
+    /// Unix timestamp (seconds)
+    pub expires_at: u64,
+}
+
+/// Key student passes are signed with
+fn pass_signer() -> Result<LocalWallet> {
+    signing_wallet(
+        APP_CONFIG.student_pass_signing_key.as_deref(),
+        "STUDENT_PASS_SIGNING_KEY",
+    )
+}
+
+/// Address of the pass signing key, published so gate readers can pin it
+pub fn student_pass_issuer() -> Result<String> {
+    Ok(format!("{:?}", pass_signer()?.address()))
+}
+
+/// Sign a pass with the pass signing key, returning the compact token
+/// `base64url(json).base64url(signature)`; the signature is an EIP-191
+/// personal signature over the first segment, so it can also be checked offline
+pub async fn sign_student_pass(pass: &StudentPass) -> Result<String> {
+    let payload =
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(pass).context("Failed to encode pass")?);
+    let signature = pass_signer()?
+        .sign_message(payload.as_bytes())
+        .await
+        .context("Failed to sign pass")?;
+
+    Ok(format!(
+        "{}.{}",
+        payload,
+        URL_SAFE_NO_PAD.encode(signature.to_vec())
+    ))
+}
+
+/// Read a pass token back, returning the pass and the address that signed it
+pub fn decode_student_pass(token: &str) -> Result<(StudentPass, String)> {
+    let (payload, signature) = token
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| anyhow::anyhow!("Malformed pass token"))?;
+
+    let pass: StudentPass = URL_SAFE_NO_PAD
+        .decode(payload)
+        .context("Invalid pass payload encoding")
+        .and_then(|json| serde_json::from_slice(&json).context("Invalid pass payload"))?;
+    pass.wallet
+        .parse::<Address>()
+        .context("Invalid pass wallet")?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .context("Invalid pass signature encoding")?;
+    let signer = Signature::try_from(signature.as_slice())
+        .context("Failed to parse signature")?
+        .recover(payload.as_bytes())
+        .context("Failed to recover pass signer")?;
+
+    Ok((pass, format!("{:?}", signer)))
+}
@@ -1,11 +1,16 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
 use ethers::signers::{LocalWallet, Signer};
+use sea_orm::sea_query::Expr;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use uuid::Uuid;
 
 use crate::config::APP_CONFIG;
-use crate::entities::{sea_orm_active_enums::RoleEnum, user, wallet};
+use crate::entities::{
+    import_job, sea_orm_active_enums::RoleEnum, student_migration_job, user, wallet,
+};
+use crate::routes::chain::migration_job::{MIGRATION_FAILED, MIGRATION_PENDING, MIGRATION_RUNNING};
+use crate::routes::users::import_job::{IMPORT_FAILED, IMPORT_PENDING, IMPORT_RUNNING};
 
 /// Initialize default admin user if not exists
 pub async fn initialize_admin_user(db: &DatabaseConnection) -> Result<()> {
@@ -78,6 +83,8 @@ pub async fn initialize_admin_user(db: &DatabaseConnection) -> Result<()> {
         last_used_at: Set(None),
         created_at: Set(now),
         updated_at: Set(now),
+        is_contract_owner: Set(true),
+        chain_deployment_id: Set(None),
     };
 
     admin_wallet
@@ -93,3 +100,60 @@ pub async fn initialize_admin_user(db: &DatabaseConnection) -> Result<()> {
 
     Ok(())
 }
+
+/// Fail bulk imports left pending or running by a previous process. Their rows
+/// were only held in memory, so they cannot be resumed.
+pub async fn fail_interrupted_import_jobs(db: &DatabaseConnection) -> Result<()> {
+    let now = Utc::now().naive_utc();
+    let result = import_job::Entity::update_many()
+        .col_expr(import_job::Column::Status, Expr::value(IMPORT_FAILED))
+        .col_expr(
+            import_job::Column::Error,
+            Expr::value("Interrupted by a server restart"),
+        )
+        .col_expr(import_job::Column::FinishedAt, Expr::value(now))
+        .col_expr(import_job::Column::UpdatedAt, Expr::value(now))
+        .filter(import_job::Column::Status.is_in([IMPORT_PENDING, IMPORT_RUNNING]))
+        .exec(db)
+        .await
+        .context("Failed to update interrupted import jobs")?;
+
+    if result.rows_affected > 0 {
+        tracing::warn!(
+            "Marked {} interrupted import jobs as failed",
+            result.rows_affected
+        );
+    }
+
+    Ok(())
+}
+
+/// Fail student migrations left pending or running by a previous process.
+/// Already copied students are skipped, so the migration can be started again.
+pub async fn fail_interrupted_student_migration_jobs(db: &DatabaseConnection) -> Result<()> {
+    let now = Utc::now().naive_utc();
+    let result = student_migration_job::Entity::update_many()
+        .col_expr(
+            student_migration_job::Column::Status,
+            Expr::value(MIGRATION_FAILED),
+        )
+        .col_expr(
+            student_migration_job::Column::Error,
+            Expr::value("Interrupted by a server restart"),
+        )
+        .col_expr(student_migration_job::Column::FinishedAt, Expr::value(now))
+        .col_expr(student_migration_job::Column::UpdatedAt, Expr::value(now))
+        .filter(student_migration_job::Column::Status.is_in([MIGRATION_PENDING, MIGRATION_RUNNING]))
+        .exec(db)
+        .await
+        .context("Failed to update interrupted student migration jobs")?;
+
+    if result.rows_affected > 0 {
+        tracing::warn!(
+            "Marked {} interrupted student migration jobs as failed",
+            result.rows_affected
+        );
+    }
+
+    Ok(())
+}
@@ -1,7 +1,25 @@
-use clap::Parser;
-use once_cell::sync::Lazy;
+use clap::{Parser, ValueEnum};
+use once_cell::sync::{Lazy, OnceCell};
 
-pub static APP_CONFIG: Lazy<Config> = Lazy::new(Config::parse);
+static CONFIG_OVERRIDE: OnceCell<Config> = OnceCell::new();
+
+pub static APP_CONFIG: Lazy<Config> =
+    Lazy::new(|| CONFIG_OVERRIDE.get().cloned().unwrap_or_else(Config::parse));
+
+/// Use `config` instead of parsing the command line (e.g. from integration tests,
+/// where the test harness owns the arguments). Must run before `APP_CONFIG` is first read.
+pub fn set_app_config(config: Config) -> Result<(), Config> {
+    CONFIG_OVERRIDE.set(config)
+}
+
+/// Which implementation handlers use to talk to the DataStorage contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BlockchainBackend {
+    /// Real RPC node via ethers
+    Ethers,
+    /// In-memory contract state, no node required
+    Memory,
+}
 
 #[derive(Debug, Parser, Clone)]
 pub struct Config {
@@ -17,6 +35,9 @@ pub struct Config {
     #[clap(long, env)]
     pub database_url: String,
 
+    #[clap(long, env, value_enum, default_value_t = BlockchainBackend::Ethers)]
+    pub blockchain_backend: BlockchainBackend,
+
     #[clap(long, env)]
     pub blockchain_rpc_url: String,
 
@@ -25,4 +46,98 @@ pub struct Config {
 
     #[clap(long, env)]
     pub admin_private_key: String,
+
+    /// Upper bound for EIP-1559 maxFeePerGas (gwei)
+    #[clap(long, env, default_value_t = 100)]
+    pub gas_max_fee_per_gas_gwei: u64,
+
+    /// Upper bound for EIP-1559 maxPriorityFeePerGas (gwei)
+    #[clap(long, env, default_value_t = 3)]
+    pub gas_max_priority_fee_gwei: u64,
+
+    /// Maximum fee a single transaction may spend (ETH)
+    #[clap(long, env, default_value = "0.05")]
+    pub gas_max_tx_fee_eth: String,
+
+    /// Maximum total fees a single signer may spend per UTC day (ETH)
+    #[clap(long, env, default_value = "1")]
+    pub gas_max_daily_fee_eth: String,
+
+    /// Safety margin added on top of estimated gas limits (percent)
+    #[clap(long, env, default_value_t = 20)]
+    pub gas_limit_margin_percent: u64,
+
+    /// Fee bump applied when replacing a stuck transaction (percent, min 10)
+    #[clap(long, env, default_value_t = 20)]
+    pub gas_replacement_bump_percent: u64,
+
+    /// Seconds to wait for a confirmation before a transaction is considered stuck
+    #[clap(long, env, default_value_t = 180)]
+    pub gas_confirmation_timeout_secs: u64,
+
+    /// Consecutive RPC failures before the chain circuit breaker opens
+    #[clap(long, env, default_value_t = 3)]
+    pub chain_failure_threshold: u32,
+
+    /// Seconds the circuit breaker stays open before a trial request is allowed
+    #[clap(long, env, default_value_t = 30)]
+    pub chain_circuit_open_secs: u64,
+
+    /// Seconds between background RPC node health checks
+    #[clap(long, env, default_value_t = 15)]
+    pub chain_health_check_interval_secs: u64,
+
+    /// Seconds between polls of the queued chain job table
+    #[clap(long, env, default_value_t = 5)]
+    pub chain_job_poll_interval_secs: u64,
+
+    /// Attempts before a queued chain job is marked failed
+    #[clap(long, env, default_value_t = 10)]
+    pub chain_job_max_attempts: i32,
+
+    /// Seconds per-address contract view results are cached (0 disables)
+    #[clap(long, env, default_value_t = 60)]
+    pub chain_cache_ttl_secs: u64,
+
+    /// Seconds contract-wide view results (owner, counts, manager list) are cached (0 disables)
+    #[clap(long, env, default_value_t = 15)]
+    pub chain_cache_summary_ttl_secs: u64,
+
+    /// Seconds between polls of contract events used to invalidate the view cache
+    #[clap(long, env, default_value_t = 10)]
+    pub chain_event_poll_interval_secs: u64,
+
+    /// Seconds between syncs of the stored student codes and statuses with every
+    /// active deployment, for changes made outside this service
+    #[clap(long, env, default_value_t = 300)]
+    pub chain_status_sync_interval_secs: u64,
+
+    /// Private key student credentials are signed with; must differ from ADMIN_PRIVATE_KEY
+    #[clap(long, env)]
+    pub credential_signing_key: Option<String>,
+
+    /// EIP-712 domain name of student credentials
+    #[clap(long, env, default_value = "Student Identity Credential")]
+    pub credential_domain_name: String,
+
+    /// Days a student credential stays valid after issuance
+    #[clap(long, env, default_value_t = 365)]
+    pub credential_ttl_days: u64,
+
+    /// Private key student QR passes are signed with; must differ from ADMIN_PRIVATE_KEY
+    #[clap(long, env)]
+    pub student_pass_signing_key: Option<String>,
+
+    /// Seconds a student QR pass stays valid after it is shown
+    #[clap(long, env, default_value_t = 120)]
+    pub student_pass_ttl_secs: u64,
+
+    /// Bulk user imports processed at the same time; further imports wait as pending
+    #[clap(long, env, default_value_t = 2)]
+    pub import_max_concurrent_jobs: usize,
+
+    /// Users a single export may contain; larger exports must be narrowed with filters.
+    /// Exports are built in memory, so this also bounds the memory one export takes.
+    #[clap(long, env, default_value_t = 20000)]
+    pub export_max_rows: u64,
 }
@@ -12,14 +12,29 @@ pub struct Model {
     pub dean: String,
     pub create_at: DateTime,
     pub update_at: DateTime,
+    pub chain_deployment_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::chain_deployment::Entity",
+        from = "Column::ChainDeploymentId",
+        to = "super::chain_deployment::Column::DeploymentId",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    ChainDeployment,
     #[sea_orm(has_many = "super::major::Entity")]
     Major,
 }
 
+impl Related<super::chain_deployment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChainDeployment.def()
+    }
+}
+
 impl Related<super::major::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Major.def()
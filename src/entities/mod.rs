@@ -2,9 +2,16 @@
 
 pub mod prelude;
 
+pub mod chain_deployment;
+pub mod chain_job;
 pub mod department;
+pub mod import_job;
 pub mod major;
+pub mod merkle_batch;
+pub mod merkle_proof;
 pub mod sea_orm_active_enums;
+pub mod student_chain_status;
+pub mod student_migration_job;
 pub mod user;
 pub mod user_major;
 pub mod wallet;
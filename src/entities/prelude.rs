@@ -1,7 +1,14 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+pub use super::chain_deployment::Entity as ChainDeployment;
+pub use super::chain_job::Entity as ChainJob;
 pub use super::department::Entity as Department;
+pub use super::import_job::Entity as ImportJob;
 pub use super::major::Entity as Major;
+pub use super::merkle_batch::Entity as MerkleBatch;
+pub use super::merkle_proof::Entity as MerkleProof;
+pub use super::student_chain_status::Entity as StudentChainStatus;
+pub use super::student_migration_job::Entity as StudentMigrationJob;
 pub use super::user::Entity as User;
 pub use super::user_major::Entity as UserMajor;
 pub use super::wallet::Entity as Wallet;
@@ -25,12 +25,44 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::chain_job::Entity")]
+    ChainJob,
+    #[sea_orm(has_many = "super::import_job::Entity")]
+    ImportJob,
+    #[sea_orm(has_one = "super::merkle_proof::Entity")]
+    MerkleProof,
+    #[sea_orm(has_many = "super::student_migration_job::Entity")]
+    StudentMigrationJob,
     #[sea_orm(has_many = "super::user_major::Entity")]
     UserMajor,
     #[sea_orm(has_one = "super::wallet::Entity")]
     Wallet,
 }
 
+impl Related<super::chain_job::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChainJob.def()
+    }
+}
+
+impl Related<super::import_job::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ImportJob.def()
+    }
+}
+
+impl Related<super::merkle_proof::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MerkleProof.def()
+    }
+}
+
+impl Related<super::student_migration_job::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StudentMigrationJob.def()
+    }
+}
+
 impl Related<super::user_major::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserMajor.def()
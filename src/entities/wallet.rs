@@ -18,10 +18,20 @@ pub struct Model {
     pub last_used_at: Option<DateTime>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub is_contract_owner: bool,
+    pub chain_deployment_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::chain_deployment::Entity",
+        from = "Column::ChainDeploymentId",
+        to = "super::chain_deployment::Column::DeploymentId",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    ChainDeployment,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::UserId",
@@ -32,6 +42,12 @@ pub enum Relation {
     User,
 }
 
+impl Related<super::chain_deployment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChainDeployment.def()
+    }
+}
+
 impl Related<super::user::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::User.def()
@@ -0,0 +1,235 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::blockchain::{NodeHealth, StudentMigrationReport};
+use crate::entities::{chain_deployment, chain_job, student_migration_job};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateChainDeploymentRequest {
+    #[schema(example = "sepolia-v2")]
+    pub name: String,
+    #[schema(example = 11155111)]
+    pub chain_id: u64,
+    #[schema(example = "https://sepolia.infura.io/v3/YOUR_INFURA_KEY")]
+    pub rpc_url: String,
+    pub contract_address: String,
+    /// Make this the deployment used when no wallet or department override applies
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChainDeploymentResponse {
+    pub deployment_id: Uuid,
+    pub name: String,
+    pub chain_id: u64,
+    pub rpc_url: String,
+    pub contract_address: String,
+    pub is_default: bool,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<chain_deployment::Model> for ChainDeploymentResponse {
+    fn from(deployment: chain_deployment::Model) -> Self {
+        Self {
+            deployment_id: deployment.deployment_id,
+            name: deployment.name,
+            chain_id: deployment.chain_id as u64,
+            rpc_url: deployment.rpc_url,
+            contract_address: deployment.contract_address,
+            is_default: deployment.is_default,
+            is_active: deployment.is_active,
+            created_at: deployment.created_at,
+            updated_at: deployment.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChainDeploymentListResponse {
+    pub deployments: Vec<ChainDeploymentResponse>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AssignDeploymentRequest {
+    /// Deployment to pin, or null to fall back to the department/default deployment
+    pub deployment_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeploymentAssignmentResponse {
+    pub deployment_id: Option<Uuid>,
+    /// Deployment the user or department resolves to after the change
+    pub effective_deployment: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MigrateStudentsRequest {
+    /// Old deployment, or null for the deployment from config
+    pub from_deployment_id: Option<Uuid>,
+    /// Move wallets and departments pinned to the old deployment to the new one
+    #[serde(default = "default_true")]
+    pub reassign: bool,
+    /// Deactivate the old deployment (the new one becomes default if it was);
+    /// not available for the deployment from config
+    #[serde(default)]
+    pub retire_source: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StudentMigrationFailureInfo {
+    pub student_code: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MigrateStudentsResponse {
+    pub from_deployment: String,
+    pub to_deployment: String,
+    pub total: u64,
+    pub migrated: u64,
+    pub skipped: u64,
+    pub deactivated: u64,
+    pub failed: Vec<StudentMigrationFailureInfo>,
+    pub wallets_reassigned: u64,
+    pub departments_reassigned: u64,
+}
+
+impl MigrateStudentsResponse {
+    pub fn new(
+        from_deployment: String,
+        to_deployment: String,
+        report: StudentMigrationReport,
+    ) -> Self {
+        Self {
+            from_deployment,
+            to_deployment,
+            total: report.total,
+            migrated: report.migrated,
+            skipped: report.skipped,
+            deactivated: report.deactivated,
+            failed: report
+                .failed
+                .into_iter()
+                .map(|f| StudentMigrationFailureInfo {
+                    student_code: f.student_code,
+                    error: f.error,
+                })
+                .collect(),
+            wallets_reassigned: 0,
+            departments_reassigned: 0,
+        }
+    }
+}
+
+/// Background copy of student registrations between deployments
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StudentMigrationJobResponse {
+    pub job_id: Uuid,
+    /// `pending`, `running`, `completed` or `failed`
+    pub status: String,
+    /// Old deployment, or null for the deployment from config
+    pub from_deployment_id: Option<Uuid>,
+    pub to_deployment_id: Uuid,
+    pub reassign: bool,
+    pub retire_source: bool,
+    /// Outcome, once the job completed
+    pub report: Option<MigrateStudentsResponse>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub started_at: Option<NaiveDateTime>,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+impl From<student_migration_job::Model> for StudentMigrationJobResponse {
+    fn from(job: student_migration_job::Model) -> Self {
+        Self {
+            job_id: job.job_id,
+            status: job.status,
+            from_deployment_id: job.from_deployment_id,
+            to_deployment_id: job.to_deployment_id,
+            reassign: job.reassign,
+            retire_source: job.retire_source,
+            report: job
+                .report
+                .and_then(|report| serde_json::from_value(report).ok()),
+            error: job.error,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            finished_at: job.finished_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChainHealthResponse {
+    /// False if any monitored node's circuit breaker is open
+    pub available: bool,
+    pub nodes: Vec<NodeHealth>,
+    pub pending_jobs: u64,
+    pub failed_jobs: u64,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ChainJobQueryParams {
+    /// pending, processing, done or failed
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChainJobResponse {
+    pub job_id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub deployment_id: Option<Uuid>,
+    /// Signing user, null for the contract owner wallet
+    pub signer_user_id: Option<Uuid>,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    /// Transaction that may already have been sent, checked before sending again
+    pub tx_hash: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<chain_job::Model> for ChainJobResponse {
+    fn from(job: chain_job::Model) -> Self {
+        Self {
+            job_id: job.job_id,
+            kind: job.kind,
+            payload: job.payload,
+            deployment_id: job.deployment_id,
+            signer_user_id: job.signer_user_id,
+            status: job.status,
+            attempts: job.attempts,
+            last_error: job.last_error,
+            next_attempt_at: job.next_attempt_at,
+            tx_hash: job.tx_hash,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChainJobListResponse {
+    pub jobs: Vec<ChainJobResponse>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ClearChainCacheResponse {
+    /// Cached view results that were dropped
+    pub removed: usize,
+}
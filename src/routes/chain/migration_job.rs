@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::dto::MigrateStudentsResponse;
+use crate::blockchain::{
+    ChainTarget, get_admin_chain_backend, get_default_deployment, migrate_students,
+};
+use crate::entities::{
+    chain_deployment, department, major, student_migration_job, user_major, wallet,
+};
+
+pub const MIGRATION_PENDING: &str = "pending";
+pub const MIGRATION_RUNNING: &str = "running";
+pub const MIGRATION_COMPLETED: &str = "completed";
+pub const MIGRATION_FAILED: &str = "failed";
+
+/// Store a pending migration from `from` to `to`
+pub async fn create_migration_job(
+    db: &DatabaseConnection,
+    created_by: Uuid,
+    from: &ChainTarget,
+    to_deployment_id: Uuid,
+    reassign: bool,
+    retire_source: bool,
+) -> Result<student_migration_job::Model> {
+    let now = Utc::now().naive_utc();
+
+    student_migration_job::ActiveModel {
+        job_id: Set(Uuid::new_v4()),
+        created_by: Set(Some(created_by)),
+        from_deployment_id: Set(from.deployment_id),
+        to_deployment_id: Set(to_deployment_id),
+        reassign: Set(reassign),
+        retire_source: Set(retire_source),
+        status: Set(MIGRATION_PENDING.to_string()),
+        report: Set(None),
+        error: Set(None),
+        created_at: Set(now),
+        started_at: Set(None),
+        finished_at: Set(None),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .context("Failed to create student migration job")
+}
+
+/// Run a stored migration in the background
+pub fn spawn_migration_job(
+    db: &'static DatabaseConnection,
+    job: student_migration_job::Model,
+    from: ChainTarget,
+    to: ChainTarget,
+) {
+    tokio::spawn(async move {
+        let job_id = job.job_id;
+
+        let outcome = run_migration(db, job.clone(), &from, &to).await;
+        if let Err(e) = &outcome {
+            tracing::error!("Student migration job {} failed: {}", job_id, e);
+        }
+        if let Err(e) = finish(db, job, outcome).await {
+            tracing::error!("Failed to record student migration job {}: {}", job_id, e);
+        }
+    });
+}
+
+async fn run_migration(
+    db: &DatabaseConnection,
+    job: student_migration_job::Model,
+    from: &ChainTarget,
+    to: &ChainTarget,
+) -> Result<MigrateStudentsResponse> {
+    let now = Utc::now().naive_utc();
+    let mut active_model: student_migration_job::ActiveModel = job.clone().into();
+    active_model.status = Set(MIGRATION_RUNNING.to_string());
+    active_model.started_at = Set(Some(now));
+    active_model.updated_at = Set(now);
+    active_model
+        .update(db)
+        .await
+        .context("Failed to start student migration job")?;
+
+    let from_backend = get_admin_chain_backend(db, from).await?;
+    let to_backend = get_admin_chain_backend(db, to).await?;
+    let report = migrate_students(from_backend.as_ref(), to_backend.as_ref()).await?;
+
+    let mut response = MigrateStudentsResponse::new(from.name.clone(), to.name.clone(), report);
+
+    // Only re-point wallets once every registration made it across
+    if response.failed.is_empty() {
+        move_to_target(db, &job, from, &mut response).await?;
+    }
+
+    Ok(response)
+}
+
+/// Re-point what used the source deployment to the target and retire the source,
+/// as requested by the job
+async fn move_to_target(
+    db: &DatabaseConnection,
+    job: &student_migration_job::Model,
+    from: &ChainTarget,
+    response: &mut MigrateStudentsResponse,
+) -> Result<()> {
+    let to_id = job.to_deployment_id;
+    // Checked before retiring the source hands the default over
+    let source_is_default = get_default_deployment(db).await? == *from;
+    let retire_source = match from.deployment_id {
+        Some(from_id) if job.retire_source => Some(from_id),
+        _ => None,
+    };
+
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await.context("Failed to start transaction")?;
+
+    if job.reassign {
+        if let Some(from_id) = from.deployment_id {
+            response.wallets_reassigned += wallet::Entity::update_many()
+                .col_expr(wallet::Column::ChainDeploymentId, Expr::value(to_id))
+                .col_expr(wallet::Column::UpdatedAt, Expr::value(now))
+                .filter(wallet::Column::ChainDeploymentId.eq(from_id))
+                .exec(&txn)
+                .await
+                .context("Failed to reassign wallets")?
+                .rows_affected;
+
+            response.departments_reassigned = department::Entity::update_many()
+                .col_expr(department::Column::ChainDeploymentId, Expr::value(to_id))
+                .col_expr(department::Column::UpdateAt, Expr::value(now))
+                .filter(department::Column::ChainDeploymentId.eq(from_id))
+                .exec(&txn)
+                .await
+                .context("Failed to reassign departments")?
+                .rows_affected;
+        }
+
+        // Wallets without a deployment of their own follow the default, which
+        // only moves along when the source is retired
+        if source_is_default && retire_source.is_none() {
+            response.wallets_reassigned += wallet::Entity::update_many()
+                .col_expr(wallet::Column::ChainDeploymentId, Expr::value(to_id))
+                .col_expr(wallet::Column::UpdatedAt, Expr::value(now))
+                .filter(follows_default_deployment())
+                .exec(&txn)
+                .await
+                .context("Failed to reassign wallets")?
+                .rows_affected;
+        }
+    }
+
+    if let Some(from_id) = retire_source {
+        let from_deployment = chain_deployment::Entity::find_by_id(from_id)
+            .one(&txn)
+            .await
+            .context("Failed to query chain deployment")?
+            .ok_or_else(|| anyhow::anyhow!("Chain deployment not found"))?;
+        let was_default = from_deployment.is_default;
+
+        let mut active_model: chain_deployment::ActiveModel = from_deployment.into();
+        active_model.is_active = Set(false);
+        active_model.is_default = Set(false);
+        active_model.updated_at = Set(now);
+        active_model
+            .update(&txn)
+            .await
+            .context("Failed to retire deployment")?;
+
+        if was_default {
+            chain_deployment::Entity::update_many()
+                .col_expr(chain_deployment::Column::IsDefault, Expr::value(true))
+                .col_expr(chain_deployment::Column::UpdatedAt, Expr::value(now))
+                .filter(chain_deployment::Column::DeploymentId.eq(to_id))
+                .exec(&txn)
+                .await
+                .context("Failed to update default deployment")?;
+        }
+    }
+
+    txn.commit().await.context("Failed to commit transaction")?;
+
+    Ok(())
+}
+
+/// Wallets resolved to the default deployment: not pinned to an active
+/// deployment, and with no major in a department pinned to one
+fn follows_default_deployment() -> Condition {
+    let active_deployments = Query::select()
+        .column(chain_deployment::Column::DeploymentId)
+        .from(chain_deployment::Entity)
+        .and_where(chain_deployment::Column::IsActive.eq(true))
+        .to_owned();
+
+    let department_users = Query::select()
+        .column((user_major::Entity, user_major::Column::UserId))
+        .from(user_major::Entity)
+        .inner_join(
+            major::Entity,
+            Expr::col((major::Entity, major::Column::MajorId))
+                .equals((user_major::Entity, user_major::Column::MajorId)),
+        )
+        .inner_join(
+            department::Entity,
+            Expr::col((department::Entity, department::Column::DepartmentId))
+                .equals((major::Entity, major::Column::DepartmentId)),
+        )
+        .and_where(
+            Expr::col((department::Entity, department::Column::ChainDeploymentId))
+                .in_subquery(active_deployments.clone()),
+        )
+        .to_owned();
+
+    Condition::all()
+        .add(
+            Condition::any()
+                .add(wallet::Column::ChainDeploymentId.is_null())
+                .add(wallet::Column::ChainDeploymentId.not_in_subquery(active_deployments)),
+        )
+        .add(wallet::Column::UserId.not_in_subquery(department_users))
+}
+
+async fn finish(
+    db: &DatabaseConnection,
+    job: student_migration_job::Model,
+    outcome: Result<MigrateStudentsResponse>,
+) -> Result<()> {
+    let now = Utc::now().naive_utc();
+    let mut active_model: student_migration_job::ActiveModel = job.into();
+
+    match outcome {
+        Ok(report) => {
+            active_model.status = Set(MIGRATION_COMPLETED.to_string());
+            active_model.report = Set(Some(
+                serde_json::to_value(&report).context("Failed to encode migration report")?,
+            ));
+        }
+        Err(e) => {
+            active_model.status = Set(MIGRATION_FAILED.to_string());
+            active_model.error = Set(Some(e.to_string()));
+        }
+    }
+    active_model.finished_at = Set(Some(now));
+    active_model.updated_at = Set(now);
+    active_model
+        .update(db)
+        .await
+        .context("Failed to finish student migration job")?;
+
+    Ok(())
+}
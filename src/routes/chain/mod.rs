@@ -0,0 +1,5 @@
+pub mod dto;
+pub mod migration_job;
+pub mod route;
+
+pub use route::create_route;
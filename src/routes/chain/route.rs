@@ -0,0 +1,799 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query},
+    http::StatusCode,
+    routing::{get, post, put},
+};
+use chrono::Utc;
+use ethers::types::Address;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::dto::{
+    AssignDeploymentRequest, ChainDeploymentListResponse, ChainDeploymentResponse,
+    ChainHealthResponse, ChainJobListResponse, ChainJobQueryParams, ChainJobResponse,
+    ClearChainCacheResponse, CreateChainDeploymentRequest, DeploymentAssignmentResponse,
+    MigrateStudentsRequest, StudentMigrationJobResponse,
+};
+use super::migration_job::{create_migration_job, spawn_migration_job};
+use crate::blockchain::health::CircuitState;
+use crate::blockchain::queue::{JOB_FAILED, JOB_PENDING};
+use crate::blockchain::{
+    CacheStats, ChainTarget, cache_stats, check_chain_health, clear_cache,
+    resolve_deployment_for_majors, resolve_user_deployment,
+};
+use crate::config::{APP_CONFIG, BlockchainBackend};
+use crate::entities::{
+    chain_deployment, chain_job, department, major, student_migration_job, wallet,
+};
+use crate::extractor::AuthClaims;
+use crate::middleware::permission;
+use crate::static_service::DATABASE_CONNECTION;
+
+pub fn create_route() -> Router {
+    Router::new()
+        .route(
+            "/api/v1/chain/deployments",
+            get(get_all_deployments).post(create_deployment),
+        )
+        .route(
+            "/api/v1/chain/deployments/{deployment_id}/default",
+            put(set_default_deployment),
+        )
+        .route(
+            "/api/v1/chain/deployments/{deployment_id}/migrate-students",
+            post(migrate_deployment_students),
+        )
+        .route(
+            "/api/v1/chain/users/{user_id}/deployment",
+            put(assign_user_deployment),
+        )
+        .route(
+            "/api/v1/chain/departments/{department_id}/deployment",
+            put(assign_department_deployment),
+        )
+        .route("/api/v1/chain/migrations/{job_id}", get(get_migration_job))
+        .route("/api/v1/chain/health", get(get_chain_health))
+        .route("/api/v1/chain/jobs", get(get_chain_jobs))
+        .route("/api/v1/chain/jobs/{job_id}/retry", post(retry_chain_job))
+        .route(
+            "/api/v1/chain/cache",
+            get(get_chain_cache_stats).delete(clear_chain_cache),
+        )
+}
+
+/// List registered chain deployments (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chain/deployments",
+    responses(
+        (status = 200, description = "Deployments retrieved", body = ChainDeploymentListResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn get_all_deployments(
+    AuthClaims(auth_claims): AuthClaims,
+) -> Result<(StatusCode, Json<ChainDeploymentListResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let deployments = chain_deployment::Entity::find()
+        .order_by_asc(chain_deployment::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get chain deployments: {}", e),
+            )
+        })?;
+
+    let response = ChainDeploymentListResponse {
+        total: deployments.len(),
+        deployments: deployments.into_iter().map(Into::into).collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Register a network / DataStorage deployment (Admin only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chain/deployments",
+    request_body = CreateChainDeploymentRequest,
+    responses(
+        (status = 201, description = "Deployment registered", body = ChainDeploymentResponse),
+        (status = 400, description = "Bad request"),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 409, description = "Deployment name already exists"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn create_deployment(
+    AuthClaims(auth_claims): AuthClaims,
+    Json(payload): Json<CreateChainDeploymentRequest>,
+) -> Result<(StatusCode, Json<ChainDeploymentResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let name = payload.name.trim().to_string();
+    if name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Name is required".to_string()));
+    }
+    let contract_address: Address = payload.contract_address.parse().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid contract address: {}", e),
+        )
+    })?;
+
+    let existing = chain_deployment::Entity::find()
+        .filter(chain_deployment::Column::Name.eq(&name))
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?;
+    if existing.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Deployment '{}' already exists", name),
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
+    if payload.is_default {
+        chain_deployment::Entity::update_many()
+            .col_expr(chain_deployment::Column::IsDefault, Expr::value(false))
+            .col_expr(chain_deployment::Column::UpdatedAt, Expr::value(now))
+            .filter(chain_deployment::Column::IsDefault.eq(true))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to update default deployment: {}", e),
+                )
+            })?;
+    }
+
+    let deployment = chain_deployment::ActiveModel {
+        deployment_id: Set(Uuid::new_v4()),
+        name: Set(name),
+        chain_id: Set(payload.chain_id as i64),
+        rpc_url: Set(payload.rpc_url),
+        contract_address: Set(format!("{:?}", contract_address)),
+        is_default: Set(payload.is_default),
+        is_active: Set(true),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create chain deployment: {}", e),
+        )
+    })?;
+
+    txn.commit().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
+    Ok((StatusCode::CREATED, Json(deployment.into())))
+}
+
+/// Make a deployment the default (Admin only)
+#[utoipa::path(
+    put,
+    path = "/api/v1/chain/deployments/{deployment_id}/default",
+    params(
+        ("deployment_id" = Uuid, Path, description = "Deployment ID")
+    ),
+    responses(
+        (status = 200, description = "Default deployment updated", body = ChainDeploymentResponse),
+        (status = 400, description = "Deployment is inactive"),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Deployment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn set_default_deployment(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(deployment_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ChainDeploymentResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let deployment = find_deployment(db, deployment_id).await?;
+    if !deployment.is_active {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Inactive deployments cannot be the default".to_string(),
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
+    chain_deployment::Entity::update_many()
+        .col_expr(chain_deployment::Column::IsDefault, Expr::value(false))
+        .col_expr(chain_deployment::Column::UpdatedAt, Expr::value(now))
+        .filter(chain_deployment::Column::IsDefault.eq(true))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update default deployment: {}", e),
+            )
+        })?;
+
+    let mut active_model: chain_deployment::ActiveModel = deployment.into();
+    active_model.is_default = Set(true);
+    active_model.updated_at = Set(now);
+    let deployment = active_model.update(&txn).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update default deployment: {}", e),
+        )
+    })?;
+
+    txn.commit().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(deployment.into())))
+}
+
+/// Pin a user's wallet to a deployment (Admin only)
+#[utoipa::path(
+    put,
+    path = "/api/v1/chain/users/{user_id}/deployment",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = AssignDeploymentRequest,
+    responses(
+        (status = 200, description = "Wallet deployment updated", body = DeploymentAssignmentResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Wallet or deployment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn assign_user_deployment(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<AssignDeploymentRequest>,
+) -> Result<(StatusCode, Json<DeploymentAssignmentResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    if let Some(deployment_id) = payload.deployment_id {
+        find_deployment(db, deployment_id).await?;
+    }
+
+    let wallet_info = wallet::Entity::find()
+        .filter(wallet::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
+
+    let mut active_model: wallet::ActiveModel = wallet_info.into();
+    active_model.chain_deployment_id = Set(payload.deployment_id);
+    active_model.updated_at = Set(Utc::now().naive_utc());
+    active_model.update(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update wallet: {}", e),
+        )
+    })?;
+
+    let effective = resolve_user_deployment(db, &user_id).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to resolve chain deployment: {}", e),
+        )
+    })?;
+
+    let response = DeploymentAssignmentResponse {
+        deployment_id: payload.deployment_id,
+        effective_deployment: effective.name,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Assign a department (tenant) to a deployment (Admin only)
+/// Users in the department's majors use it unless their wallet is pinned
+#[utoipa::path(
+    put,
+    path = "/api/v1/chain/departments/{department_id}/deployment",
+    params(
+        ("department_id" = Uuid, Path, description = "Department ID")
+    ),
+    request_body = AssignDeploymentRequest,
+    responses(
+        (status = 200, description = "Department deployment updated", body = DeploymentAssignmentResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Department or deployment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn assign_department_deployment(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(department_id): Path<Uuid>,
+    Json(payload): Json<AssignDeploymentRequest>,
+) -> Result<(StatusCode, Json<DeploymentAssignmentResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    if let Some(deployment_id) = payload.deployment_id {
+        find_deployment(db, deployment_id).await?;
+    }
+
+    let department_info = department::Entity::find_by_id(department_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Department not found".to_string()))?;
+
+    let mut active_model: department::ActiveModel = department_info.into();
+    active_model.chain_deployment_id = Set(payload.deployment_id);
+    active_model.update_at = Set(Utc::now().naive_utc());
+    active_model.update(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update department: {}", e),
+        )
+    })?;
+
+    let major_ids: Vec<Uuid> = major::Entity::find()
+        .filter(major::Column::DepartmentId.eq(department_id))
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .into_iter()
+        .map(|m| m.major_id)
+        .collect();
+
+    let effective = resolve_deployment_for_majors(db, &major_ids)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to resolve chain deployment: {}", e),
+            )
+        })?;
+
+    let response = DeploymentAssignmentResponse {
+        deployment_id: payload.deployment_id,
+        effective_deployment: effective.name,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Copy student registrations from an old deployment to this one (Admin only)
+/// Signs with the contract owner key on both contracts; students already
+/// registered on the new contract are skipped, so the call can be repeated.
+/// Runs in the background; poll `GET /api/v1/chain/migrations/{job_id}`
+#[utoipa::path(
+    post,
+    path = "/api/v1/chain/deployments/{deployment_id}/migrate-students",
+    params(
+        ("deployment_id" = Uuid, Path, description = "New deployment ID")
+    ),
+    request_body = MigrateStudentsRequest,
+    responses(
+        (status = 202, description = "Migration started", body = StudentMigrationJobResponse),
+        (status = 400, description = "Same or inactive target deployment, or retiring the deployment from config"),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Deployment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn migrate_deployment_students(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(deployment_id): Path<Uuid>,
+    Json(payload): Json<MigrateStudentsRequest>,
+) -> Result<(StatusCode, Json<StudentMigrationJobResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let to_deployment = find_deployment(db, deployment_id).await?;
+    if !to_deployment.is_active {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Cannot migrate students to an inactive deployment".to_string(),
+        ));
+    }
+    let to_target = ChainTarget::from(to_deployment.clone());
+    let from_target = match payload.from_deployment_id {
+        Some(from_id) => ChainTarget::from(find_deployment(db, from_id).await?),
+        // The deployment from config has no row to deactivate
+        None if payload.retire_source => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "The deployment from config cannot be retired".to_string(),
+            ));
+        }
+        None => ChainTarget::from_config(),
+    };
+
+    if from_target
+        .contract_address
+        .eq_ignore_ascii_case(&to_target.contract_address)
+        && from_target.rpc_url == to_target.rpc_url
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Source and target are the same deployment".to_string(),
+        ));
+    }
+
+    let caller_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid user ID format: {}", e),
+        )
+    })?;
+
+    let job = create_migration_job(
+        db,
+        caller_id,
+        &from_target,
+        deployment_id,
+        payload.reassign,
+        payload.retire_source,
+    )
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create migration job: {}", e),
+        )
+    })?;
+
+    spawn_migration_job(db, job.clone(), from_target, to_target);
+
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+/// Get a student migration job (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chain/migrations/{job_id}",
+    params(
+        ("job_id" = Uuid, Path, description = "Migration job ID")
+    ),
+    responses(
+        (status = 200, description = "Migration job retrieved", body = StudentMigrationJobResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Migration job not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn get_migration_job(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(job_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<StudentMigrationJobResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let job = student_migration_job::Entity::find_by_id(job_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Migration job not found".to_string()))?;
+
+    Ok((StatusCode::OK, Json(job.into())))
+}
+
+/// Probe the RPC nodes and report circuit breakers and queued chain work (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chain/health",
+    responses(
+        (status = 200, description = "Chain health retrieved", body = ChainHealthResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn get_chain_health(
+    AuthClaims(auth_claims): AuthClaims,
+) -> Result<(StatusCode, Json<ChainHealthResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let nodes = match APP_CONFIG.blockchain_backend {
+        BlockchainBackend::Ethers => check_chain_health(db).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to check chain health: {}", e),
+            )
+        })?,
+        BlockchainBackend::Memory => Vec::new(),
+    };
+
+    let count_jobs = |status: &'static str| {
+        chain_job::Entity::find()
+            .filter(chain_job::Column::Status.eq(status))
+            .count(db)
+    };
+    let pending_jobs = count_jobs(JOB_PENDING).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+    let failed_jobs = count_jobs(JOB_FAILED).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
+    let response = ChainHealthResponse {
+        available: nodes.iter().all(|node| node.state != CircuitState::Open),
+        nodes,
+        pending_jobs,
+        failed_jobs,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// List chain writes queued while a node was unavailable (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chain/jobs",
+    params(ChainJobQueryParams),
+    responses(
+        (status = 200, description = "Chain jobs retrieved", body = ChainJobListResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn get_chain_jobs(
+    AuthClaims(auth_claims): AuthClaims,
+    Query(params): Query<ChainJobQueryParams>,
+) -> Result<(StatusCode, Json<ChainJobListResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let mut query = chain_job::Entity::find().order_by_asc(chain_job::Column::CreatedAt);
+    if let Some(status) = params.status {
+        query = query.filter(chain_job::Column::Status.eq(status));
+    }
+
+    let jobs = query.all(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to get chain jobs: {}", e),
+        )
+    })?;
+
+    let response = ChainJobListResponse {
+        total: jobs.len(),
+        jobs: jobs.into_iter().map(Into::into).collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Requeue a failed chain job (Admin only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chain/jobs/{job_id}/retry",
+    params(
+        ("job_id" = Uuid, Path, description = "Chain job ID")
+    ),
+    responses(
+        (status = 200, description = "Job requeued", body = ChainJobResponse),
+        (status = 400, description = "Job has not failed"),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Job not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn retry_chain_job(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(job_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ChainJobResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let job = chain_job::Entity::find_by_id(job_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Chain job not found".to_string()))?;
+
+    if job.status != JOB_FAILED {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Only failed jobs can be retried (job is {})", job.status),
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+    let mut active_model: chain_job::ActiveModel = job.into();
+    active_model.status = Set(JOB_PENDING.to_string());
+    active_model.attempts = Set(0);
+    active_model.next_attempt_at = Set(now);
+    active_model.updated_at = Set(now);
+    let job = active_model.update(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update chain job: {}", e),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(job.into())))
+}
+
+/// Report hit/miss counters of the contract view call cache (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chain/cache",
+    responses(
+        (status = 200, description = "Cache statistics retrieved", body = CacheStats),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn get_chain_cache_stats(
+    AuthClaims(auth_claims): AuthClaims,
+) -> Result<(StatusCode, Json<CacheStats>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    Ok((StatusCode::OK, Json(cache_stats())))
+}
+
+/// Drop all cached contract view results (Admin only)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chain/cache",
+    responses(
+        (status = 200, description = "Cache cleared", body = ClearChainCacheResponse),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Chain"
+)]
+pub async fn clear_chain_cache(
+    AuthClaims(auth_claims): AuthClaims,
+) -> Result<(StatusCode, Json<ClearChainCacheResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let removed = clear_cache();
+    tracing::info!("Cleared {} cached contract view results", removed);
+
+    Ok((StatusCode::OK, Json(ClearChainCacheResponse { removed })))
+}
+
+async fn find_deployment(
+    db: &DatabaseConnection,
+    deployment_id: Uuid,
+) -> Result<chain_deployment::Model, (StatusCode, String)> {
+    chain_deployment::Entity::find_by_id(deployment_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                "Chain deployment not found".to_string(),
+            )
+        })
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ContractOwnerResponse {
+    pub owner_address: String,
+    /// DB user holding the owner key (None if the owner wallet is not managed by this service)
+    pub owner_user_id: Option<Uuid>,
+    pub factory_contract: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransferOwnershipRequest {
+    /// Admin user whose wallet becomes the new contract owner
+    pub new_owner_user_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ContractAddressRequest {
+    pub contract_address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuthorizedContractResponse {
+    pub contract_address: String,
+    pub is_authorized: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuthorizedContractInfo {
+    pub contract_address: String,
+    pub authorized_at_block: u64,
+    pub transaction_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuthorizedContractListResponse {
+    pub contracts: Vec<AuthorizedContractInfo>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReplaceTransactionResponse {
+    pub original_tx_hash: String,
+    pub replacement_tx_hash: String,
+    /// True if the original call was cancelled rather than re-sent
+    pub cancelled: bool,
+}
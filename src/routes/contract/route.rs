@@ -0,0 +1,510 @@
+use axum::{
+    Json, Router,
+    extract::Path,
+    http::StatusCode,
+    routing::{get, post},
+};
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, TransactionTrait};
+use uuid::Uuid;
+
+use super::dto::{
+    AuthorizedContractInfo, AuthorizedContractListResponse, AuthorizedContractResponse,
+    ContractAddressRequest, ContractOwnerResponse, ReplaceTransactionResponse,
+    TransferOwnershipRequest,
+};
+use crate::blockchain::{
+    get_admin_blockchain_service, get_user_blockchain_service, map_blockchain_error,
+};
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{user, wallet};
+use crate::extractor::AuthClaims;
+use crate::middleware::permission;
+use crate::static_service::DATABASE_CONNECTION;
+
+pub fn create_route() -> Router {
+    Router::new()
+        .route(
+            "/api/v1/contract/owner",
+            get(get_contract_owner).put(transfer_ownership),
+        )
+        .route(
+            "/api/v1/contract/authorized-contracts",
+            post(authorize_contract)
+                .delete(unauthorize_contract)
+                .get(get_authorized_contracts),
+        )
+        .route(
+            "/api/v1/contract/transactions/{tx_hash}/speed-up",
+            post(speed_up_transaction),
+        )
+        .route(
+            "/api/v1/contract/transactions/{tx_hash}/cancel",
+            post(cancel_transaction),
+        )
+}
+
+/// Get contract owner and factory contract (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/contract/owner",
+    responses(
+        (status = 200, description = "Contract owner retrieved", body = ContractOwnerResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Contract"
+)]
+pub async fn get_contract_owner(
+    AuthClaims(auth_claims): AuthClaims,
+) -> Result<(StatusCode, Json<ContractOwnerResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let blockchain = get_admin_blockchain_service(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to initialize blockchain service: {}", e),
+        )
+    })?;
+
+    let owner_address = blockchain.get_owner().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to get contract owner: {}", e),
+        )
+    })?;
+
+    let factory_contract = blockchain.get_factory_contract().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to get factory contract: {}", e),
+        )
+    })?;
+
+    let owner_wallet = track_owner_wallet(db, &owner_address).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update owner wallet: {}", e),
+        )
+    })?;
+
+    let response = ContractOwnerResponse {
+        owner_address,
+        owner_user_id: owner_wallet.map(|w| w.user_id),
+        factory_contract,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Transfer contract ownership to another admin (Admin only)
+/// Signed with the owner key; the new owner's wallet then holds it for admin
+/// operations. If the transfer times out, reading the owner once it is mined
+/// moves the key there.
+#[utoipa::path(
+    put,
+    path = "/api/v1/contract/owner",
+    request_body = TransferOwnershipRequest,
+    responses(
+        (status = 200, description = "Ownership transferred", body = ContractOwnerResponse),
+        (status = 400, description = "Bad request"),
+        (status = 403, description = "Forbidden - Admin only, or the owner key is not held by this service"),
+        (status = 404, description = "User or wallet not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Contract"
+)]
+pub async fn transfer_ownership(
+    AuthClaims(auth_claims): AuthClaims,
+    Json(payload): Json<TransferOwnershipRequest>,
+) -> Result<(StatusCode, Json<ContractOwnerResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    // The new owner must be an admin with a managed wallet
+    let new_owner = user::Entity::find_by_id(payload.new_owner_user_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    if new_owner.role != RoleEnum::Admin {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Contract ownership can only be transferred to an admin".to_string(),
+        ));
+    }
+
+    let new_owner_wallet = wallet::Entity::find()
+        .filter(wallet::Column::UserId.eq(new_owner.user_id))
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
+
+    let mut blockchain = get_admin_blockchain_service(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to initialize blockchain service: {}", e),
+        )
+    })?;
+
+    // Only the owner key can sign changeOwner; a transfer that was mined after
+    // timing out leaves the owner flag on the previous wallet
+    let owner_address = blockchain.get_owner().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to get contract owner: {}", e),
+        )
+    })?;
+    if !owner_address.eq_ignore_ascii_case(&blockchain.signer_address()) {
+        let owner_wallet = track_owner_wallet(db, &owner_address).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update owner wallet: {}", e),
+            )
+        })?;
+        if owner_wallet.is_none() {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "The contract owner key is not held by this service".to_string(),
+            ));
+        }
+        blockchain = get_admin_blockchain_service(db).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to initialize blockchain service: {}", e),
+            )
+        })?;
+    }
+
+    blockchain
+        .change_owner(&new_owner_wallet.address)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to change owner", e))?;
+
+    track_owner_wallet(db, &new_owner_wallet.address)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update owner wallet: {}", e),
+            )
+        })?;
+
+    let factory_contract = blockchain.get_factory_contract().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to get factory contract: {}", e),
+        )
+    })?;
+
+    let response = ContractOwnerResponse {
+        owner_address: new_owner_wallet.address,
+        owner_user_id: Some(new_owner.user_id),
+        factory_contract,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Authorize a dependent contract (Admin only)
+/// Signed with the contract owner key
+#[utoipa::path(
+    post,
+    path = "/api/v1/contract/authorized-contracts",
+    request_body = ContractAddressRequest,
+    responses(
+        (status = 200, description = "Contract authorized", body = AuthorizedContractResponse),
+        (status = 403, description = "Forbidden - Admin only, or rejected by the smart contract"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Contract"
+)]
+pub async fn authorize_contract(
+    AuthClaims(auth_claims): AuthClaims,
+    Json(payload): Json<ContractAddressRequest>,
+) -> Result<(StatusCode, Json<AuthorizedContractResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let blockchain = get_admin_blockchain_service(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to initialize blockchain service: {}", e),
+        )
+    })?;
+
+    blockchain
+        .authorize_contract(&payload.contract_address)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to authorize contract", e))?;
+
+    let response = AuthorizedContractResponse {
+        contract_address: payload.contract_address,
+        is_authorized: true,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Revoke authorization of a dependent contract (Admin only)
+/// Signed with the contract owner key
+#[utoipa::path(
+    delete,
+    path = "/api/v1/contract/authorized-contracts",
+    request_body = ContractAddressRequest,
+    responses(
+        (status = 200, description = "Contract unauthorized", body = AuthorizedContractResponse),
+        (status = 403, description = "Forbidden - Admin only, or rejected by the smart contract"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Contract"
+)]
+pub async fn unauthorize_contract(
+    AuthClaims(auth_claims): AuthClaims,
+    Json(payload): Json<ContractAddressRequest>,
+) -> Result<(StatusCode, Json<AuthorizedContractResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let blockchain = get_admin_blockchain_service(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to initialize blockchain service: {}", e),
+        )
+    })?;
+
+    blockchain
+        .unauthorize_contract(&payload.contract_address)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to unauthorize contract", e))?;
+
+    let response = AuthorizedContractResponse {
+        contract_address: payload.contract_address,
+        is_authorized: false,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// List authorized contracts from indexed events (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/contract/authorized-contracts",
+    responses(
+        (status = 200, description = "Authorized contracts retrieved", body = AuthorizedContractListResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Contract"
+)]
+pub async fn get_authorized_contracts(
+    AuthClaims(auth_claims): AuthClaims,
+) -> Result<(StatusCode, Json<AuthorizedContractListResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let blockchain = get_admin_blockchain_service(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to initialize blockchain service: {}", e),
+        )
+    })?;
+
+    let contracts = blockchain.get_authorized_contracts().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to get authorized contracts: {}", e),
+        )
+    })?;
+
+    let response = AuthorizedContractListResponse {
+        total: contracts.len(),
+        contracts: contracts
+            .into_iter()
+            .map(|c| AuthorizedContractInfo {
+                contract_address: c.contract_address,
+                authorized_at_block: c.authorized_at_block,
+                transaction_hash: c.transaction_hash,
+            })
+            .collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Speed up a stuck transaction sent from the caller's wallet (Admin/Manager only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/contract/transactions/{tx_hash}/speed-up",
+    params(
+        ("tx_hash" = String, Path, description = "Hash of the pending transaction")
+    ),
+    responses(
+        (status = 200, description = "Replacement transaction sent", body = ReplaceTransactionResponse),
+        (status = 403, description = "Forbidden - Admin/Manager only"),
+        (status = 503, description = "Replacement would exceed a fee cap"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Contract"
+)]
+pub async fn speed_up_transaction(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(tx_hash): Path<String>,
+) -> Result<(StatusCode, Json<ReplaceTransactionResponse>), (StatusCode, String)> {
+    permission::is_admin_or_manager(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let user_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid user_id: {}", e),
+        )
+    })?;
+    let blockchain = get_user_blockchain_service(db, &user_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to initialize blockchain service: {}", e),
+            )
+        })?;
+
+    let replacement_tx_hash = blockchain
+        .speed_up_transaction(&tx_hash)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to speed up transaction", e))?;
+
+    let response = ReplaceTransactionResponse {
+        original_tx_hash: tx_hash,
+        replacement_tx_hash,
+        cancelled: false,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Cancel a stuck transaction sent from the caller's wallet (Admin/Manager only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/contract/transactions/{tx_hash}/cancel",
+    params(
+        ("tx_hash" = String, Path, description = "Hash of the pending transaction")
+    ),
+    responses(
+        (status = 200, description = "Cancellation transaction sent", body = ReplaceTransactionResponse),
+        (status = 403, description = "Forbidden - Admin/Manager only"),
+        (status = 503, description = "Cancellation would exceed a fee cap"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Contract"
+)]
+pub async fn cancel_transaction(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(tx_hash): Path<String>,
+) -> Result<(StatusCode, Json<ReplaceTransactionResponse>), (StatusCode, String)> {
+    permission::is_admin_or_manager(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let user_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid user_id: {}", e),
+        )
+    })?;
+    let blockchain = get_user_blockchain_service(db, &user_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to initialize blockchain service: {}", e),
+            )
+        })?;
+
+    let replacement_tx_hash = blockchain
+        .cancel_transaction(&tx_hash)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to cancel transaction", e))?;
+
+    let response = ReplaceTransactionResponse {
+        original_tx_hash: tx_hash,
+        replacement_tx_hash,
+        cancelled: true,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Mark the managed wallet at `owner_address` as the one holding the contract
+/// owner key, returning it (None if the owner wallet is not managed here)
+async fn track_owner_wallet(
+    db: &DatabaseConnection,
+    owner_address: &str,
+) -> Result<Option<wallet::Model>, DbErr> {
+    let Some(owner_wallet) = wallet::Entity::find()
+        .filter(
+            Expr::expr(Func::lower(Expr::col(wallet::Column::Address)))
+                .eq(owner_address.to_lowercase()),
+        )
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+    if owner_wallet.is_contract_owner {
+        return Ok(Some(owner_wallet));
+    }
+
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await?;
+    wallet::Entity::update_many()
+        .col_expr(wallet::Column::IsContractOwner, Expr::value(false))
+        .col_expr(wallet::Column::UpdatedAt, Expr::value(now))
+        .filter(wallet::Column::IsContractOwner.eq(true))
+        .exec(&txn)
+        .await?;
+    wallet::Entity::update_many()
+        .col_expr(wallet::Column::IsContractOwner, Expr::value(true))
+        .col_expr(wallet::Column::UpdatedAt, Expr::value(now))
+        .filter(wallet::Column::WalletId.eq(owner_wallet.wallet_id))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+
+    Ok(Some(owner_wallet))
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::blockchain::{ChainStatus, StudentCredential};
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct IssueCredentialRequest {
+    /// Student to issue for (Admin/Manager only); defaults to the caller
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CredentialResponse {
+    pub credential: StudentCredential,
+    /// Full EIP-712 payload (`domain`, `types`, `primaryType`, `message`), as passed
+    /// to `eth_signTypedData_v4`
+    #[schema(value_type = Object)]
+    pub typed_data: serde_json::Value,
+    pub signature: String,
+    /// Institution address the signature recovers to
+    pub issuer: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyCredentialRequest {
+    /// EIP-712 payload as returned at issuance
+    #[schema(value_type = Object)]
+    pub typed_data: serde_json::Value,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyCredentialResponse {
+    /// Signed by the institution, not expired and the student is active on-chain
+    pub valid: bool,
+    pub signature_valid: bool,
+    pub expired: bool,
+    /// Current `isActiveStudent` status of the credential's wallet; null while the node is unavailable
+    pub is_active: Option<bool>,
+    pub issuer: String,
+    /// Address the signature recovers to
+    pub signer: Option<String>,
+    pub credential: StudentCredential,
+    /// `unavailable` if the student status could not be checked because the node is down
+    pub chain_status: ChainStatus,
+}
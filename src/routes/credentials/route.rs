@@ -0,0 +1,250 @@
+use axum::{Json, Router, http::StatusCode, routing::post};
+use chrono::Utc;
+use ethers::types::transaction::eip712::TypedData;
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
+use uuid::Uuid;
+
+use super::dto::{
+    CredentialResponse, IssueCredentialRequest, VerifyCredentialRequest, VerifyCredentialResponse,
+};
+use crate::blockchain::{
+    ChainStatus, StudentCredential, credential_issuer, enrollment_status, find_enrolled_student,
+    get_user_chain_backend, map_blockchain_error, recover_credential_signer, sign_credential,
+};
+use crate::config::APP_CONFIG;
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{major, user, wallet};
+use crate::extractor::AuthClaims;
+use crate::middleware::permission;
+use crate::static_service::DATABASE_CONNECTION;
+
+pub fn create_route() -> Router {
+    Router::new()
+        .route("/api/v1/credentials", post(issue_credential))
+        .route("/api/v1/credentials/verify", post(verify_credential))
+}
+
+/// Issue a signed student identity credential (EIP-712)
+///
+/// Students get their own credential; Admin/Manager can issue for any student.
+/// Code, name and status are read from the contract, majors from the DB.
+/// Students enrolled through an anchored Merkle batch get the record from their
+/// proof.
+#[utoipa::path(
+    post,
+    path = "/api/v1/credentials",
+    request_body = IssueCredentialRequest,
+    responses(
+        (status = 201, description = "Credential issued", body = CredentialResponse),
+        (status = 400, description = "User is not a student"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User or wallet not found"),
+        (status = 409, description = "Student is not registered on-chain"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Blockchain node unavailable")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Credentials"
+)]
+pub async fn issue_credential(
+    AuthClaims(auth_claims): AuthClaims,
+    Json(payload): Json<IssueCredentialRequest>,
+) -> Result<(StatusCode, Json<CredentialResponse>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let caller_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid user_id: {}", e),
+        )
+    })?;
+    let user_id = payload.user_id.unwrap_or(caller_id);
+    if user_id != caller_id {
+        permission::is_admin_or_manager(&auth_claims)?;
+    }
+
+    let student = user::Entity::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    if student.role != RoleEnum::Student {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Credentials can only be issued to students".to_string(),
+        ));
+    }
+
+    let student_wallet = wallet::Entity::find()
+        .filter(wallet::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
+
+    let majors = student
+        .find_related(major::Entity)
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .into_iter()
+        .map(|m| m.name)
+        .collect();
+
+    let blockchain = get_user_chain_backend(db, &user_id)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to initialize blockchain service", e))?;
+
+    let record = find_enrolled_student(db, blockchain.as_ref(), &student_wallet.address)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to get student", e))?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                "Student is not registered on-chain".to_string(),
+            )
+        })?;
+
+    let issued_at = Utc::now().timestamp() as u64;
+    let credential = StudentCredential {
+        credential_id: Uuid::new_v4().to_string(),
+        wallet: record.wallet_address.to_lowercase(),
+        student_code: record.student_code,
+        full_name: record.full_name,
+        majors,
+        status: if record.is_active {
+            "active".to_string()
+        } else {
+            "inactive".to_string()
+        },
+        issued_at,
+        expires_at: issued_at + APP_CONFIG.credential_ttl_days * 24 * 60 * 60,
+    };
+
+    let (typed_data, signature) = sign_credential(&credential).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to sign credential: {}", e),
+        )
+    })?;
+    let issuer = credential_issuer().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load credential issuer: {}", e),
+        )
+    })?;
+
+    tracing::info!(
+        "Issued credential {} for student {}",
+        credential.credential_id,
+        credential.student_code
+    );
+
+    let response = CredentialResponse {
+        credential,
+        typed_data: serde_json::to_value(typed_data).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to encode credential: {}", e),
+            )
+        })?,
+        signature,
+        issuer,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Verify a student credential (public)
+///
+/// Checks that the EIP-712 signature recovers to the institution key, that the
+/// credential has not expired and that the wallet is still `isActiveStudent`
+/// on-chain. While the node is unavailable the credential is not valid.
+#[utoipa::path(
+    post,
+    path = "/api/v1/credentials/verify",
+    request_body = VerifyCredentialRequest,
+    responses(
+        (status = 200, description = "Credential checked", body = VerifyCredentialResponse),
+        (status = 400, description = "Malformed credential"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Credentials"
+)]
+pub async fn verify_credential(
+    Json(payload): Json<VerifyCredentialRequest>,
+) -> Result<(StatusCode, Json<VerifyCredentialResponse>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let typed_data: TypedData = serde_json::from_value(payload.typed_data).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid EIP-712 payload: {}", e),
+        )
+    })?;
+    let credential = StudentCredential::from_typed_data(&typed_data).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid credential: {}", e),
+        )
+    })?;
+
+    let issuer = credential_issuer().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load credential issuer: {}", e),
+        )
+    })?;
+    let signer = recover_credential_signer(&typed_data, &payload.signature).ok();
+    let signature_valid = signer
+        .as_deref()
+        .is_some_and(|signer| signer.eq_ignore_ascii_case(&issuer));
+    let expired = Utc::now().timestamp() as u64 >= credential.expires_at;
+
+    let is_active = enrollment_status(db, &credential.wallet)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to check student status: {}", e),
+            )
+        })?;
+    let chain_status = if is_active.is_some() {
+        ChainStatus::Available
+    } else {
+        ChainStatus::Unavailable
+    };
+
+    let response = VerifyCredentialResponse {
+        valid: signature_valid && !expired && is_active == Some(true),
+        signature_valid,
+        expired,
+        is_active,
+        issuer,
+        signer,
+        credential,
+        chain_status,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
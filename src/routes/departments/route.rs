@@ -71,6 +71,7 @@ pub async fn create_department(
         dean: Set(payload.dean),
         create_at: Set(now),
         update_at: Set(now),
+        chain_deployment_id: Set(None),
     };
 
     let department = department_model.insert(db).await.map_err(|e| {
@@ -0,0 +1,106 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::blockchain::ChainStatus;
+use crate::entities::import_job;
+use crate::routes::users::import_job::{
+    ChainBatchStatus, ImportChainBatch, ImportRowResult, ImportRowStatus,
+};
+use crate::utils::spreadsheet::SpreadsheetFormat;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportJobResponse {
+    pub job_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub file_name: Option<String>,
+    /// batch or merkle
+    pub registration_mode: String,
+    /// fail, skip or update: what happens to rows for existing users
+    pub existing_user_mode: String,
+    /// pending, running, completed, failed or cancelled
+    pub status: String,
+    /// Non-empty data rows in the file
+    pub total_rows: i32,
+    pub processed_rows: i32,
+    /// Rows created, updated or skipped
+    pub successful: i32,
+    pub failed: i32,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    /// Outcome of every processed row, in file order
+    pub results: Vec<ImportRowResult>,
+    /// On-chain writes sent (or queued) for the created students
+    pub chain_batches: Vec<ImportChainBatch>,
+    /// Headers in the file that did not map to any field and were ignored
+    pub unmapped_columns: Vec<String>,
+    /// Batch holding the students' Merkle proofs (`merkle` registration mode)
+    pub merkle_batch_id: Option<Uuid>,
+    /// `queued` if any chain batch is waiting for the blockchain node
+    pub chain_status: ChainStatus,
+    pub error: Option<String>,
+    pub cancel_requested: bool,
+    pub created_at: NaiveDateTime,
+    pub started_at: Option<NaiveDateTime>,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+impl From<import_job::Model> for ImportJobResponse {
+    fn from(job: import_job::Model) -> Self {
+        let mut results: Vec<ImportRowResult> =
+            serde_json::from_value(job.results).unwrap_or_default();
+        results.sort_by_key(|result| result.row);
+        let count = |status: ImportRowStatus| results.iter().filter(|r| r.status == status).count();
+        let (created, updated, skipped) = (
+            count(ImportRowStatus::Created),
+            count(ImportRowStatus::Updated),
+            count(ImportRowStatus::Skipped),
+        );
+        let chain_batches: Vec<ImportChainBatch> =
+            serde_json::from_value(job.chain_batches).unwrap_or_default();
+        let chain_status = if chain_batches
+            .iter()
+            .any(|batch| batch.status == ChainBatchStatus::Queued)
+        {
+            ChainStatus::Queued
+        } else {
+            ChainStatus::Available
+        };
+
+        Self {
+            job_id: job.job_id,
+            created_by: job.created_by,
+            file_name: job.file_name,
+            registration_mode: job.registration_mode,
+            existing_user_mode: job.existing_user_mode,
+            status: job.status,
+            total_rows: job.total_rows,
+            processed_rows: job.processed_rows,
+            successful: job.successful,
+            failed: job.failed,
+            created,
+            updated,
+            skipped,
+            results,
+            chain_batches,
+            unmapped_columns: serde_json::from_value(job.unmapped_columns).unwrap_or_default(),
+            merkle_batch_id: job.merkle_batch_id,
+            chain_status,
+            error: job.error,
+            cancel_requested: job.cancel_requested,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            finished_at: job.finished_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ImportErrorFileParams {
+    /// `xlsx` (default) or `csv`
+    #[serde(default)]
+    #[param(inline)]
+    pub format: SpreadsheetFormat,
+}
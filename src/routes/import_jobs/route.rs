@@ -0,0 +1,240 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    routing::{get, post},
+};
+use chrono::Utc;
+use do_an_lib::structs::token_claims::{TokenClaims, UserRole};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::dto::{ImportErrorFileParams, ImportJobResponse};
+use crate::entities::import_job;
+use crate::extractor::AuthClaims;
+use crate::routes::users::import::ImportRow;
+use crate::routes::users::import_job::{IMPORT_PENDING, IMPORT_RUNNING, ImportRowStatus};
+use crate::static_service::DATABASE_CONNECTION;
+use crate::utils::spreadsheet::write_table;
+
+pub fn create_route() -> Router {
+    Router::new()
+        .route("/api/v1/import-jobs/{job_id}", get(get_import_job))
+        .route(
+            "/api/v1/import-jobs/{job_id}/cancel",
+            post(cancel_import_job),
+        )
+        .route(
+            "/api/v1/import-jobs/{job_id}/errors",
+            get(get_import_error_file),
+        )
+}
+
+/// Get the progress and per-row results of a bulk user import (Admin, or the
+/// user who started it)
+#[utoipa::path(
+    get,
+    path = "/api/v1/import-jobs/{job_id}",
+    params(
+        ("job_id" = Uuid, Path, description = "Import job ID")
+    ),
+    responses(
+        (status = 200, description = "Import job retrieved", body = ImportJobResponse),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Import job not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Import Jobs"
+)]
+pub async fn get_import_job(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(job_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ImportJobResponse>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let job = find_job(db, &auth_claims, job_id).await?;
+
+    Ok((StatusCode::OK, Json(job.into())))
+}
+
+/// Cancel a pending or running import (Admin, or the user who started it)
+///
+/// The worker stops before the next group of rows. Users already created are
+/// kept and their students are still registered on-chain.
+#[utoipa::path(
+    post,
+    path = "/api/v1/import-jobs/{job_id}/cancel",
+    params(
+        ("job_id" = Uuid, Path, description = "Import job ID")
+    ),
+    responses(
+        (status = 202, description = "Cancellation requested", body = ImportJobResponse),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Import job not found"),
+        (status = 409, description = "Import job already finished"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Import Jobs"
+)]
+pub async fn cancel_import_job(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(job_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ImportJobResponse>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let job = find_job(db, &auth_claims, job_id).await?;
+    if job.status != IMPORT_PENDING && job.status != IMPORT_RUNNING {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Import job already finished (job is {})", job.status),
+        ));
+    }
+
+    let mut active_model: import_job::ActiveModel = job.into();
+    active_model.cancel_requested = Set(true);
+    active_model.updated_at = Set(Utc::now().naive_utc());
+    let job = active_model.update(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update import job: {}", e),
+        )
+    })?;
+
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+/// Download the failed rows of an import as a spreadsheet (Admin, or the user
+/// who started it)
+///
+/// Rows keep their original columns, with an `Error` column appended; the
+/// password column is left empty and must be filled in again. Once fixed, the
+/// file can be uploaded to `POST /api/v1/users/bulk` as-is, since the `Error`
+/// column is ignored.
+#[utoipa::path(
+    get,
+    path = "/api/v1/import-jobs/{job_id}/errors",
+    params(
+        ("job_id" = Uuid, Path, description = "Import job ID"),
+        ImportErrorFileParams
+    ),
+    responses(
+        (status = 200, description = "Failed rows with their errors", content(
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (String = "text/csv")
+        )),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Import job not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Import Jobs"
+)]
+pub async fn get_import_error_file(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(job_id): Path<Uuid>,
+    Query(params): Query<ImportErrorFileParams>,
+) -> Result<(StatusCode, HeaderMap, Vec<u8>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let job = find_job(db, &auth_claims, job_id).await?;
+    let source_header: ImportRow =
+        serde_json::from_value(job.source_header.clone()).unwrap_or_default();
+    let failed_rows: BTreeMap<usize, ImportRow> =
+        serde_json::from_value(job.failed_rows.clone()).unwrap_or_default();
+    let response = ImportJobResponse::from(job);
+
+    let mut columns: Vec<String> = source_header
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect();
+    let width = columns.len();
+    columns.push("Error".to_string());
+
+    let rows: Vec<Vec<String>> = response
+        .results
+        .iter()
+        .filter(|result| result.status == ImportRowStatus::Failed)
+        .map(|result| {
+            let mut cells: Vec<String> = failed_rows
+                .get(&result.row)
+                .map(|cells| {
+                    cells
+                        .iter()
+                        .cloned()
+                        .map(Option::unwrap_or_default)
+                        .collect()
+                })
+                .unwrap_or_default();
+            cells.resize(width, String::new());
+            cells.push(result.error.clone().unwrap_or_default());
+            cells
+        })
+        .collect();
+
+    let data = write_table(params.format, "Errors", &columns, &rows).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to build error file: {}", e),
+        )
+    })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(params.format.content_type()),
+    );
+    let disposition = format!(
+        "attachment; filename=\"import-{}-errors.{}\"",
+        job_id,
+        params.format.extension()
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&disposition).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid file name: {}", e),
+            )
+        })?,
+    );
+
+    Ok((StatusCode::OK, headers, data))
+}
+
+async fn find_job(
+    db: &DatabaseConnection,
+    auth_claims: &TokenClaims,
+    job_id: Uuid,
+) -> Result<import_job::Model, (StatusCode, String)> {
+    let job = import_job::Entity::find_by_id(job_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Import job not found".to_string()))?;
+
+    let is_creator = job
+        .created_by
+        .is_some_and(|created_by| created_by.to_string() == auth_claims.user_id);
+    if auth_claims.role != UserRole::ADMIN && !is_creator {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Only admins and the user who started the import can access it".to_string(),
+        ));
+    }
+
+    Ok(job)
+}
@@ -1,3 +1,4 @@
+use crate::blockchain::ChainStatus;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -15,12 +16,15 @@ pub struct RemoveManagerRequest {
 pub struct ManagerResponse {
     pub address: String,
     pub is_manager: bool,
+    pub chain_status: ChainStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ManagerListResponse {
     pub managers: Vec<String>,
     pub total_count: u64,
+    /// `unavailable` when the list comes from manager accounts in DB
+    pub chain_status: ChainStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -2,7 +2,11 @@ use super::dto::{
     AddManagerRequest, CheckManagerRequest, ManagerListResponse, ManagerResponse,
     RemoveManagerRequest,
 };
-use crate::blockchain::get_user_blockchain_service;
+use crate::blockchain::{
+    ChainOperation, ChainSigner, ChainStatus, execute_or_enqueue, get_user_chain_backend,
+    is_chain_unavailable, map_blockchain_error, resolve_user_deployment,
+};
+use crate::entities::{sea_orm_active_enums::RoleEnum, user, wallet};
 use crate::extractor::AuthClaims;
 use crate::static_service::DATABASE_CONNECTION;
 use do_an_lib::structs::token_claims::UserRole;
@@ -11,6 +15,7 @@ use axum::{
     http::StatusCode,
     routing::{delete, get, post},
 };
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
 use uuid::Uuid;
 
 pub fn create_route() -> Router {
@@ -29,6 +34,7 @@ pub fn create_route() -> Router {
     request_body = AddManagerRequest,
     responses(
         (status = 200, description = "Manager added successfully", body = ManagerResponse),
+        (status = 202, description = "Blockchain node unavailable, manager queued", body = ManagerResponse),
         (status = 403, description = "Forbidden - Admin/Owner only"),
         (status = 400, description = "Bad request"),
         (status = 500, description = "Internal server error")
@@ -58,31 +64,27 @@ pub async fn add_manager(
         )
     })?;
 
-    let blockchain = get_user_blockchain_service(db, &user_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
+    let chain_target = resolve_user_deployment(db, &user_id).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to resolve chain deployment: {}", e),
+        )
+    })?;
 
-    blockchain
-        .add_manager(&payload.manager_address)
+    let operation = ChainOperation::AddManager {
+        manager_address: payload.manager_address.clone(),
+    };
+    let chain_status = execute_or_enqueue(db, &chain_target, ChainSigner::User(user_id), operation)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to add manager: {}", e),
-            )
-        })?;
+        .map_err(|e| map_blockchain_error("Failed to add manager", e))?;
 
     let response = ManagerResponse {
         address: payload.manager_address,
         is_manager: true,
+        chain_status,
     };
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((chain_status.write_status_code(), Json(response)))
 }
 
 /// Remove a manager from the blockchain (Admin only)
@@ -93,6 +95,7 @@ pub async fn add_manager(
     request_body = RemoveManagerRequest,
     responses(
         (status = 200, description = "Manager removed successfully", body = ManagerResponse),
+        (status = 202, description = "Blockchain node unavailable, removal queued", body = ManagerResponse),
         (status = 403, description = "Forbidden - Admin/Owner only"),
         (status = 400, description = "Bad request"),
         (status = 500, description = "Internal server error")
@@ -123,31 +126,27 @@ pub async fn remove_manager(
         )
     })?;
 
-    let blockchain = get_user_blockchain_service(db, &user_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
+    let chain_target = resolve_user_deployment(db, &user_id).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to resolve chain deployment: {}", e),
+        )
+    })?;
 
-    blockchain
-        .remove_manager(&payload.manager_address)
+    let operation = ChainOperation::RemoveManager {
+        manager_address: payload.manager_address.clone(),
+    };
+    let chain_status = execute_or_enqueue(db, &chain_target, ChainSigner::User(user_id), operation)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to remove manager: {}", e),
-            )
-        })?;
+        .map_err(|e| map_blockchain_error("Failed to remove manager", e))?;
 
     let response = ManagerResponse {
         address: payload.manager_address,
         is_manager: false,
+        chain_status,
     };
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((chain_status.write_status_code(), Json(response)))
 }
 
 /// Get all managers from the blockchain (Authenticated users)
@@ -175,32 +174,34 @@ pub async fn get_all_managers(
         )
     })?;
 
-    let blockchain = get_user_blockchain_service(db, &user_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
-
-    let managers = blockchain.get_all_managers().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to get managers: {}", e),
-        )
-    })?;
-
-    let count = blockchain.get_manager_count().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to get manager count: {}", e),
-        )
-    })?;
+    let chain_managers = async {
+        let blockchain = get_user_chain_backend(db, &user_id).await?;
+        let managers = blockchain.get_all_managers().await?;
+        let count = blockchain.get_manager_count().await?;
+        anyhow::Ok((managers, count))
+    }
+    .await;
 
-    let response = ManagerListResponse {
-        managers,
-        total_count: count,
+    let response = match chain_managers {
+        Ok((managers, count)) => ManagerListResponse {
+            managers,
+            total_count: count,
+            chain_status: ChainStatus::Available,
+        },
+        Err(e) if is_chain_unavailable(&e) => {
+            let managers = db_manager_addresses(db).await?;
+            ManagerListResponse {
+                total_count: managers.len() as u64,
+                managers,
+                chain_status: ChainStatus::Unavailable,
+            }
+        }
+        Err(e) => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get managers: {}", e),
+            ));
+        }
     };
 
     Ok((StatusCode::OK, Json(response)))
@@ -234,26 +235,53 @@ pub async fn check_manager(
         )
     })?;
 
-    let blockchain = get_user_blockchain_service(db, &user_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
+    let chain_is_manager = async {
+        let blockchain = get_user_chain_backend(db, &user_id).await?;
+        blockchain.is_manager(&payload.address).await
+    }
+    .await;
 
-    let is_manager = blockchain.is_manager(&payload.address).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to check manager: {}", e),
-        )
-    })?;
+    let (is_manager, chain_status) = match chain_is_manager {
+        Ok(is_manager) => (is_manager, ChainStatus::Available),
+        Err(e) if is_chain_unavailable(&e) => {
+            let is_manager = db_manager_addresses(db)
+                .await?
+                .iter()
+                .any(|address| address.eq_ignore_ascii_case(&payload.address));
+            (is_manager, ChainStatus::Unavailable)
+        }
+        Err(e) => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to check manager: {}", e),
+            ));
+        }
+    };
 
     let response = ManagerResponse {
         address: payload.address,
         is_manager,
+        chain_status,
     };
 
     Ok((StatusCode::OK, Json(response)))
 }
+
+/// Wallet addresses of manager accounts in DB, used while the blockchain node is unavailable
+async fn db_manager_addresses(
+    db: &DatabaseConnection,
+) -> Result<Vec<String>, (StatusCode, String)> {
+    let wallets = wallet::Entity::find()
+        .inner_join(user::Entity)
+        .filter(user::Column::Role.eq(RoleEnum::Manager))
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?;
+
+    Ok(wallets.into_iter().map(|w| w.address).collect())
+}
@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::blockchain::ChainStatus;
+use crate::entities::merkle_batch;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MerkleBatchResponse {
+    pub batch_id: Uuid,
+    pub deployment_id: Option<Uuid>,
+    pub root: String,
+    pub leaf_count: i32,
+    /// pending or anchored
+    pub status: String,
+    pub tx_hash: Option<String>,
+    /// Wallet that sent the anchor transaction
+    pub anchored_by: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub anchored_at: Option<NaiveDateTime>,
+}
+
+impl From<merkle_batch::Model> for MerkleBatchResponse {
+    fn from(batch: merkle_batch::Model) -> Self {
+        Self {
+            batch_id: batch.batch_id,
+            deployment_id: batch.deployment_id,
+            root: batch.root,
+            leaf_count: batch.leaf_count,
+            status: batch.status,
+            tx_hash: batch.tx_hash,
+            anchored_by: batch.anchored_by,
+            created_at: batch.created_at,
+            anchored_at: batch.anchored_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AnchorMerkleBatchResponse {
+    pub batch: MerkleBatchResponse,
+    /// `queued` if the anchor transaction is waiting for the blockchain node
+    pub chain_status: ChainStatus,
+}
+
+/// A student's record with everything needed to verify it against the anchored root
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MerkleProofResponse {
+    pub batch_id: Uuid,
+    pub wallet_address: String,
+    pub student_code: String,
+    pub full_name: String,
+    pub email: String,
+    pub leaf_index: i32,
+    pub leaf: String,
+    /// Sibling hashes from the leaf up to the root
+    pub proof: Vec<String>,
+    pub root: String,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyMerkleProofRequest {
+    #[schema(example = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e")]
+    pub wallet_address: String,
+    #[schema(example = "SV001")]
+    pub student_code: String,
+    #[schema(example = "Nguyen Van A")]
+    pub full_name: String,
+    #[schema(example = "student@example.com")]
+    pub email: String,
+    pub proof: Vec<String>,
+    pub root: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyMerkleProofResponse {
+    /// The record is included under the root and the root is anchored on-chain
+    pub valid: bool,
+    pub leaf: String,
+    /// The proof leads from the record's leaf to the root
+    pub proof_valid: bool,
+    /// Batch the root belongs to, if it was created by this service
+    pub batch_id: Option<Uuid>,
+    pub anchored: bool,
+    pub tx_hash: Option<String>,
+    /// `unavailable` if the anchor could not be checked on-chain; `valid` is then false
+    pub chain_status: ChainStatus,
+}
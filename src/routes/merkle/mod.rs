@@ -0,0 +1,4 @@
+pub mod dto;
+pub mod route;
+
+pub use route::create_route;
@@ -0,0 +1,306 @@
+use axum::{
+    Json, Router,
+    extract::Path,
+    http::StatusCode,
+    routing::{get, post},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use super::dto::{
+    AnchorMerkleBatchResponse, MerkleBatchResponse, MerkleProofResponse, VerifyMerkleProofRequest,
+    VerifyMerkleProofResponse,
+};
+use crate::blockchain::merkle::{BATCH_ANCHORED, parse_hash, to_hex};
+use crate::blockchain::{
+    ChainStatus, ChainTarget, anchor_merkle_batch, get_admin_chain_backend, get_deployment,
+    is_batch_anchored, is_chain_unavailable, student_leaf_hash, verify_proof,
+};
+use crate::entities::{merkle_batch, merkle_proof};
+use crate::extractor::AuthClaims;
+use crate::middleware::permission;
+use crate::static_service::DATABASE_CONNECTION;
+
+pub fn create_route() -> Router {
+    Router::new()
+        .route("/api/v1/merkle/batches/{batch_id}", get(get_merkle_batch))
+        .route(
+            "/api/v1/merkle/batches/{batch_id}/anchor",
+            post(anchor_batch),
+        )
+        .route(
+            "/api/v1/merkle/proofs/{student_code}",
+            get(get_student_proof),
+        )
+        .route("/api/v1/merkle/verify", post(verify_student_proof))
+}
+
+/// Get a Merkle batch created by a bulk import (Admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/merkle/batches/{batch_id}",
+    params(
+        ("batch_id" = Uuid, Path, description = "Merkle batch ID")
+    ),
+    responses(
+        (status = 200, description = "Batch retrieved", body = MerkleBatchResponse),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Batch not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Merkle"
+)]
+pub async fn get_merkle_batch(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(batch_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<MerkleBatchResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let batch = find_batch(db, batch_id).await?;
+
+    Ok((StatusCode::OK, Json(batch.into())))
+}
+
+/// Anchor a batch whose root is not on-chain yet (Admin only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/merkle/batches/{batch_id}/anchor",
+    params(
+        ("batch_id" = Uuid, Path, description = "Merkle batch ID")
+    ),
+    responses(
+        (status = 200, description = "Root anchored", body = AnchorMerkleBatchResponse),
+        (status = 202, description = "Anchor queued until the blockchain node is available", body = AnchorMerkleBatchResponse),
+        (status = 400, description = "Batch is already anchored"),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Batch not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Merkle"
+)]
+pub async fn anchor_batch(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(batch_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<AnchorMerkleBatchResponse>), (StatusCode, String)> {
+    permission::is_admin(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let batch = find_batch(db, batch_id).await?;
+    if batch.status == BATCH_ANCHORED {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Merkle batch is already anchored".to_string(),
+        ));
+    }
+
+    let chain_status = anchor_merkle_batch(db, &batch).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to anchor Merkle root: {}", e),
+        )
+    })?;
+
+    let batch = find_batch(db, batch_id).await?;
+    let response = AnchorMerkleBatchResponse {
+        batch: batch.into(),
+        chain_status,
+    };
+
+    Ok((chain_status.write_status_code(), Json(response)))
+}
+
+/// Get a student's Merkle proof (Admin/Manager, or the student themselves)
+#[utoipa::path(
+    get,
+    path = "/api/v1/merkle/proofs/{student_code}",
+    params(
+        ("student_code" = String, Path, description = "Student code")
+    ),
+    responses(
+        (status = 200, description = "Proof retrieved", body = MerkleProofResponse),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "No Merkle proof for this student"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Merkle"
+)]
+pub async fn get_student_proof(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(student_code): Path<String>,
+) -> Result<(StatusCode, Json<MerkleProofResponse>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let (proof, batch) = merkle_proof::Entity::find()
+        .filter(merkle_proof::Column::StudentCode.eq(&student_code))
+        .find_also_related(merkle_batch::Entity)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                "No Merkle proof for this student".to_string(),
+            )
+        })?;
+
+    if permission::is_admin_or_manager(&auth_claims).is_err() {
+        permission::can_access_user_resource(&auth_claims, &proof.user_id.to_string())?;
+    }
+
+    let batch = batch.ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Merkle batch not found".to_string(),
+        )
+    })?;
+
+    let response = MerkleProofResponse {
+        batch_id: batch.batch_id,
+        wallet_address: proof.wallet_address,
+        student_code: proof.student_code,
+        full_name: proof.full_name,
+        email: proof.email,
+        leaf_index: proof.leaf_index,
+        leaf: proof.leaf_hash,
+        proof: serde_json::from_value(proof.proof).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid stored proof: {}", e),
+            )
+        })?,
+        root: batch.root,
+        tx_hash: batch.tx_hash,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Verify a student record against a Merkle proof and its anchored root
+///
+/// The proof is checked locally; the root must belong to a batch of this service
+/// whose anchor transaction is confirmed on-chain. While the node is unavailable
+/// the anchor cannot be checked and the proof is reported as not valid.
+#[utoipa::path(
+    post,
+    path = "/api/v1/merkle/verify",
+    request_body = VerifyMerkleProofRequest,
+    responses(
+        (status = 200, description = "Proof checked", body = VerifyMerkleProofResponse),
+        (status = 400, description = "Malformed address or hash"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Merkle"
+)]
+pub async fn verify_student_proof(
+    AuthClaims(_auth_claims): AuthClaims,
+    Json(payload): Json<VerifyMerkleProofRequest>,
+) -> Result<(StatusCode, Json<VerifyMerkleProofResponse>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let leaf = student_leaf_hash(
+        &payload.wallet_address,
+        &payload.student_code,
+        &payload.full_name,
+        &payload.email,
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid record: {}", e)))?;
+    let root = parse_hash(&payload.root)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid root: {}", e)))?;
+    let proof = payload
+        .proof
+        .iter()
+        .map(|hash| parse_hash(hash))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid proof: {}", e)))?;
+
+    let proof_valid = verify_proof(&leaf, &proof, &root);
+
+    let batch = merkle_batch::Entity::find()
+        .filter(merkle_batch::Column::Root.eq(to_hex(&root)))
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?;
+
+    let mut chain_status = ChainStatus::Available;
+    let anchored = match &batch {
+        Some(batch) if batch.status == BATCH_ANCHORED => {
+            let result = async {
+                let target = match batch.deployment_id {
+                    Some(deployment_id) => get_deployment(db, &deployment_id).await?,
+                    None => ChainTarget::from_config(),
+                };
+                let backend = get_admin_chain_backend(db, &target).await?;
+                is_batch_anchored(backend.as_ref(), batch).await
+            }
+            .await;
+
+            match result {
+                Ok(anchored) => anchored,
+                Err(e) if is_chain_unavailable(&e) => {
+                    chain_status = ChainStatus::Unavailable;
+                    false
+                }
+                Err(e) => {
+                    return Err((
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("Failed to check Merkle root anchor: {}", e),
+                    ));
+                }
+            }
+        }
+        _ => false,
+    };
+
+    let response = VerifyMerkleProofResponse {
+        valid: proof_valid && anchored,
+        leaf: to_hex(&leaf),
+        proof_valid,
+        batch_id: batch.as_ref().map(|batch| batch.batch_id),
+        anchored,
+        tx_hash: batch.and_then(|batch| batch.tx_hash),
+        chain_status,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn find_batch(
+    db: &DatabaseConnection,
+    batch_id: Uuid,
+) -> Result<merkle_batch::Model, (StatusCode, String)> {
+    merkle_batch::Entity::find_by_id(batch_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Merkle batch not found".to_string()))
+}
@@ -1,8 +1,13 @@
 pub mod auth;
+pub mod chain;
+pub mod contract;
+pub mod credentials;
 pub mod departments;
 pub mod health;
+pub mod import_jobs;
 pub mod majors;
 pub mod managers;
+pub mod merkle;
 pub mod profile;
 pub mod students;
 pub mod users;
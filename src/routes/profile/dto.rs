@@ -1,3 +1,4 @@
+use crate::blockchain::ChainStatus;
 use serde::Serialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
@@ -10,6 +11,9 @@ pub struct ProfileResponse {
     pub email: String,
     pub role: String,
     pub wallet_address: String,
-    pub blockchain_role: u8,
-    pub is_active: bool,
+    /// On-chain role, null while the blockchain node is unavailable
+    pub blockchain_role: Option<u8>,
+    /// On-chain active flag, null while the blockchain node is unavailable
+    pub is_active: Option<bool>,
+    pub chain_status: ChainStatus,
 }
@@ -1,5 +1,5 @@
 use super::dto::ProfileResponse;
-use crate::blockchain::get_user_blockchain_service;
+use crate::blockchain::{ChainStatus, get_user_chain_backend, is_chain_unavailable};
 use crate::entities::{sea_orm_active_enums::RoleEnum, user, wallet};
 use crate::extractor::AuthClaims;
 use crate::static_service::DATABASE_CONNECTION;
@@ -20,7 +20,7 @@ pub fn create_route() -> Router {
         ("bearer_auth" = [])
     ),
     responses(
-        (status = 200, description = "Profile retrieved", body = ProfileResponse),
+        (status = 200, description = "Profile retrieved (`chain_status: unavailable` when the blockchain node is down)", body = ProfileResponse),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     ),
@@ -66,34 +66,40 @@ pub async fn get_profile(
         })?
         .ok_or_else(|| (StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
 
-    // Create blockchain service with user's private key
-    let user_blockchain = get_user_blockchain_service(db, &user_id_uuid)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to create blockchain service: {}", e),
-            )
-        })?;
+    // Read role and status from the contract using the user's wallet; both
+    // are left out while the node is unavailable
+    let chain_info = async {
+        let user_blockchain = get_user_chain_backend(db, &user_id_uuid).await?;
+        let blockchain_role = user_blockchain.get_user_role(&wallet_info.address).await?;
+        let is_active = if user_info.role == RoleEnum::Student {
+            match user_blockchain
+                .is_active_student(&wallet_info.address)
+                .await
+            {
+                Ok(is_active) => is_active,
+                Err(e) if is_chain_unavailable(&e) => return Err(e),
+                Err(_) => false,
+            }
+        } else {
+            true
+        };
+        anyhow::Ok((blockchain_role, is_active))
+    }
+    .await;
 
-    // Call blockchain contract using user's wallet
-    let blockchain_role = user_blockchain
-        .get_user_role(&wallet_info.address)
-        .await
-        .map_err(|e| {
-            (
+    let (blockchain_role, is_active, chain_status) = match chain_info {
+        Ok((blockchain_role, is_active)) => (
+            Some(blockchain_role),
+            Some(is_active),
+            ChainStatus::Available,
+        ),
+        Err(e) if is_chain_unavailable(&e) => (None, None, ChainStatus::Unavailable),
+        Err(e) => {
+            return Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Failed to get blockchain role: {}", e),
-            )
-        })?;
-
-    let is_active = if user_info.role == RoleEnum::Student {
-        user_blockchain
-            .is_active_student(&wallet_info.address)
-            .await
-            .unwrap_or(false)
-    } else {
-        true
+            ));
+        }
     };
 
     let role_str = match user_info.role {
@@ -112,6 +118,7 @@ pub async fn get_profile(
         wallet_address: wallet_info.address,
         blockchain_role,
         is_active,
+        chain_status,
     };
 
     Ok((StatusCode::OK, Json(response)))
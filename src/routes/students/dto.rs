@@ -1,5 +1,8 @@
+use crate::blockchain::service::StudentInfo;
+use crate::blockchain::{ChainStatus, StudentPass};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct StudentIdRequest {
@@ -27,10 +30,71 @@ pub struct StudentInfoResponse {
     pub registered_at: u64,
 }
 
+impl From<StudentInfo> for StudentInfoResponse {
+    fn from(student: StudentInfo) -> Self {
+        Self {
+            id: student.id,
+            wallet_address: student.wallet_address,
+            student_code: student.student_code,
+            full_name: student.full_name,
+            email: student.email,
+            is_active: student.is_active,
+            registered_at: student.registered_at,
+        }
+    }
+}
+
+/// On-chain student record merged with its DB account (if any)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StudentRecordResponse {
+    pub id: u64,
+    pub wallet_address: String,
+    pub student_code: String,
+    pub full_name: String,
+    pub email: String,
+    pub is_active: bool,
+    pub registered_at: u64,
+    /// DB user owning the wallet (None if the wallet is not managed by this service)
+    pub user_id: Option<Uuid>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub phone_number: Option<String>,
+    pub major_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StudentListResponse {
+    pub students: Vec<StudentRecordResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+/// Largest `page_size` of `GET /api/v1/students`
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct StudentQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+}
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    20
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct StudentStatusResponse {
+    /// On-chain student ID, 0 when unknown (not registered or node unavailable)
     pub student_id: u64,
     pub is_active: bool,
+    pub chain_status: ChainStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -43,4 +107,45 @@ pub struct SystemInfoResponse {
     pub owner: String,
     pub total_students: u64,
     pub total_managers: u64,
+    /// `unavailable` when the counts come from DB accounts
+    pub chain_status: ChainStatus,
+}
+
+/// Image format of the student QR pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct StudentQrQueryParams {
+    /// `png` (default) or `svg`
+    #[serde(default)]
+    #[param(inline)]
+    pub format: QrFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyStudentPassRequest {
+    /// Token scanned from the student's QR code
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyStudentPassResponse {
+    /// Signature, expiry and on-chain status all check out
+    pub valid: bool,
+    pub signature_valid: bool,
+    pub expired: bool,
+    /// `isActiveStudent` for the pass wallet; null while the node is unavailable
+    pub is_active: Option<bool>,
+    /// Address of the pass signing key
+    pub issuer: String,
+    /// Address recovered from the signature
+    pub signer: String,
+    pub pass: StudentPass,
+    pub chain_status: ChainStatus,
 }
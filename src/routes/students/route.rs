@@ -1,22 +1,48 @@
 use axum::{
     Json, Router,
-    extract::Path,
-    http::StatusCode,
+    extract::{Path, Query},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
     routing::{get, post, put},
 };
+use chrono::Utc;
+use image::{ImageFormat, Luma};
+use qrcode::QrCode;
+use qrcode::render::svg;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect,
+};
+use std::collections::HashMap;
+use std::io::Cursor;
+use uuid::Uuid;
 
 use super::dto::{
-    StudentAddressRequest, StudentCodeRequest, StudentIdResponse, StudentInfoResponse,
-    StudentStatusResponse, SystemInfoResponse,
+    MAX_PAGE_SIZE, QrFormat, StudentAddressRequest, StudentCodeRequest, StudentIdResponse,
+    StudentInfoResponse, StudentListResponse, StudentQrQueryParams, StudentQueryParams,
+    StudentRecordResponse, StudentStatusResponse, SystemInfoResponse, VerifyStudentPassRequest,
+    VerifyStudentPassResponse,
+};
+use crate::blockchain::{
+    ChainOperation, ChainSigner, ChainStatus, StudentPass, decode_student_pass, enrollment_status,
+    execute_or_enqueue, find_enrolled_student, get_contract_owner_wallet, get_user_chain_backend,
+    is_chain_unavailable, map_blockchain_error, resolve_user_deployment, sign_student_pass,
+    student_pass_issuer,
 };
-use crate::blockchain::get_user_blockchain_service;
+use crate::config::APP_CONFIG;
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{user, user_major, wallet};
 use crate::extractor::AuthClaims;
+use crate::middleware::permission;
 use crate::static_service::DATABASE_CONNECTION;
 use do_an_lib::structs::token_claims::UserRole;
 
 pub fn create_route() -> Router {
     Router::new()
+        .route("/api/v1/students", get(get_all_students))
         .route("/api/v1/students/{student_id}", get(get_student_by_id))
+        .route(
+            "/api/v1/students/by-user/{user_id}",
+            get(get_student_by_user_id),
+        )
         .route(
             "/api/v1/students/by-address",
             post(get_student_id_by_address),
@@ -31,6 +57,8 @@ pub fn create_route() -> Router {
             put(activate_student),
         )
         .route("/api/v1/students/check-active", post(check_student_active))
+        .route("/api/v1/students/me/qr", get(get_student_qr))
+        .route("/api/v1/students/qr/verify", post(verify_student_pass))
         .route("/api/v1/system/info", get(get_system_info))
 }
 
@@ -62,33 +90,242 @@ pub async fn get_student_by_id(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let blockchain = get_user_blockchain_service(db, &user_id)
+    let blockchain = get_user_chain_backend(db, &user_id)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to initialize blockchain service", e))?;
+
+    if student_id == 0 {
+        return Err((StatusCode::NOT_FOUND, "Student not found".to_string()));
+    }
+
+    let student = blockchain
+        .get_student(student_id)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to get student", e))?;
+
+    if student.id == 0 {
+        return Err((StatusCode::NOT_FOUND, "Student not found".to_string()));
+    }
+
+    Ok((StatusCode::OK, Json(student.into())))
+}
+
+/// Get all on-chain students with pagination, merged with DB accounts (Staff only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/students",
+    params(StudentQueryParams),
+    responses(
+        (status = 200, description = "Students retrieved successfully", body = StudentListResponse),
+        (status = 400, description = "Bad request"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Students"
+)]
+pub async fn get_all_students(
+    AuthClaims(auth_claims): AuthClaims,
+    Query(params): Query<StudentQueryParams>,
+) -> Result<(StatusCode, Json<StudentListResponse>), (StatusCode, String)> {
+    permission::is_staff(&auth_claims)?;
+
+    if params.page == 0 || params.page_size == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "page and page_size must be greater than 0".to_string(),
+        ));
+    }
+    if params.page_size > MAX_PAGE_SIZE {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("page_size must be at most {}", MAX_PAGE_SIZE),
+        ));
+    }
+    // Student IDs are sequential on-chain, starting at 1
+    let start_id = (params.page - 1)
+        .checked_mul(params.page_size)
+        .and_then(|offset| offset.checked_add(1))
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "page is out of range".to_string()))?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let user_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid user_id: {}", e),
+        )
+    })?;
+    let blockchain = get_user_chain_backend(db, &user_id)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to initialize blockchain service", e))?;
+
+    let total = blockchain
+        .get_total_students()
+        .await
+        .map_err(|e| map_blockchain_error("Failed to get total students", e))?;
+
+    let students = blockchain
+        .get_students(start_id, params.page_size, total)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to get students", e))?;
+
+    // Merge with DB accounts by wallet address
+    let addresses: Vec<String> = students.iter().map(|s| s.wallet_address.clone()).collect();
+    let wallets = wallet::Entity::find()
+        .filter(wallet::Column::Address.is_in(addresses))
+        .all(db)
         .await
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
+                format!("Database error: {}", e),
             )
         })?;
 
-    let student = blockchain.get_student(student_id).await.map_err(|e| {
+    let user_ids: Vec<Uuid> = wallets.iter().map(|w| w.user_id).collect();
+    let users: HashMap<Uuid, user::Model> = user::Entity::find()
+        .filter(user::Column::UserId.is_in(user_ids.clone()))
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .into_iter()
+        .map(|u| (u.user_id, u))
+        .collect();
+
+    let mut majors: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for relationship in user_major::Entity::find()
+        .filter(user_major::Column::UserId.is_in(user_ids))
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+    {
+        majors
+            .entry(relationship.user_id)
+            .or_default()
+            .push(relationship.major_id);
+    }
+
+    let wallet_owners: HashMap<String, Uuid> = wallets
+        .into_iter()
+        .map(|w| (w.address, w.user_id))
+        .collect();
+
+    let students = students
+        .into_iter()
+        .map(|student| {
+            let account = wallet_owners
+                .get(&student.wallet_address)
+                .and_then(|user_id| users.get(user_id));
+
+            StudentRecordResponse {
+                id: student.id,
+                wallet_address: student.wallet_address,
+                student_code: student.student_code,
+                full_name: student.full_name,
+                email: student.email,
+                is_active: student.is_active,
+                registered_at: student.registered_at,
+                user_id: account.map(|u| u.user_id),
+                first_name: account.map(|u| u.first_name.clone()),
+                last_name: account.map(|u| u.last_name.clone()),
+                phone_number: account.map(|u| u.phone_number.clone()),
+                major_ids: account
+                    .and_then(|u| majors.get(&u.user_id))
+                    .cloned()
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(StudentListResponse {
+            students,
+            total,
+            page: params.page,
+            page_size: params.page_size,
+        }),
+    ))
+}
+
+/// Get on-chain student information by DB user ID
+/// Staff can see all, students can see themselves
+#[utoipa::path(
+    get,
+    path = "/api/v1/students/by-user/{user_id}",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Student retrieved successfully", body = StudentInfoResponse),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Student not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Students"
+)]
+pub async fn get_student_by_user_id(
+    AuthClaims(auth_claims): AuthClaims,
+    Path(target_user_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<StudentInfoResponse>), (StatusCode, String)> {
+    if permission::is_staff(&auth_claims).is_err() {
+        permission::can_access_user_resource(&auth_claims, &target_user_id.to_string())?;
+    }
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let user_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to get student: {}", e),
+            format!("Invalid user_id: {}", e),
         )
     })?;
 
-    let response = StudentInfoResponse {
-        id: student.id,
-        wallet_address: student.wallet_address,
-        student_code: student.student_code,
-        full_name: student.full_name,
-        email: student.email,
-        is_active: student.is_active,
-        registered_at: student.registered_at,
-    };
+    let target_wallet = wallet::Entity::find()
+        .filter(wallet::Column::UserId.eq(target_user_id))
+        .one(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
 
-    Ok((StatusCode::OK, Json(response)))
+    let blockchain = get_user_chain_backend(db, &user_id)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to initialize blockchain service", e))?;
+
+    let student_id = blockchain
+        .get_student_id_by_address(&target_wallet.address)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to get student ID", e))?;
+
+    if student_id == 0 {
+        return Err((StatusCode::NOT_FOUND, "Student not found".to_string()));
+    }
+
+    let student = blockchain
+        .get_student(student_id)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to get student", e))?;
+
+    Ok((StatusCode::OK, Json(student.into())))
 }
 
 /// Get student ID by wallet address
@@ -117,24 +354,14 @@ pub async fn get_student_id_by_address(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let blockchain = get_user_blockchain_service(db, &user_id)
+    let blockchain = get_user_chain_backend(db, &user_id)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
+        .map_err(|e| map_blockchain_error("Failed to initialize blockchain service", e))?;
 
     let student_id = blockchain
         .get_student_id_by_address(&payload.address)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get student ID: {}", e),
-            )
-        })?;
+        .map_err(|e| map_blockchain_error("Failed to get student ID", e))?;
 
     if student_id == 0 {
         return Err((StatusCode::NOT_FOUND, "Student not found".to_string()));
@@ -171,24 +398,14 @@ pub async fn get_student_id_by_code(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let blockchain = get_user_blockchain_service(db, &user_id)
+    let blockchain = get_user_chain_backend(db, &user_id)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
+        .map_err(|e| map_blockchain_error("Failed to initialize blockchain service", e))?;
 
     let student_id = blockchain
         .get_student_id_by_code(&payload.student_code)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get student ID: {}", e),
-            )
-        })?;
+        .map_err(|e| map_blockchain_error("Failed to get student ID", e))?;
 
     if student_id == 0 {
         return Err((StatusCode::NOT_FOUND, "Student not found".to_string()));
@@ -209,7 +426,9 @@ pub async fn get_student_id_by_code(
     ),
     responses(
         (status = 200, description = "Student deactivated", body = StudentStatusResponse),
+        (status = 202, description = "Blockchain node unavailable, deactivation queued", body = StudentStatusResponse),
         (status = 403, description = "Forbidden - Admin/Manager only"),
+        (status = 404, description = "Student not found"),
         (status = 500, description = "Internal server error")
     ),
     security(("bearer_auth" = [])),
@@ -236,31 +455,25 @@ pub async fn deactivate_student(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let blockchain = get_user_blockchain_service(db, &user_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
+    let chain_target = resolve_user_deployment(db, &user_id).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to resolve chain deployment: {}", e),
+        )
+    })?;
 
-    blockchain
-        .deactivate_student(student_id)
+    let operation = ChainOperation::DeactivateStudent { student_id };
+    let chain_status = execute_or_enqueue(db, &chain_target, ChainSigner::User(user_id), operation)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to deactivate student: {}", e),
-            )
-        })?;
+        .map_err(|e| map_blockchain_error("Failed to deactivate student", e))?;
 
     let response = StudentStatusResponse {
         student_id,
         is_active: false,
+        chain_status,
     };
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((chain_status.write_status_code(), Json(response)))
 }
 
 /// Activate a student (Admin/Manager only)
@@ -273,7 +486,9 @@ pub async fn deactivate_student(
     ),
     responses(
         (status = 200, description = "Student activated", body = StudentStatusResponse),
+        (status = 202, description = "Blockchain node unavailable, activation queued", body = StudentStatusResponse),
         (status = 403, description = "Forbidden - Admin/Manager only"),
+        (status = 404, description = "Student not found"),
         (status = 500, description = "Internal server error")
     ),
     security(("bearer_auth" = [])),
@@ -300,28 +515,25 @@ pub async fn activate_student(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let blockchain = get_user_blockchain_service(db, &user_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
-            )
-        })?;
-
-    blockchain.activate_student(student_id).await.map_err(|e| {
+    let chain_target = resolve_user_deployment(db, &user_id).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to activate student: {}", e),
+            format!("Failed to resolve chain deployment: {}", e),
         )
     })?;
 
+    let operation = ChainOperation::ActivateStudent { student_id };
+    let chain_status = execute_or_enqueue(db, &chain_target, ChainSigner::User(user_id), operation)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to activate student", e))?;
+
     let response = StudentStatusResponse {
         student_id,
         is_active: true,
+        chain_status,
     };
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((chain_status.write_status_code(), Json(response)))
 }
 
 /// Check if student is active by address
@@ -331,7 +543,8 @@ pub async fn activate_student(
     request_body = StudentAddressRequest,
     responses(
         (status = 200, description = "Student status retrieved", body = StudentStatusResponse),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Blockchain node unavailable")
     ),
     security(("bearer_auth" = [])),
     tag = "Students"
@@ -349,38 +562,234 @@ pub async fn check_student_active(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let blockchain = get_user_blockchain_service(db, &user_id)
+    let chain_status_info = async {
+        let blockchain = get_user_chain_backend(db, &user_id).await?;
+        let is_active = blockchain.is_active_student(&payload.address).await?;
+        let student_id = match blockchain.get_student_id_by_address(&payload.address).await {
+            Ok(student_id) => student_id,
+            Err(e) if is_chain_unavailable(&e) => return Err(e),
+            Err(_) => 0,
+        };
+        anyhow::Ok((student_id, is_active))
+    }
+    .await;
+
+    // No DB fallback: activation can change on-chain without this service, so
+    // the status is only known while the node is reachable
+    let (student_id, is_active) =
+        chain_status_info.map_err(|e| map_blockchain_error("Failed to check student status", e))?;
+
+    let response = StudentStatusResponse {
+        student_id,
+        is_active,
+        chain_status: ChainStatus::Available,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a short-lived signed QR pass for the current student
+///
+/// The QR code encodes a pass token signed with the pass signing key; gates and
+/// exam halls check it with `POST /api/v1/students/qr/verify`. The token and its
+/// expiry are also returned in the `X-Student-Pass-Token` and
+/// `X-Student-Pass-Expires-At` headers.
+#[utoipa::path(
+    get,
+    path = "/api/v1/students/me/qr",
+    params(StudentQrQueryParams),
+    responses(
+        (status = 200, description = "QR pass rendered", content(
+            (Vec<u8> = "image/png"),
+            (String = "image/svg+xml")
+        )),
+        (status = 403, description = "Forbidden - Students only, or student is deactivated"),
+        (status = 404, description = "Wallet not found"),
+        (status = 409, description = "Student is not registered on-chain"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Blockchain node unavailable")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Students"
+)]
+pub async fn get_student_qr(
+    AuthClaims(auth_claims): AuthClaims,
+    Query(params): Query<StudentQrQueryParams>,
+) -> Result<(StatusCode, HeaderMap, Vec<u8>), (StatusCode, String)> {
+    permission::has_role(&auth_claims, &[UserRole::STUDENT])?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let user_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid user_id: {}", e),
+        )
+    })?;
+
+    let student_wallet = wallet::Entity::find()
+        .filter(wallet::Column::UserId.eq(user_id))
+        .one(db)
         .await
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
+                format!("Database error: {}", e),
             )
-        })?;
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
 
-    let is_active = blockchain
-        .is_active_student(&payload.address)
+    let blockchain = get_user_chain_backend(db, &user_id)
         .await
-        .map_err(|e| {
+        .map_err(|e| map_blockchain_error("Failed to initialize blockchain service", e))?;
+
+    let record = find_enrolled_student(db, blockchain.as_ref(), &student_wallet.address)
+        .await
+        .map_err(|e| map_blockchain_error("Failed to get student", e))?
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to check student status: {}", e),
+                StatusCode::CONFLICT,
+                "Student is not registered on-chain".to_string(),
             )
         })?;
+    if !record.is_active {
+        return Err((StatusCode::FORBIDDEN, "Student is deactivated".to_string()));
+    }
 
-    let student_id = blockchain
-        .get_student_id_by_address(&payload.address)
-        .await
-        .unwrap_or(0);
+    let issued_at = Utc::now().timestamp() as u64;
+    let pass = StudentPass {
+        wallet: record.wallet_address.to_lowercase(),
+        student_code: record.student_code,
+        full_name: record.full_name,
+        issued_at,
+        expires_at: issued_at + APP_CONFIG.student_pass_ttl_secs,
+    };
+    let token = sign_student_pass(&pass).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to sign student pass: {}", e),
+        )
+    })?;
 
-    let response = StudentStatusResponse {
-        student_id,
+    let (content_type, body) = render_qr(&token, params.format).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to render QR code: {}", e),
+        )
+    })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    headers.insert(
+        "x-student-pass-expires-at",
+        HeaderValue::from(pass.expires_at),
+    );
+    headers.insert(
+        "x-student-pass-token",
+        HeaderValue::from_str(&token).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid pass token header: {}", e),
+            )
+        })?,
+    );
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+/// Verify a scanned student QR pass (public)
+///
+/// Checks that the token is signed by the pass signing key, has not expired and
+/// that the wallet is still `isActiveStudent` on-chain, or enrolled through an
+/// anchored Merkle batch. While the node is unavailable the pass is not valid.
+#[utoipa::path(
+    post,
+    path = "/api/v1/students/qr/verify",
+    request_body = VerifyStudentPassRequest,
+    responses(
+        (status = 200, description = "Pass checked", body = VerifyStudentPassResponse),
+        (status = 400, description = "Malformed pass token"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Students"
+)]
+pub async fn verify_student_pass(
+    Json(payload): Json<VerifyStudentPassRequest>,
+) -> Result<(StatusCode, Json<VerifyStudentPassResponse>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    let (pass, signer) = decode_student_pass(&payload.token).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid pass token: {}", e),
+        )
+    })?;
+
+    let issuer = student_pass_issuer().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load student pass issuer: {}", e),
+        )
+    })?;
+    let signature_valid = signer.eq_ignore_ascii_case(&issuer);
+    let expired = Utc::now().timestamp() as u64 >= pass.expires_at;
+
+    let is_active = enrollment_status(db, &pass.wallet).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to check student status: {}", e),
+        )
+    })?;
+    let chain_status = if is_active.is_some() {
+        ChainStatus::Available
+    } else {
+        ChainStatus::Unavailable
+    };
+
+    if signature_valid && !expired {
+        tracing::info!(
+            "Student pass for {} checked (active: {:?})",
+            pass.student_code,
+            is_active
+        );
+    }
+
+    let response = VerifyStudentPassResponse {
+        valid: signature_valid && !expired && is_active == Some(true),
+        signature_valid,
+        expired,
         is_active,
+        issuer,
+        signer,
+        pass,
+        chain_status,
     };
 
     Ok((StatusCode::OK, Json(response)))
 }
 
+/// Render `data` as a QR code, returning the content type and image bytes
+fn render_qr(data: &str, format: QrFormat) -> anyhow::Result<(&'static str, Vec<u8>)> {
+    let code = QrCode::new(data.as_bytes())?;
+
+    match format {
+        QrFormat::Png => {
+            let image = code.render::<Luma<u8>>().min_dimensions(320, 320).build();
+            let mut bytes = Vec::new();
+            image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+            Ok(("image/png", bytes))
+        }
+        QrFormat::Svg => {
+            let image = code.render::<svg::Color>().min_dimensions(320, 320).build();
+            Ok(("image/svg+xml", image.into_bytes()))
+        }
+    }
+}
+
 /// Get system information
 #[utoipa::path(
     get,
@@ -403,28 +812,70 @@ pub async fn get_system_info(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let blockchain = get_user_blockchain_service(db, &user_id)
+    let contract_info = async {
+        let blockchain = get_user_chain_backend(db, &user_id).await?;
+        blockchain.get_contract_info().await
+    }
+    .await;
+
+    let response = match contract_info {
+        Ok((owner, student_count, manager_count)) => SystemInfoResponse {
+            owner,
+            total_students: student_count,
+            total_managers: manager_count,
+            chain_status: ChainStatus::Available,
+        },
+        Err(e) if is_chain_unavailable(&e) => system_info_from_db(db).await?,
+        Err(e) => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get contract info: {}", e),
+            ));
+        }
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// System info from DB accounts, used while the blockchain node is unavailable
+async fn system_info_from_db(
+    db: &DatabaseConnection,
+) -> Result<SystemInfoResponse, (StatusCode, String)> {
+    let owner = get_contract_owner_wallet(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .map(|w| w.address)
+        .unwrap_or_default();
+    let total_students = user::Entity::find()
+        .filter(user::Column::Role.eq(RoleEnum::Student))
+        .count(db)
         .await
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to initialize blockchain service: {}", e),
+                format!("Database error: {}", e),
             )
         })?;
-
-    let (owner, student_count, manager_count) =
-        blockchain.get_contract_info().await.map_err(|e| {
+    let total_managers = user::Entity::find()
+        .filter(user::Column::Role.eq(RoleEnum::Manager))
+        .count(db)
+        .await
+        .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get contract info: {}", e),
+                format!("Database error: {}", e),
             )
         })?;
 
-    let response = SystemInfoResponse {
+    Ok(SystemInfoResponse {
         owner,
-        total_students: student_count,
-        total_managers: manager_count,
-    };
-
-    Ok((StatusCode::OK, Json(response)))
+        total_students,
+        total_managers,
+        chain_status: ChainStatus::Unavailable,
+    })
 }
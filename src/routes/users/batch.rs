@@ -0,0 +1,438 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use do_an_lib::structs::token_claims::{TokenClaims, UserRole};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set, TransactionTrait,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::dto::{BatchOperation, BatchUserResult};
+use crate::blockchain::memory::{ROLE_ADMIN, ROLE_NONE, ROLE_TEACHER};
+use crate::blockchain::{
+    ChainBackend, ChainOperation, ChainSigner, ChainStatus, ChainTarget, enqueue, enqueue_after,
+    get_signer_chain_backend, is_chain_unavailable, map_blockchain_error, resolve_user_deployment,
+};
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{user, user_major, wallet};
+use crate::middleware::permission;
+
+/// Users accepted by one `POST /api/v1/users/batch` request
+pub const BATCH_MAX_USERS: usize = 500;
+
+/// Role the permission checks use for a stored role
+pub fn user_role(role: &RoleEnum) -> UserRole {
+    match role {
+        RoleEnum::Admin => UserRole::ADMIN,
+        RoleEnum::Manager => UserRole::MANAGER,
+        RoleEnum::Student => UserRole::STUDENT,
+        RoleEnum::Teacher => UserRole::TEACHER,
+    }
+}
+
+/// Delete a user with its majors and wallet
+pub async fn delete_user_records<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<(), DbErr> {
+    // Relationships first (foreign key constraints)
+    user_major::Entity::delete_many()
+        .filter(user_major::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    wallet::Entity::delete_many()
+        .filter(wallet::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    user::Entity::delete_by_id(user_id).exec(db).await?;
+    Ok(())
+}
+
+/// Replace the majors of `user_ids` with `major_ids`
+pub async fn replace_user_majors<C: ConnectionTrait>(
+    db: &C,
+    user_ids: &[Uuid],
+    major_ids: &[Uuid],
+) -> Result<(), DbErr> {
+    user_major::Entity::delete_many()
+        .filter(user_major::Column::UserId.is_in(user_ids.to_vec()))
+        .exec(db)
+        .await?;
+
+    let now = Utc::now().naive_utc();
+    let relationships: Vec<user_major::ActiveModel> = user_ids
+        .iter()
+        .flat_map(|user_id| {
+            major_ids
+                .iter()
+                .map(move |major_id| user_major::ActiveModel {
+                    user_id: Set(*user_id),
+                    major_id: Set(*major_id),
+                    create_at: Set(now),
+                    updated_at: Set(now),
+                })
+        })
+        .collect();
+    if !relationships.is_empty() {
+        user_major::Entity::insert_many(relationships)
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Users of `user_ids` the caller may apply `operation` to, and a failed result
+/// for each of the others
+pub async fn load_batch_users(
+    db: &DatabaseConnection,
+    claims: &TokenClaims,
+    user_ids: &[Uuid],
+    operation: &BatchOperation,
+) -> Result<(Vec<user::Model>, Vec<BatchUserResult>)> {
+    let mut found: HashMap<Uuid, user::Model> = user::Entity::find()
+        .filter(user::Column::UserId.is_in(user_ids.to_vec()))
+        .all(db)
+        .await
+        .context("Failed to query users")?
+        .into_iter()
+        .map(|user| (user.user_id, user))
+        .collect();
+
+    let mut users = Vec::new();
+    let mut rejected = Vec::new();
+    for user_id in user_ids {
+        let Some(user) = found.remove(user_id) else {
+            rejected.push(BatchUserResult::failed(
+                *user_id,
+                "User not found".to_string(),
+            ));
+            continue;
+        };
+        if let Err((_, message)) = permission::can_modify_user(claims, &user_role(&user.role)) {
+            rejected.push(BatchUserResult::failed(*user_id, message));
+            continue;
+        }
+
+        let error = match operation {
+            BatchOperation::Deactivate | BatchOperation::Activate
+                if user.role != RoleEnum::Student =>
+            {
+                Some("Only students can be activated or deactivated")
+            }
+            BatchOperation::ChangeRole { .. } if user.role == RoleEnum::Student => {
+                Some("Students cannot change role")
+            }
+            _ => None,
+        };
+        match error {
+            Some(error) => rejected.push(BatchUserResult::failed(*user_id, error.to_string())),
+            None => users.push(user),
+        }
+    }
+
+    Ok((users, rejected))
+}
+
+/// Apply `operation` to `users`, already checked by [`load_batch_users`].
+///
+/// Database changes are made in one statement per table where the users share
+/// them; deletes run per user so one blocked user does not fail the others.
+/// The contract has no batch calls for these operations, so chain writes are
+/// sent one by one over one connection per deployment, signed by `signer`.
+pub async fn apply_batch(
+    db: &DatabaseConnection,
+    signer: ChainSigner,
+    users: Vec<user::Model>,
+    operation: &BatchOperation,
+) -> Result<Vec<BatchUserResult>> {
+    let user_ids: Vec<Uuid> = users.iter().map(|user| user.user_id).collect();
+    if user_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    match operation {
+        BatchOperation::Deactivate | BatchOperation::Activate => {
+            let active = matches!(operation, BatchOperation::Activate);
+            let wallets = load_wallets(db, &user_ids).await?;
+            let mut chain = ChainSender::new(db, signer);
+            let mut results = Vec::with_capacity(users.len());
+            for user in users {
+                let result = match wallets.get(&user.user_id) {
+                    Some(address) => chain
+                        .set_student_active(user.user_id, address, active)
+                        .await
+                        .map(Some),
+                    None => Err("User has no wallet".to_string()),
+                };
+                results.push(to_result(user.user_id, result));
+            }
+            Ok(results)
+        }
+        BatchOperation::Delete => {
+            let mut results = Vec::with_capacity(users.len());
+            for user_id in user_ids {
+                let result = async {
+                    let txn = db.begin().await?;
+                    delete_user_records(&txn, user_id).await?;
+                    txn.commit().await
+                }
+                .await
+                .map(|_| None)
+                .map_err(|e| format!("Failed to delete user: {}", e));
+                results.push(to_result(user_id, result));
+            }
+            Ok(results)
+        }
+        BatchOperation::AssignMajors { major_ids } => {
+            let result = async {
+                let txn = db.begin().await?;
+                replace_user_majors(&txn, &user_ids, major_ids).await?;
+                txn.commit().await
+            }
+            .await
+            .map(|_| None)
+            .map_err(|e| format!("Failed to update majors: {}", e));
+            Ok(user_ids
+                .into_iter()
+                .map(|user_id| to_result(user_id, result.clone()))
+                .collect())
+        }
+        BatchOperation::ResetPassword { password } => {
+            // One hash for the whole batch; bcrypt is deliberately slow
+            let password = password.clone();
+            let hashed_password =
+                tokio::task::spawn_blocking(move || bcrypt::hash(password, bcrypt::DEFAULT_COST))
+                    .await
+                    .context("Failed to hash password")?
+                    .context("Failed to hash password")?;
+
+            let result = user::Entity::update_many()
+                .col_expr(user::Column::Password, Expr::value(hashed_password))
+                .col_expr(user::Column::IsFirstLogin, Expr::value(true))
+                .col_expr(user::Column::UpdateAt, Expr::value(Utc::now().naive_utc()))
+                .filter(user::Column::UserId.is_in(user_ids.clone()))
+                .exec(db)
+                .await
+                .map(|_| None)
+                .map_err(|e| format!("Failed to reset password: {}", e));
+            Ok(user_ids
+                .into_iter()
+                .map(|user_id| to_result(user_id, result.clone()))
+                .collect())
+        }
+        BatchOperation::ChangeRole { role } => {
+            let wallets = load_wallets(db, &user_ids).await?;
+            let mut chain = ChainSender::new(db, signer);
+            let mut results = Vec::with_capacity(users.len());
+            for user in users {
+                let result = match wallets.get(&user.user_id) {
+                    Some(address) => change_role(db, &mut chain, &user, address, role).await,
+                    None => Err("User has no wallet".to_string()),
+                };
+                results.push(to_result(user.user_id, result));
+            }
+            Ok(results)
+        }
+    }
+}
+
+fn to_result(user_id: Uuid, result: Result<Option<ChainStatus>, String>) -> BatchUserResult {
+    match result {
+        Ok(chain_status) => BatchUserResult::succeeded(user_id, chain_status),
+        Err(error) => BatchUserResult::failed(user_id, error),
+    }
+}
+
+async fn load_wallets(db: &DatabaseConnection, user_ids: &[Uuid]) -> Result<HashMap<Uuid, String>> {
+    Ok(wallet::Entity::find()
+        .filter(wallet::Column::UserId.is_in(user_ids.to_vec()))
+        .all(db)
+        .await
+        .context("Failed to query wallets")?
+        .into_iter()
+        .map(|wallet| (wallet.user_id, wallet.address))
+        .collect())
+}
+
+/// Update the role on-chain, then in the database, so a rejected chain write
+/// leaves the user unchanged
+async fn change_role(
+    db: &DatabaseConnection,
+    chain: &mut ChainSender<'_>,
+    user: &user::Model,
+    address: &str,
+    role: &RoleEnum,
+) -> Result<Option<ChainStatus>, String> {
+    if user.role == *role {
+        return Ok(None);
+    }
+
+    let target = resolve_user_deployment(db, &user.user_id)
+        .await
+        .map_err(|e| format!("Failed to resolve chain deployment: {}", e))?;
+    let mut status = ChainStatus::Available;
+    for operation in role_operations(address, &user.role, role) {
+        if chain
+            .send(&target, operation)
+            .await
+            .map_err(|e| map_blockchain_error("Failed to change role on blockchain", e).1)?
+            == ChainStatus::Queued
+        {
+            status = ChainStatus::Queued;
+        }
+    }
+
+    // The role column is a Postgres enum, which the active model casts to
+    let mut active_user: user::ActiveModel = user.clone().into();
+    active_user.role = Set(role.clone());
+    active_user.update_at = Set(Utc::now().naive_utc());
+    active_user
+        .update(db)
+        .await
+        .map_err(|e| format!("Failed to update user: {}", e))?;
+
+    Ok(Some(status))
+}
+
+/// Chain writes moving a staff wallet from role `from` to role `to`. Managers are
+/// tracked by `addManager`/`removeManager`, teachers and admins by `assignRole`.
+fn role_operations(address: &str, from: &RoleEnum, to: &RoleEnum) -> Vec<ChainOperation> {
+    let mut operations = Vec::new();
+    if *from == RoleEnum::Manager {
+        operations.push(ChainOperation::RemoveManager {
+            manager_address: address.to_string(),
+        });
+    }
+
+    let role = match to {
+        RoleEnum::Admin => ROLE_ADMIN,
+        RoleEnum::Teacher => ROLE_TEACHER,
+        RoleEnum::Manager => {
+            operations.push(ChainOperation::AddManager {
+                manager_address: address.to_string(),
+            });
+            // Clear the teacher or admin role held before
+            ROLE_NONE
+        }
+        RoleEnum::Student => return operations,
+    };
+    operations.push(ChainOperation::AssignRole {
+        user_address: address.to_string(),
+        role,
+    });
+
+    operations
+}
+
+/// Sends the chain writes of a batch, connecting once per deployment, and stores
+/// their results like single writes do. Once a node turns out unavailable, the
+/// remaining writes for it are queued without trying it again.
+struct ChainSender<'a> {
+    db: &'a DatabaseConnection,
+    signer: ChainSigner,
+    /// `None` once the deployment's node is unavailable
+    backends: HashMap<Option<Uuid>, Option<Arc<dyn ChainBackend>>>,
+}
+
+impl<'a> ChainSender<'a> {
+    fn new(db: &'a DatabaseConnection, signer: ChainSigner) -> Self {
+        Self {
+            db,
+            signer,
+            backends: HashMap::new(),
+        }
+    }
+
+    async fn backend(&mut self, target: &ChainTarget) -> Result<Option<Arc<dyn ChainBackend>>> {
+        if let Some(backend) = self.backends.get(&target.deployment_id) {
+            return Ok(backend.clone());
+        }
+        let backend = match get_signer_chain_backend(self.db, &self.signer, target).await {
+            Ok(backend) => Some(backend),
+            Err(e) if is_chain_unavailable(&e) => None,
+            Err(e) => return Err(e),
+        };
+        self.backends.insert(target.deployment_id, backend.clone());
+        Ok(backend)
+    }
+
+    async fn send(
+        &mut self,
+        target: &ChainTarget,
+        operation: ChainOperation,
+    ) -> Result<ChainStatus> {
+        let Some(backend) = self.backend(target).await? else {
+            let job_id = enqueue(self.db, target, self.signer, &operation).await?;
+            tracing::warn!(
+                "Queued {} as chain job {} on deployment '{}': node unavailable",
+                operation.kind(),
+                job_id,
+                target.name
+            );
+            return Ok(ChainStatus::Queued);
+        };
+
+        let e = match operation.execute(backend.as_ref()).await {
+            Ok(tx_hash) => {
+                operation
+                    .record_result(self.db, target, backend.as_ref(), tx_hash)
+                    .await?;
+                return Ok(ChainStatus::Available);
+            }
+            Err(e) => e,
+        };
+        match enqueue_after(self.db, target, self.signer, &operation, &e).await? {
+            Some(_) => {
+                self.backends.insert(target.deployment_id, None);
+                Ok(ChainStatus::Queued)
+            }
+            None => Err(e),
+        }
+    }
+
+    /// Activate or deactivate the student with wallet `address` on the student's
+    /// deployment
+    async fn set_student_active(
+        &mut self,
+        user_id: Uuid,
+        address: &str,
+        active: bool,
+    ) -> Result<ChainStatus, String> {
+        let target = resolve_user_deployment(self.db, &user_id)
+            .await
+            .map_err(|e| format!("Failed to resolve chain deployment: {}", e))?;
+        let backend = self
+            .backend(&target)
+            .await
+            .map_err(|e| map_blockchain_error("Failed to connect to blockchain", e).1)?
+            .ok_or_else(|| {
+                "Blockchain node unavailable; the student could not be looked up".to_string()
+            })?;
+
+        let student_id = match backend.get_student_id_by_address(address).await {
+            Ok(0) => return Err("Student is not registered on-chain".to_string()),
+            Ok(student_id) => student_id,
+            Err(e) => {
+                if is_chain_unavailable(&e) {
+                    self.backends.insert(target.deployment_id, None);
+                }
+                return Err(map_blockchain_error("Failed to query student on-chain", e).1);
+            }
+        };
+
+        let (operation, error_context) = if active {
+            (
+                ChainOperation::ActivateStudent { student_id },
+                "Failed to activate student",
+            )
+        } else {
+            (
+                ChainOperation::DeactivateStudent { student_id },
+                "Failed to deactivate student",
+            )
+        };
+        self.send(&target, operation)
+            .await
+            .map_err(|e| map_blockchain_error(error_context, e).1)
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::dto::UserDetailResponse;
+use crate::entities::{user, user_major, wallet};
+
+/// Build the detail responses of `users`, loading the wallets and majors of all
+/// of them in one query each
+pub async fn load_user_details(
+    db: &DatabaseConnection,
+    users: Vec<user::Model>,
+) -> Result<Vec<UserDetailResponse>> {
+    let user_ids: Vec<Uuid> = users.iter().map(|user| user.user_id).collect();
+    if user_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut wallets: HashMap<Uuid, String> = HashMap::new();
+    for wallet in wallet::Entity::find()
+        .filter(wallet::Column::UserId.is_in(user_ids.clone()))
+        .all(db)
+        .await
+        .context("Failed to query wallets")?
+    {
+        wallets.entry(wallet.user_id).or_insert(wallet.address);
+    }
+
+    let mut majors: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for relationship in user_major::Entity::find()
+        .filter(user_major::Column::UserId.is_in(user_ids))
+        .all(db)
+        .await
+        .context("Failed to query user majors")?
+    {
+        majors
+            .entry(relationship.user_id)
+            .or_default()
+            .push(relationship.major_id);
+    }
+
+    Ok(users
+        .into_iter()
+        .map(|user| UserDetailResponse {
+            wallet_address: wallets.remove(&user.user_id),
+            major_ids: majors.remove(&user.user_id).unwrap_or_default(),
+            user_id: user.user_id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            address: user.address,
+            email: user.email,
+            cccd: user.cccd,
+            phone_number: user.phone_number,
+            role: user.role,
+            is_priority: user.is_priority,
+            is_first_login: user.is_first_login,
+            created_at: user.create_at,
+            updated_at: user.update_at,
+        })
+        .collect())
+}
+
+/// [`load_user_details`] for a single user
+pub async fn load_user_detail(
+    db: &DatabaseConnection,
+    user: user::Model,
+) -> Result<UserDetailResponse> {
+    load_user_details(db, vec![user])
+        .await?
+        .pop()
+        .context("User detail missing")
+}
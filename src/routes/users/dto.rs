@@ -1,4 +1,6 @@
+use crate::blockchain::ChainStatus;
 use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::utils::spreadsheet::SpreadsheetFormat;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
@@ -50,21 +52,129 @@ pub struct UserResponse {
     pub wallet_private_key: String,
     pub is_first_login: bool,
     pub created_at: chrono::NaiveDateTime,
+    /// Whether the on-chain registration was sent or queued for later
+    pub chain_status: ChainStatus,
+    /// Transaction sent but not mined yet; the queued job settles it
+    pub tx_hash: Option<String>,
+    /// Chain job to follow at `GET /api/v1/chain/jobs` while queued
+    pub chain_job_id: Option<Uuid>,
 }
 
+/// How bulk-imported students are written to the chain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistrationMode {
+    /// `registerStudentsBatch` per 50 students
+    #[default]
+    Batch,
+    /// Anchor a single Merkle root of all students and keep per-student proofs in the DB
+    Merkle,
+}
+
+impl RegistrationMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RegistrationMode::Batch => "batch",
+            RegistrationMode::Merkle => "merkle",
+        }
+    }
+}
+
+impl std::str::FromStr for RegistrationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "batch" => Ok(RegistrationMode::Batch),
+            "merkle" => Ok(RegistrationMode::Merkle),
+            other => Err(format!(
+                "Invalid registration_mode '{}', expected batch or merkle",
+                other
+            )),
+        }
+    }
+}
+
+/// What a bulk import does with rows whose user already exists, matched by email
+/// or else by student code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExistingUserMode {
+    /// Reject the row
+    #[default]
+    Fail,
+    /// Leave the user as is
+    Skip,
+    /// Overwrite name, address, email, CCCD and phone number (not password or role)
+    Update,
+}
+
+impl ExistingUserMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExistingUserMode::Fail => "fail",
+            ExistingUserMode::Skip => "skip",
+            ExistingUserMode::Update => "update",
+        }
+    }
+}
+
+impl std::str::FromStr for ExistingUserMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fail" => Ok(ExistingUserMode::Fail),
+            "skip" => Ok(ExistingUserMode::Skip),
+            "update" => Ok(ExistingUserMode::Update),
+            other => Err(format!(
+                "Invalid on_existing '{}', expected fail, skip or update",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct BulkImportParams {
+    /// Validate the file and report every row without creating anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ImportTemplateParams {
+    /// `xlsx` (default) or `csv`
+    #[serde(default)]
+    #[param(inline)]
+    pub format: SpreadsheetFormat,
+}
+
+/// Dry-run result of a bulk import
 #[derive(Debug, Serialize, ToSchema)]
-pub struct BulkUserResponse {
+pub struct BulkImportReport {
     pub total_records: usize,
-    pub successful: usize,
-    pub failed: usize,
-    pub errors: Vec<BulkUserError>,
+    pub valid: usize,
+    pub invalid: usize,
+    pub rows: Vec<BulkRowReport>,
+    /// Headers in the file that did not map to any field and would be ignored
+    pub unmapped_columns: Vec<String>,
+    /// Majors that would be created (`create_missing_majors=true`)
+    pub new_majors: Vec<String>,
+    /// `unavailable` if student codes could not be checked on-chain
+    pub chain_status: ChainStatus,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
-pub struct BulkUserError {
+pub struct BulkRowReport {
+    /// Row number in the file, the header being row 1
     pub row: usize,
     pub email: String,
-    pub error: String,
+    pub student_code: Option<String>,
+    pub valid: bool,
+    /// Existing user the row would be skipped or updated for (`on_existing`)
+    pub existing_user_id: Option<Uuid>,
+    pub errors: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -78,6 +188,9 @@ pub struct ExcelUserRow {
     pub phone_number: String,
     pub role: String,
     pub student_code: Option<String>,
+    /// Major names or IDs, resolved by the import
+    pub majors: Vec<String>,
+    pub department: Option<String>,
 }
 
 impl ExcelUserRow {
@@ -174,10 +287,67 @@ pub struct UserListResponse {
 pub struct UserQueryParams {
     #[serde(default = "default_page")]
     pub page: usize,
+    /// At most 100
     #[serde(default = "default_page_size")]
     pub page_size: usize,
     pub role: Option<RoleEnum>,
+    /// Words matched against name, email, phone number, CCCD and student code,
+    /// ignoring case and Vietnamese diacritics; every word has to match
     pub search: Option<String>,
+    /// Users studying this major
+    pub major_id: Option<Uuid>,
+    /// Users studying a major of this department
+    pub department_id: Option<Uuid>,
+    pub is_first_login: Option<bool>,
+    pub is_priority: Option<bool>,
+    /// Created on or after this day
+    pub created_from: Option<chrono::NaiveDate>,
+    /// Created on or before this day
+    pub created_to: Option<chrono::NaiveDate>,
+    /// Students whose on-chain record is active (`true`), or deactivated or
+    /// missing (`false`) on every active deployment, as of the last status sync
+    pub on_chain_active: Option<bool>,
+    #[serde(default)]
+    #[param(inline)]
+    pub sort_by: UserSortColumn,
+    #[serde(default)]
+    #[param(inline)]
+    pub sort_order: SortOrder,
+}
+
+/// Column `GET /api/v1/users` is sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSortColumn {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    FirstName,
+    LastName,
+    Email,
+    PhoneNumber,
+    Cccd,
+    Address,
+    Role,
+    IsPriority,
+    IsFirstLogin,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Filters come from [`UserQueryParams`]; `page` and `page_size` are ignored
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct UserExportParams {
+    /// `xlsx` (default) or `csv`
+    #[serde(default)]
+    #[param(inline)]
+    pub format: SpreadsheetFormat,
 }
 
 fn default_page() -> usize {
@@ -187,3 +357,87 @@ fn default_page() -> usize {
 fn default_page_size() -> usize {
     20
 }
+
+/// Operation applied by `POST /api/v1/users/batch`
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum BatchOperation {
+    /// Deactivate students on-chain
+    Deactivate,
+    /// Activate students on-chain
+    Activate,
+    Delete,
+    /// Replace the users' majors
+    AssignMajors {
+        major_ids: Vec<Uuid>,
+    },
+    /// Set a password the users have to change at their next login
+    ResetPassword {
+        password: String,
+    },
+    /// Admin only; students keep their role, and users cannot become students
+    ChangeRole {
+        role: RoleEnum,
+    },
+}
+
+impl BatchOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchOperation::Deactivate => "deactivate",
+            BatchOperation::Activate => "activate",
+            BatchOperation::Delete => "delete",
+            BatchOperation::AssignMajors { .. } => "assign_majors",
+            BatchOperation::ResetPassword { .. } => "reset_password",
+            BatchOperation::ChangeRole { .. } => "change_role",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchUserRequest {
+    pub user_ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub operation: BatchOperation,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchUserResponse {
+    pub operation: String,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchUserResult>,
+    /// `queued` if any chain write waits for the node to come back
+    pub chain_status: ChainStatus,
+}
+
+/// Outcome for one user of a batch operation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchUserResult {
+    pub user_id: Uuid,
+    pub success: bool,
+    /// Set for users whose operation wrote to the chain
+    pub chain_status: Option<ChainStatus>,
+    pub error: Option<String>,
+}
+
+impl BatchUserResult {
+    pub fn succeeded(user_id: Uuid, chain_status: Option<ChainStatus>) -> Self {
+        Self {
+            user_id,
+            success: true,
+            chain_status,
+            error: None,
+        }
+    }
+
+    pub fn failed(user_id: Uuid, error: String) -> Self {
+        Self {
+            user_id,
+            success: false,
+            chain_status: None,
+            error: Some(error),
+        }
+    }
+}
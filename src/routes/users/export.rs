@@ -0,0 +1,381 @@
+use anyhow::{Context, Result};
+use do_an_lib::structs::token_claims::UserRole;
+use sea_orm::{ActiveEnum, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::blockchain::{
+    ChainBackend, ChainTarget, get_admin_chain_backend, get_default_deployment,
+    is_chain_unavailable,
+};
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{
+    chain_deployment, department, major, merkle_proof, user, user_major, wallet,
+};
+
+/// Users loaded per query while exporting
+pub const EXPORT_PAGE_SIZE: u64 = 500;
+/// Students looked up on-chain at the same time
+const CHAIN_LOOKUP_CONCURRENCY: usize = 16;
+
+/// Column of the user export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportColumn {
+    UserId,
+    FirstName,
+    LastName,
+    Email,
+    PhoneNumber,
+    Cccd,
+    Address,
+    Role,
+    StudentCode,
+    Majors,
+    Departments,
+    WalletAddress,
+    OnChainStatus,
+    IsFirstLogin,
+    CreatedAt,
+}
+
+impl ExportColumn {
+    const ALL: [ExportColumn; 15] = [
+        ExportColumn::UserId,
+        ExportColumn::FirstName,
+        ExportColumn::LastName,
+        ExportColumn::Email,
+        ExportColumn::PhoneNumber,
+        ExportColumn::Cccd,
+        ExportColumn::Address,
+        ExportColumn::Role,
+        ExportColumn::StudentCode,
+        ExportColumn::Majors,
+        ExportColumn::Departments,
+        ExportColumn::WalletAddress,
+        ExportColumn::OnChainStatus,
+        ExportColumn::IsFirstLogin,
+        ExportColumn::CreatedAt,
+    ];
+
+    pub fn header(self) -> &'static str {
+        match self {
+            ExportColumn::UserId => "user_id",
+            ExportColumn::FirstName => "first_name",
+            ExportColumn::LastName => "last_name",
+            ExportColumn::Email => "email",
+            ExportColumn::PhoneNumber => "phone_number",
+            ExportColumn::Cccd => "cccd",
+            ExportColumn::Address => "address",
+            ExportColumn::Role => "role",
+            ExportColumn::StudentCode => "student_code",
+            ExportColumn::Majors => "majors",
+            ExportColumn::Departments => "departments",
+            ExportColumn::WalletAddress => "wallet_address",
+            ExportColumn::OnChainStatus => "on_chain_status",
+            ExportColumn::IsFirstLogin => "is_first_login",
+            ExportColumn::CreatedAt => "created_at",
+        }
+    }
+
+    /// Columns `role` may export; managers do not get CCCDs
+    pub fn visible_to(role: &UserRole) -> Vec<ExportColumn> {
+        ExportColumn::ALL
+            .into_iter()
+            .filter(|column| *role == UserRole::ADMIN || *column != ExportColumn::Cccd)
+            .collect()
+    }
+}
+
+/// Registration of a student, as shown in the `on_chain_status` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StudentChainStatus {
+    Active,
+    Inactive,
+    /// Only anchored in a Merkle batch
+    Merkle,
+    NotRegistered,
+    /// The deployment's node could not be reached
+    Unavailable,
+}
+
+impl StudentChainStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            StudentChainStatus::Active => "active",
+            StudentChainStatus::Inactive => "inactive",
+            StudentChainStatus::Merkle => "merkle",
+            StudentChainStatus::NotRegistered => "not_registered",
+            StudentChainStatus::Unavailable => "unavailable",
+        }
+    }
+}
+
+struct ExportRow {
+    user: user::Model,
+    wallet_address: Option<String>,
+    majors: Vec<String>,
+    departments: Vec<String>,
+    student_code: Option<String>,
+    /// `None` for users who are not students
+    chain_status: Option<StudentChainStatus>,
+}
+
+impl ExportRow {
+    fn cell(&self, column: ExportColumn) -> String {
+        match column {
+            ExportColumn::UserId => self.user.user_id.to_string(),
+            ExportColumn::FirstName => self.user.first_name.clone(),
+            ExportColumn::LastName => self.user.last_name.clone(),
+            ExportColumn::Email => self.user.email.clone(),
+            ExportColumn::PhoneNumber => self.user.phone_number.clone(),
+            ExportColumn::Cccd => self.user.cccd.clone(),
+            ExportColumn::Address => self.user.address.clone(),
+            ExportColumn::Role => self.user.role.to_value(),
+            ExportColumn::StudentCode => self.student_code.clone().unwrap_or_default(),
+            ExportColumn::Majors => self.majors.join("; "),
+            ExportColumn::Departments => self.departments.join("; "),
+            ExportColumn::WalletAddress => self.wallet_address.clone().unwrap_or_default(),
+            ExportColumn::OnChainStatus => self
+                .chain_status
+                .map(|status| status.as_str().to_string())
+                .unwrap_or_default(),
+            ExportColumn::IsFirstLogin => self.user.is_first_login.to_string(),
+            ExportColumn::CreatedAt => self.user.create_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Builds export rows page by page, reusing departments, deployments and one chain
+/// backend per deployment across pages
+pub struct UserExporter<'a> {
+    db: &'a DatabaseConnection,
+    departments: HashMap<Uuid, department::Model>,
+    /// Active registered deployments
+    deployments: HashMap<Uuid, ChainTarget>,
+    default_target: ChainTarget,
+    /// `None` if the deployment's node is unavailable
+    backends: HashMap<Option<Uuid>, Option<Arc<dyn ChainBackend>>>,
+}
+
+impl<'a> UserExporter<'a> {
+    pub async fn new(db: &'a DatabaseConnection) -> Result<Self> {
+        let departments = department::Entity::find()
+            .all(db)
+            .await
+            .context("Failed to query departments")?
+            .into_iter()
+            .map(|department| (department.department_id, department))
+            .collect();
+        let deployments = chain_deployment::Entity::find()
+            .filter(chain_deployment::Column::IsActive.eq(true))
+            .all(db)
+            .await
+            .context("Failed to query chain deployments")?
+            .into_iter()
+            .map(|deployment| (deployment.deployment_id, ChainTarget::from(deployment)))
+            .collect();
+
+        Ok(Self {
+            db,
+            departments,
+            deployments,
+            default_target: get_default_deployment(db).await?,
+            backends: HashMap::new(),
+        })
+    }
+
+    /// Cells of `users` for `columns`, with wallets, majors and Merkle proofs
+    /// loaded in one query each and students looked up per deployment
+    pub async fn rows(
+        &mut self,
+        users: Vec<user::Model>,
+        columns: &[ExportColumn],
+    ) -> Result<Vec<Vec<String>>> {
+        let user_ids: Vec<Uuid> = users.iter().map(|user| user.user_id).collect();
+
+        let mut wallets: HashMap<Uuid, wallet::Model> = wallet::Entity::find()
+            .filter(wallet::Column::UserId.is_in(user_ids.clone()))
+            .all(self.db)
+            .await
+            .context("Failed to query wallets")?
+            .into_iter()
+            .map(|wallet| (wallet.user_id, wallet))
+            .collect();
+
+        let mut majors: HashMap<Uuid, Vec<major::Model>> = HashMap::new();
+        for (user_major, major) in user_major::Entity::find()
+            .filter(user_major::Column::UserId.is_in(user_ids.clone()))
+            .find_also_related(major::Entity)
+            .all(self.db)
+            .await
+            .context("Failed to query user majors")?
+        {
+            if let Some(major) = major {
+                majors.entry(user_major.user_id).or_default().push(major);
+            }
+        }
+
+        let mut merkle_codes: HashMap<Uuid, String> = merkle_proof::Entity::find()
+            .filter(merkle_proof::Column::UserId.is_in(user_ids))
+            .all(self.db)
+            .await
+            .context("Failed to query Merkle proofs")?
+            .into_iter()
+            .map(|proof| (proof.user_id, proof.student_code))
+            .collect();
+
+        let mut students: HashMap<Option<Uuid>, (ChainTarget, Vec<(Uuid, String)>)> =
+            HashMap::new();
+        for user in users.iter().filter(|user| user.role == RoleEnum::Student) {
+            if let Some(wallet) = wallets.get(&user.user_id) {
+                let user_majors = majors.get(&user.user_id).map(Vec::as_slice);
+                let target = self.resolve_target(wallet, user_majors.unwrap_or_default());
+                students
+                    .entry(target.deployment_id)
+                    .or_insert_with(|| (target, Vec::new()))
+                    .1
+                    .push((user.user_id, wallet.address.clone()));
+            }
+        }
+        let mut statuses: HashMap<Uuid, (Option<String>, StudentChainStatus)> = HashMap::new();
+        for (target, students) in students.into_values() {
+            statuses.extend(self.student_statuses(&target, students).await?);
+        }
+
+        let mut rows = Vec::with_capacity(users.len());
+        for user in users {
+            let wallet_address = wallets.remove(&user.user_id).map(|wallet| wallet.address);
+            let user_majors = majors.remove(&user.user_id).unwrap_or_default();
+            let mut departments: Vec<String> = user_majors
+                .iter()
+                .filter_map(|major| major.department_id)
+                .filter_map(|department_id| self.departments.get(&department_id))
+                .map(|department| department.name.clone())
+                .collect();
+            departments.sort();
+            departments.dedup();
+
+            let merkle_code = merkle_codes.remove(&user.user_id);
+            let (student_code, chain_status) = match statuses.remove(&user.user_id) {
+                Some((None, StudentChainStatus::NotRegistered)) if merkle_code.is_some() => {
+                    (merkle_code, Some(StudentChainStatus::Merkle))
+                }
+                Some((code, status)) => (code.or(merkle_code), Some(status)),
+                None if user.role == RoleEnum::Student => {
+                    (merkle_code, Some(StudentChainStatus::NotRegistered))
+                }
+                None => (None, None),
+            };
+
+            let row = ExportRow {
+                user,
+                wallet_address,
+                majors: user_majors.into_iter().map(|major| major.name).collect(),
+                departments,
+                student_code,
+                chain_status,
+            };
+            rows.push(columns.iter().map(|column| row.cell(*column)).collect());
+        }
+
+        Ok(rows)
+    }
+
+    /// Deployment of a user, as [`crate::blockchain::resolve_user_deployment`]
+    /// resolves it, from the loaded deployments: wallet override, then department
+    /// deployment, then the default
+    fn resolve_target(&self, wallet: &wallet::Model, majors: &[major::Model]) -> ChainTarget {
+        if let Some(target) = wallet
+            .chain_deployment_id
+            .and_then(|deployment_id| self.deployments.get(&deployment_id))
+        {
+            return target.clone();
+        }
+
+        majors
+            .iter()
+            .filter_map(|major| major.department_id)
+            .filter_map(|department_id| self.departments.get(&department_id))
+            .filter_map(|department| department.chain_deployment_id)
+            .filter_map(|deployment_id| self.deployments.get(&deployment_id))
+            .min_by(|a, b| a.name.cmp(&b.name))
+            .unwrap_or(&self.default_target)
+            .clone()
+    }
+
+    /// Student code and status of each `(user_id, wallet address)` registered on
+    /// `target`, looked up a few at a time
+    async fn student_statuses(
+        &mut self,
+        target: &ChainTarget,
+        students: Vec<(Uuid, String)>,
+    ) -> Result<HashMap<Uuid, (Option<String>, StudentChainStatus)>> {
+        let backend = match self.backends.get(&target.deployment_id) {
+            Some(backend) => backend.clone(),
+            None => {
+                let backend = match get_admin_chain_backend(self.db, target).await {
+                    Ok(backend) => Some(backend),
+                    Err(e) if is_chain_unavailable(&e) => None,
+                    Err(e) => return Err(e),
+                };
+                self.backends.insert(target.deployment_id, backend.clone());
+                backend
+            }
+        };
+        let Some(backend) = backend else {
+            return Ok(students
+                .into_iter()
+                .map(|(user_id, _)| (user_id, (None, StudentChainStatus::Unavailable)))
+                .collect());
+        };
+
+        let mut statuses = HashMap::with_capacity(students.len());
+        for chunk in students.chunks(CHAIN_LOOKUP_CONCURRENCY) {
+            let mut lookups = JoinSet::new();
+            for (user_id, address) in chunk.iter().cloned() {
+                let backend = backend.clone();
+                lookups.spawn(async move {
+                    let status = student_status(backend.as_ref(), &address).await;
+                    (user_id, status)
+                });
+            }
+            while let Some(lookup) = lookups.join_next().await {
+                let (user_id, status) = lookup.context("Student lookup failed")?;
+                statuses.insert(user_id, status?);
+            }
+        }
+
+        Ok(statuses)
+    }
+}
+
+/// Student code and status of the student with wallet `address`
+async fn student_status(
+    backend: &dyn ChainBackend,
+    address: &str,
+) -> Result<(Option<String>, StudentChainStatus)> {
+    let lookup = async {
+        let student_id = backend.get_student_id_by_address(address).await?;
+        if student_id == 0 {
+            return anyhow::Ok(None);
+        }
+        backend.get_student(student_id).await.map(Some)
+    }
+    .await;
+
+    match lookup {
+        Ok(Some(student)) => {
+            let status = if student.is_active {
+                StudentChainStatus::Active
+            } else {
+                StudentChainStatus::Inactive
+            };
+            Ok((Some(student.student_code), status))
+        }
+        Ok(None) => Ok((None, StudentChainStatus::NotRegistered)),
+        Err(e) if is_chain_unavailable(&e) => Ok((None, StudentChainStatus::Unavailable)),
+        Err(e) => Err(e.context("Failed to query student on-chain")),
+    }
+}
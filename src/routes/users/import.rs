@@ -0,0 +1,721 @@
+use anyhow::Context;
+use calamine::{DataType, Reader, Sheets, open_workbook_from_rs};
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Cursor;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use super::dto::{ExcelUserRow, ExistingUserMode};
+use crate::blockchain::{ChainStatus, ChainTarget, get_admin_chain_backend, is_chain_unavailable};
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{merkle_proof, user, wallet};
+use crate::utils::spreadsheet::csv_value;
+use crate::utils::text::fold_diacritics;
+
+/// One spreadsheet row, `None` for empty cells
+pub type ImportRow = Vec<Option<String>>;
+
+const OLE2_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const ODS_MIMETYPE: &[u8] = b"application/vnd.oasis.opendocument.spreadsheet";
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+/// Student codes looked up on-chain at the same time
+const CHAIN_LOOKUP_CONCURRENCY: usize = 16;
+
+/// Spreadsheet formats accepted by the bulk import
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Xlsx,
+    Xls,
+    Ods,
+    Csv,
+}
+
+impl ImportFormat {
+    /// Detect the format from the file's magic bytes. The declared content type and
+    /// file name only break ties between zip-based formats and explain rejected text files.
+    pub fn detect(
+        content_type: Option<&str>,
+        file_name: Option<&str>,
+        data: &[u8],
+    ) -> Result<Self, String> {
+        let content_type = content_type.unwrap_or_default().to_lowercase();
+        let extension = file_name
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, extension)| extension.to_lowercase())
+            .unwrap_or_default();
+
+        if data.starts_with(OLE2_MAGIC) {
+            return Ok(ImportFormat::Xls);
+        }
+
+        if data.starts_with(ZIP_MAGIC) {
+            // ODF packages store their mimetype uncompressed as the first zip entry
+            let is_ods = data
+                .get(..128)
+                .unwrap_or(data)
+                .windows(ODS_MIMETYPE.len())
+                .any(|window| window == ODS_MIMETYPE);
+            if is_ods || content_type.contains("opendocument") || extension == "ods" {
+                return Ok(ImportFormat::Ods);
+            }
+            return Ok(ImportFormat::Xlsx);
+        }
+
+        let text = data.strip_prefix(UTF8_BOM).unwrap_or(data);
+        if std::str::from_utf8(text).is_ok() {
+            return Ok(ImportFormat::Csv);
+        }
+
+        if content_type.contains("csv") || content_type.starts_with("text/") || extension == "csv" {
+            Err("CSV files must be UTF-8 encoded".to_string())
+        } else {
+            Err("Unsupported file format, expected .xlsx, .xls, .ods or .csv".to_string())
+        }
+    }
+
+    /// Read every row of the first sheet (or the whole CSV file), header included
+    pub fn read_rows(self, data: Vec<u8>) -> Result<Vec<ImportRow>, String> {
+        match self {
+            ImportFormat::Csv => read_csv(&data),
+            _ => read_workbook(self, data),
+        }
+    }
+}
+
+fn read_workbook(format: ImportFormat, data: Vec<u8>) -> Result<Vec<ImportRow>, String> {
+    let cursor = Cursor::new(data);
+    let mut workbook = match format {
+        ImportFormat::Xlsx => Sheets::Xlsx(
+            open_workbook_from_rs(cursor)
+                .map_err(|e| format!("Failed to open Excel file: {}", e))?,
+        ),
+        ImportFormat::Xls => Sheets::Xls(
+            open_workbook_from_rs(cursor)
+                .map_err(|e| format!("Failed to open Excel file: {}", e))?,
+        ),
+        ImportFormat::Ods => Sheets::Ods(
+            open_workbook_from_rs(cursor)
+                .map_err(|e| format!("Failed to open OpenDocument file: {}", e))?,
+        ),
+        ImportFormat::Csv => unreachable!("CSV files are not workbooks"),
+    };
+
+    let sheet_names = workbook.sheet_names().to_owned();
+    let first_sheet = sheet_names
+        .first()
+        .ok_or_else(|| "Spreadsheet has no sheets".to_string())?;
+
+    let range = workbook
+        .worksheet_range(first_sheet)
+        .map_err(|e| format!("Failed to read sheet: {}", e))?;
+
+    Ok(range
+        .rows()
+        .map(|row| row.iter().map(|cell| cell.as_string()).collect())
+        .collect())
+}
+
+fn read_csv(data: &[u8]) -> Result<Vec<ImportRow>, String> {
+    let data = data.strip_prefix(UTF8_BOM).unwrap_or(data);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(sniff_delimiter(data))
+        .from_reader(data);
+
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| format!("Failed to read CSV: {}", e))?;
+            Ok(record
+                .iter()
+                .map(|value| Some(csv_value(value).to_string()).filter(|value| !value.is_empty()))
+                .collect())
+        })
+        .collect()
+}
+
+/// Pick the delimiter used most in the header line; spreadsheet apps with a
+/// Vietnamese locale export `;`-separated files
+fn sniff_delimiter(data: &[u8]) -> u8 {
+    let header = data.split(|byte| *byte == b'\n').next().unwrap_or_default();
+
+    // `max_by_key` keeps the last maximum, so ties fall back to a comma
+    [b'\t', b';', b',']
+        .into_iter()
+        .max_by_key(|delimiter| header.iter().filter(|byte| *byte == delimiter).count())
+        .unwrap_or(b',')
+}
+
+/// Column of the bulk import format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportField {
+    FirstName,
+    LastName,
+    Address,
+    Email,
+    Password,
+    Cccd,
+    PhoneNumber,
+    Role,
+    StudentCode,
+    /// Major names or IDs, separated by `;` or `,`
+    Majors,
+    /// Department the row's majors are looked up in
+    Department,
+}
+
+impl ImportField {
+    pub const ALL: [ImportField; 11] = [
+        ImportField::FirstName,
+        ImportField::LastName,
+        ImportField::Address,
+        ImportField::Email,
+        ImportField::Password,
+        ImportField::Cccd,
+        ImportField::PhoneNumber,
+        ImportField::Role,
+        ImportField::StudentCode,
+        ImportField::Majors,
+        ImportField::Department,
+    ];
+
+    /// Canonical header, also the key used in the `mapping` form field
+    pub fn key(self) -> &'static str {
+        match self {
+            ImportField::FirstName => "first_name",
+            ImportField::LastName => "last_name",
+            ImportField::Address => "address",
+            ImportField::Email => "email",
+            ImportField::Password => "password",
+            ImportField::Cccd => "cccd",
+            ImportField::PhoneNumber => "phone_number",
+            ImportField::Role => "role",
+            ImportField::StudentCode => "student_code",
+            ImportField::Majors => "majors",
+            ImportField::Department => "department",
+        }
+    }
+
+    /// Other headers recognized for the column, compared after [`normalize_header`]
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            ImportField::FirstName => &["ho", "surname", "familyname"],
+            ImportField::LastName => &["ten", "givenname"],
+            ImportField::Address => &["diachi"],
+            ImportField::Email => &["emailaddress", "mail", "thudientu"],
+            ImportField::Password => &["matkhau"],
+            ImportField::Cccd => &["socccd", "cancuoccongdan", "cmnd", "idnumber"],
+            ImportField::PhoneNumber => &["phone", "sodienthoai", "dienthoai", "sdt"],
+            ImportField::Role => &["vaitro"],
+            ImportField::StudentCode => &["masinhvien", "masv", "mssv", "masosinhvien"],
+            ImportField::Majors => &["major", "nganh", "nganhhoc", "chuyennganh"],
+            ImportField::Department => &["khoa", "faculty"],
+        }
+    }
+
+    /// What the column holds, for the template's instructions
+    pub fn description(self) -> &'static str {
+        match self {
+            ImportField::FirstName => "Family name",
+            ImportField::LastName => "Middle and given name",
+            ImportField::Address => "Home address",
+            ImportField::Email => "Login email, unique per user",
+            ImportField::Password => "Initial password, at least 6 characters",
+            ImportField::Cccd => "Citizen ID (CCCD), unique per user",
+            ImportField::PhoneNumber => "Phone number",
+            ImportField::Role => "student, teacher, admin or manager",
+            ImportField::StudentCode => "Students only; registered on-chain, unique per student",
+            ImportField::Majors => "Major names or IDs, separated by ;",
+            ImportField::Department => "Department the majors are looked up in",
+        }
+    }
+
+    /// Cells of the template's example rows, a student and a teacher
+    pub fn examples(self) -> [&'static str; 2] {
+        match self {
+            ImportField::FirstName => ["Nguyễn", "Trần"],
+            ImportField::LastName => ["Văn An", "Thị Bình"],
+            ImportField::Address => ["123 Nguyễn Trãi, Hà Nội", "456 Lê Lợi, TP. Hồ Chí Minh"],
+            ImportField::Email => ["student1@example.com", "teacher1@example.com"],
+            ImportField::Password => ["password123", "password456"],
+            ImportField::Cccd => ["001200000001", "001200000002"],
+            ImportField::PhoneNumber => ["0912345678", "0912345679"],
+            ImportField::Role => ["student", "teacher"],
+            ImportField::StudentCode => ["SV001", ""],
+            ImportField::Majors | ImportField::Department => ["", ""],
+        }
+    }
+
+    pub fn is_required(self) -> bool {
+        !matches!(
+            self,
+            ImportField::StudentCode | ImportField::Majors | ImportField::Department
+        )
+    }
+
+    fn matches(self, header: &str) -> bool {
+        header == normalize_header(self.key()) || self.aliases().contains(&header)
+    }
+}
+
+/// Header in comparable form: case, Vietnamese diacritics, spaces and
+/// punctuation are ignored, so "Mã sinh viên" matches `masinhvien`
+fn normalize_header(header: &str) -> String {
+    fold_diacritics(header)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Position of each import field in the uploaded sheet, resolved from its header row
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
+    columns: HashMap<ImportField, usize>,
+    /// Headers that did not map to any field; their cells are ignored
+    pub unmapped: Vec<String>,
+}
+
+impl ColumnMapping {
+    /// Map header cells to fields. `overrides` maps a header as written in the file
+    /// to a field and takes precedence over the built-in names and aliases.
+    pub fn from_header(
+        header: &ImportRow,
+        overrides: &HashMap<String, ImportField>,
+    ) -> Result<Self, String> {
+        let overrides: HashMap<String, ImportField> = overrides
+            .iter()
+            .map(|(header, field)| (normalize_header(header), *field))
+            .collect();
+
+        let mut columns: HashMap<ImportField, usize> = HashMap::new();
+        let mut unmapped = Vec::new();
+
+        for (index, cell) in header.iter().enumerate() {
+            let Some(name) = cell
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            else {
+                continue;
+            };
+            let normalized = normalize_header(name);
+
+            let field = overrides.get(&normalized).copied().or_else(|| {
+                ImportField::ALL
+                    .into_iter()
+                    .find(|field| field.matches(&normalized))
+            });
+            let Some(field) = field else {
+                unmapped.push(name.to_string());
+                continue;
+            };
+
+            if let Some(previous) = columns.insert(field, index) {
+                return Err(format!(
+                    "Columns {} and {} both map to {}",
+                    column_label(previous),
+                    column_label(index),
+                    field.key()
+                ));
+            }
+        }
+
+        let missing: Vec<&str> = ImportField::ALL
+            .into_iter()
+            .filter(|field| field.is_required() && !columns.contains_key(field))
+            .map(ImportField::key)
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Missing required columns: {}", missing.join(", ")));
+        }
+
+        Ok(Self { columns, unmapped })
+    }
+
+    /// Trimmed value of `field` in `row`, `None` if the cell is empty or absent
+    pub fn get(&self, row: &ImportRow, field: ImportField) -> Option<String> {
+        let index = *self.columns.get(&field)?;
+        row.get(index)?
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Copy of `row` with the password cell cleared, for storing failed rows
+    pub fn redact(&self, row: &ImportRow) -> ImportRow {
+        let mut row = row.clone();
+        if let Some(cell) = self
+            .columns
+            .get(&ImportField::Password)
+            .and_then(|index| row.get_mut(*index))
+        {
+            *cell = None;
+        }
+        row
+    }
+
+    /// Build and validate the user described by `row`
+    pub fn parse_row(&self, row: &ImportRow) -> Result<ExcelUserRow, String> {
+        let required = |field: ImportField| {
+            self.get(row, field)
+                .ok_or_else(|| format!("Missing value for {}", field.key()))
+        };
+
+        let user_row = ExcelUserRow {
+            first_name: required(ImportField::FirstName)?,
+            last_name: required(ImportField::LastName)?,
+            address: required(ImportField::Address)?,
+            email: required(ImportField::Email)?,
+            password: required(ImportField::Password)?,
+            cccd: required(ImportField::Cccd)?,
+            phone_number: required(ImportField::PhoneNumber)?,
+            role: required(ImportField::Role)?,
+            student_code: self.get(row, ImportField::StudentCode),
+            majors: self
+                .get(row, ImportField::Majors)
+                .map(|majors| {
+                    majors
+                        .split([';', ','])
+                        .map(str::trim)
+                        .filter(|major| !major.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            department: self.get(row, ImportField::Department),
+        };
+
+        user_row.validate()?;
+        Ok(user_row)
+    }
+}
+
+/// Spreadsheet-style column label (`A`, `B`, ..., `AA`) for error messages
+fn column_label(index: usize) -> String {
+    let mut label = String::new();
+    let mut index = index + 1;
+    while index > 0 {
+        let remainder = (index - 1) % 26;
+        label.insert(0, (b'A' + remainder as u8) as char);
+        index = (index - 1) / 26;
+    }
+    label
+}
+
+/// Data row that parsed and validated, with its 1-based row number in the file
+#[derive(Debug)]
+pub struct ParsedRow {
+    pub row: usize,
+    pub user: ExcelUserRow,
+    /// Cells as uploaded, password cleared (see [`ColumnMapping::redact`])
+    pub cells: ImportRow,
+    /// Majors resolved from the `majors` column, filled in before the import runs
+    pub major_ids: Vec<Uuid>,
+}
+
+/// Problems found per row number
+pub type RowIssues = BTreeMap<usize, Vec<String>>;
+
+/// Flag rows whose email, CCCD or student code repeats an earlier row of the file
+pub fn find_file_duplicates(rows: &[ParsedRow]) -> RowIssues {
+    let mut issues = RowIssues::new();
+    let mut emails: HashMap<String, usize> = HashMap::new();
+    let mut cccds: HashMap<&str, usize> = HashMap::new();
+    let mut student_codes: HashMap<&str, usize> = HashMap::new();
+
+    for parsed in rows {
+        let mut duplicate = |field: &str, first: Option<usize>| {
+            if let Some(first) = first {
+                issues
+                    .entry(parsed.row)
+                    .or_default()
+                    .push(format!("Same {} as row {}", field, first));
+            }
+        };
+
+        let email = parsed.user.email.to_lowercase();
+        duplicate("email", emails.get(&email).copied());
+        emails.entry(email).or_insert(parsed.row);
+
+        duplicate("CCCD", cccds.get(parsed.user.cccd.as_str()).copied());
+        cccds.entry(&parsed.user.cccd).or_insert(parsed.row);
+
+        if let Some(student_code) = &parsed.user.student_code {
+            duplicate(
+                "student code",
+                student_codes.get(student_code.as_str()).copied(),
+            );
+            student_codes.entry(student_code).or_insert(parsed.row);
+        }
+    }
+
+    issues
+}
+
+/// Existing account a row refers to
+#[derive(Debug, Clone)]
+pub struct ExistingUser {
+    pub user_id: Uuid,
+    pub wallet_address: String,
+    /// The row's student code is neither on-chain nor in a Merkle batch yet, e.g.
+    /// because registration failed in an earlier import
+    pub needs_registration: bool,
+}
+
+/// Rows checked against existing users, Merkle batches and the chain
+#[derive(Debug)]
+pub struct ExistingCheck {
+    /// Problems that reject a row
+    pub issues: RowIssues,
+    /// Rows without issues that refer to an existing user
+    pub matches: BTreeMap<usize, ExistingUser>,
+    /// `unavailable` if student codes could only be checked in the DB
+    pub chain_status: ChainStatus,
+}
+
+/// Match rows to existing users, by email or else by student code, and flag rows
+/// that clash with other users' CCCDs or student codes.
+///
+/// Emails match case-insensitively. With [`ExistingUserMode::Fail`] a match is an
+/// issue itself. Student codes are looked up on-chain a few at a time, and only
+/// checked in the DB while the node is unavailable, which the result reports.
+pub async fn check_existing(
+    db: &DatabaseConnection,
+    target: &ChainTarget,
+    rows: &[ParsedRow],
+    mode: ExistingUserMode,
+) -> anyhow::Result<ExistingCheck> {
+    let mut users_by_email: HashMap<String, (Uuid, RoleEnum)> = HashMap::new();
+    let mut users_by_cccd: HashMap<String, Uuid> = HashMap::new();
+    // Owner of each taken student code; `None` for wallets outside this database
+    let mut code_owners: HashMap<String, Option<Uuid>> = HashMap::new();
+
+    // Keep each lookup well below Postgres' bind parameter limit
+    for chunk in rows.chunks(1000) {
+        let emails: Vec<String> = chunk.iter().map(|r| r.user.email.to_lowercase()).collect();
+        let existing: Vec<(Uuid, String, RoleEnum)> = user::Entity::find()
+            .select_only()
+            .columns([
+                user::Column::UserId,
+                user::Column::Email,
+                user::Column::Role,
+            ])
+            .filter(Expr::expr(Func::lower(Expr::col(user::Column::Email))).is_in(emails))
+            .into_tuple()
+            .all(db)
+            .await
+            .context("Failed to query existing emails")?;
+        for (user_id, email, role) in existing {
+            users_by_email.insert(email.to_lowercase(), (user_id, role));
+        }
+
+        let cccds: Vec<&str> = chunk.iter().map(|r| r.user.cccd.as_str()).collect();
+        let existing: Vec<(String, Uuid)> = user::Entity::find()
+            .select_only()
+            .columns([user::Column::Cccd, user::Column::UserId])
+            .filter(user::Column::Cccd.is_in(cccds))
+            .into_tuple()
+            .all(db)
+            .await
+            .context("Failed to query existing CCCDs")?;
+        users_by_cccd.extend(existing);
+
+        let student_codes: Vec<&str> = chunk
+            .iter()
+            .filter_map(|r| r.user.student_code.as_deref())
+            .collect();
+        let existing: Vec<(String, Uuid)> = merkle_proof::Entity::find()
+            .select_only()
+            .columns([
+                merkle_proof::Column::StudentCode,
+                merkle_proof::Column::UserId,
+            ])
+            .filter(merkle_proof::Column::StudentCode.is_in(student_codes))
+            .into_tuple()
+            .all(db)
+            .await
+            .context("Failed to query existing student codes")?;
+        code_owners.extend(
+            existing
+                .into_iter()
+                .map(|(code, user_id)| (code, Some(user_id))),
+        );
+    }
+
+    let on_chain = async {
+        let backend = get_admin_chain_backend(db, target).await?;
+        let student_codes: Vec<String> = rows
+            .iter()
+            .filter_map(|r| r.user.student_code.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let mut owners: HashMap<String, String> = HashMap::new();
+        for chunk in student_codes.chunks(CHAIN_LOOKUP_CONCURRENCY) {
+            let mut lookups = JoinSet::new();
+            for student_code in chunk {
+                let backend = backend.clone();
+                let student_code = student_code.clone();
+                lookups.spawn(async move {
+                    let student_id = backend.get_student_id_by_code(&student_code).await?;
+                    if student_id == 0 {
+                        return anyhow::Ok(None);
+                    }
+                    let student = backend.get_student(student_id).await?;
+                    Ok(Some((student_code, student.wallet_address.to_lowercase())))
+                });
+            }
+            while let Some(lookup) = lookups.join_next().await {
+                owners.extend(lookup.context("Student code lookup failed")??);
+            }
+        }
+        anyhow::Ok(owners)
+    }
+    .await;
+
+    let chain_status = match on_chain {
+        Ok(owners) => {
+            let addresses: Vec<&str> = owners.values().map(String::as_str).collect();
+            let mut wallet_users: HashMap<String, Uuid> = HashMap::new();
+            for chunk in addresses.chunks(1000) {
+                let wallets: Vec<(String, Uuid)> = wallet::Entity::find()
+                    .select_only()
+                    .columns([wallet::Column::Address, wallet::Column::UserId])
+                    .filter(wallet::Column::Address.is_in(chunk.to_vec()))
+                    .into_tuple()
+                    .all(db)
+                    .await
+                    .context("Failed to query student wallets")?;
+                wallet_users.extend(wallets);
+            }
+            for (student_code, address) in owners {
+                code_owners
+                    .entry(student_code)
+                    .or_insert_with(|| wallet_users.get(&address).copied());
+            }
+            ChainStatus::Available
+        }
+        Err(e) if is_chain_unavailable(&e) => ChainStatus::Unavailable,
+        Err(e) => return Err(e.context("Failed to check student codes on-chain")),
+    };
+
+    let mut issues = RowIssues::new();
+    let mut matched: Vec<(usize, Uuid, bool)> = Vec::new();
+    for parsed in rows {
+        let mut row_issues = Vec::new();
+        let email_user = users_by_email.get(&parsed.user.email.to_lowercase());
+        let cccd_user = users_by_cccd.get(&parsed.user.cccd);
+        let student_code = parsed.user.student_code.as_ref();
+        let code_owner = student_code.and_then(|code| code_owners.get(code));
+        let existing = email_user
+            .map(|(user_id, _)| *user_id)
+            .or_else(|| code_owner.copied().flatten());
+
+        match existing {
+            Some(_) if mode == ExistingUserMode::Fail => {
+                if email_user.is_some() {
+                    row_issues.push("Email already exists".to_string());
+                }
+                if cccd_user.is_some() {
+                    row_issues.push("CCCD already exists".to_string());
+                }
+                if code_owner.is_some() {
+                    row_issues.push("Student code already exists".to_string());
+                }
+            }
+            Some(user_id) => {
+                if cccd_user.is_some_and(|owner| *owner != user_id) {
+                    row_issues.push("CCCD belongs to another user".to_string());
+                }
+                if code_owner.is_some_and(|owner| *owner != Some(user_id)) {
+                    row_issues.push("Student code belongs to another user".to_string());
+                }
+                if mode == ExistingUserMode::Update
+                    && let Some((_, role)) = email_user
+                    && parsed.user.parse_role().ok().as_ref() != Some(role)
+                {
+                    row_issues.push(format!(
+                        "Existing user has role {:?}; roles are not changed by imports",
+                        role
+                    ));
+                }
+                if row_issues.is_empty() {
+                    // Users matched by student code are students already
+                    let is_student = email_user.is_none_or(|(_, role)| *role == RoleEnum::Student);
+                    let needs_registration =
+                        is_student && student_code.is_some() && code_owner.is_none();
+                    matched.push((parsed.row, user_id, needs_registration));
+                }
+            }
+            None => {
+                if cccd_user.is_some() {
+                    row_issues.push("CCCD already exists".to_string());
+                }
+                if code_owner.is_some() {
+                    row_issues.push("Student code already exists".to_string());
+                }
+            }
+        }
+
+        if !row_issues.is_empty() {
+            issues.insert(parsed.row, row_issues);
+        }
+    }
+
+    // Wallets of matched users, to register their student codes
+    let user_ids: Vec<Uuid> = matched.iter().map(|(_, user_id, _)| *user_id).collect();
+    let mut wallets: HashMap<Uuid, String> = HashMap::new();
+    for chunk in user_ids.chunks(1000) {
+        let found: Vec<(Uuid, String)> = wallet::Entity::find()
+            .select_only()
+            .columns([wallet::Column::UserId, wallet::Column::Address])
+            .filter(wallet::Column::UserId.is_in(chunk.to_vec()))
+            .into_tuple()
+            .all(db)
+            .await
+            .context("Failed to query user wallets")?;
+        wallets.extend(found);
+    }
+
+    // Rows can match one user through different fields, e.g. its email in one row
+    // and its student code in another; only the first of them is applied
+    let mut first_rows: HashMap<Uuid, usize> = HashMap::new();
+    let mut matches = BTreeMap::new();
+    for (row, user_id, needs_registration) in matched {
+        if let Some(first) = first_rows.get(&user_id) {
+            issues.insert(row, vec![format!("Same existing user as row {}", first)]);
+            continue;
+        }
+        first_rows.insert(user_id, row);
+
+        match wallets.get(&user_id) {
+            Some(wallet_address) => {
+                matches.insert(
+                    row,
+                    ExistingUser {
+                        user_id,
+                        wallet_address: wallet_address.clone(),
+                        needs_registration,
+                    },
+                );
+            }
+            None => {
+                issues.insert(row, vec!["Existing user has no wallet".to_string()]);
+            }
+        }
+    }
+
+    Ok(ExistingCheck {
+        issues,
+        matches,
+        chain_status,
+    })
+}
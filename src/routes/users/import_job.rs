@@ -0,0 +1,742 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::sync::Semaphore;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::dto::{ExcelUserRow, ExistingUserMode, RegistrationMode};
+use super::import::{ExistingUser, ImportRow, ParsedRow, check_existing};
+use crate::blockchain::{
+    BlockchainService, ChainOperation, ChainSigner, ChainStatus, ChainTarget, ContractRevertError,
+    StudentLeaf, anchor_merkle_batch, create_merkle_batch, send_or_enqueue,
+};
+use crate::config::APP_CONFIG;
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{import_job, merkle_batch, user, user_major, wallet};
+
+pub const IMPORT_PENDING: &str = "pending";
+pub const IMPORT_RUNNING: &str = "running";
+pub const IMPORT_COMPLETED: &str = "completed";
+pub const IMPORT_FAILED: &str = "failed";
+pub const IMPORT_CANCELLED: &str = "cancelled";
+
+/// Rows created between progress updates (and cancellation checks)
+const PROGRESS_INTERVAL: usize = 25;
+/// Students per `registerStudentsBatch` transaction
+const CHAIN_BATCH_SIZE: usize = 50;
+
+/// Imports processed at the same time; the rest wait as `pending`
+static IMPORT_SLOTS: Lazy<Semaphore> =
+    Lazy::new(|| Semaphore::new(APP_CONFIG.import_max_concurrent_jobs.max(1)));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportRowStatus {
+    Created,
+    /// Existing user updated (`on_existing=update`)
+    Updated,
+    /// Existing user left as is (`on_existing=skip`)
+    Skipped,
+    Failed,
+}
+
+/// Outcome of one row of the file
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowResult {
+    /// Row number in the file, the header being row 1
+    pub row: usize,
+    pub email: String,
+    pub status: ImportRowStatus,
+    /// Also set on failed rows whose account was saved but whose on-chain
+    /// registration failed
+    pub user_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+impl ImportRowResult {
+    pub fn failed(row: usize, email: String, error: String) -> Self {
+        Self {
+            row,
+            email,
+            status: ImportRowStatus::Failed,
+            user_id: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainBatchStatus {
+    Sent,
+    /// Waiting in the chain job queue for the node to come back
+    Queued,
+    Failed,
+}
+
+/// One on-chain write of an import: a `registerStudentsBatch` call, or the
+/// Merkle root anchor in `merkle` registration mode
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportChainBatch {
+    /// File rows of the students in the batch
+    pub rows: Vec<usize>,
+    pub student_count: usize,
+    pub status: ChainBatchStatus,
+    pub tx_hash: Option<String>,
+    /// Chain job to follow at `GET /api/v1/chain/jobs` while queued
+    pub chain_job_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+/// Row rejected before the job starts, with its cells for the error file
+pub struct RejectedRow {
+    pub result: ImportRowResult,
+    pub cells: ImportRow,
+}
+
+/// Validated rows handed to the worker; kept in memory only, since they carry
+/// plaintext passwords
+pub struct ImportInput {
+    pub rows: Vec<ParsedRow>,
+    pub registration_mode: RegistrationMode,
+    pub existing_user_mode: ExistingUserMode,
+    pub target: ChainTarget,
+}
+
+/// Store a pending job for `input`. Rows rejected while parsing the file are
+/// recorded as already processed.
+pub async fn create_import_job(
+    db: &DatabaseConnection,
+    created_by: Uuid,
+    file_name: Option<String>,
+    header: &ImportRow,
+    unmapped_columns: &[String],
+    rejected: Vec<RejectedRow>,
+    input: &ImportInput,
+) -> Result<import_job::Model> {
+    let now = Utc::now().naive_utc();
+    let total_rows = input.rows.len() + rejected.len();
+    let (results, failed_rows): (Vec<ImportRowResult>, BTreeMap<usize, ImportRow>) = rejected
+        .into_iter()
+        .map(|rejected| {
+            let row = rejected.result.row;
+            (rejected.result, (row, rejected.cells))
+        })
+        .unzip();
+
+    import_job::ActiveModel {
+        job_id: Set(Uuid::new_v4()),
+        created_by: Set(Some(created_by)),
+        file_name: Set(file_name),
+        registration_mode: Set(input.registration_mode.as_str().to_string()),
+        status: Set(IMPORT_PENDING.to_string()),
+        total_rows: Set(total_rows as i32),
+        processed_rows: Set(results.len() as i32),
+        successful: Set(0),
+        failed: Set(results.len() as i32),
+        results: Set(serde_json::to_value(&results).context("Failed to encode row results")?),
+        chain_batches: Set(serde_json::Value::Array(Vec::new())),
+        unmapped_columns: Set(serde_json::Value::from(unmapped_columns.to_vec())),
+        merkle_batch_id: Set(None),
+        error: Set(None),
+        cancel_requested: Set(false),
+        created_at: Set(now),
+        started_at: Set(None),
+        finished_at: Set(None),
+        updated_at: Set(now),
+        source_header: Set(serde_json::to_value(header).context("Failed to encode header")?),
+        failed_rows: Set(
+            serde_json::to_value(&failed_rows).context("Failed to encode failed rows")?
+        ),
+        existing_user_mode: Set(input.existing_user_mode.as_str().to_string()),
+    }
+    .insert(db)
+    .await
+    .context("Failed to create import job")
+}
+
+/// Process `input` for a stored job in the background
+pub fn spawn_import_job(
+    db: &'static DatabaseConnection,
+    job: import_job::Model,
+    input: ImportInput,
+) {
+    tokio::spawn(async move {
+        let job_id = job.job_id;
+        let Ok(_permit) = IMPORT_SLOTS.acquire().await else {
+            return;
+        };
+
+        let mut worker = match ImportWorker::start(db, job).await {
+            Ok(Some(worker)) => worker,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to start import job {}: {}", job_id, e);
+                return;
+            }
+        };
+
+        if let Err(e) = worker.run(input).await {
+            tracing::error!("Import job {} failed: {}", job_id, e);
+            if let Err(e) = worker.finish(IMPORT_FAILED, Some(e.to_string())).await {
+                tracing::error!("Failed to record import job {} failure: {}", job_id, e);
+            }
+        }
+    });
+}
+
+/// Student saved by the job, waiting for its on-chain registration
+struct CreatedStudent {
+    row: usize,
+    leaf: StudentLeaf,
+    cells: ImportRow,
+}
+
+struct ImportWorker {
+    db: &'static DatabaseConnection,
+    job: import_job::Model,
+    results: Vec<ImportRowResult>,
+    /// Cells of failed rows by row number, for the error file
+    failed_rows: BTreeMap<usize, ImportRow>,
+    chain_batches: Vec<ImportChainBatch>,
+    merkle_batch_id: Option<Uuid>,
+}
+
+impl ImportWorker {
+    /// Mark the job running, or cancelled if that was requested while it waited
+    async fn start(
+        db: &'static DatabaseConnection,
+        job: import_job::Model,
+    ) -> Result<Option<Self>> {
+        let job = import_job::Entity::find_by_id(job.job_id)
+            .one(db)
+            .await
+            .context("Failed to query import job")?
+            .ok_or_else(|| anyhow::anyhow!("Import job not found"))?;
+        let results = serde_json::from_value(job.results.clone()).unwrap_or_default();
+        let failed_rows = serde_json::from_value(job.failed_rows.clone()).unwrap_or_default();
+
+        let mut worker = Self {
+            db,
+            job,
+            results,
+            failed_rows,
+            chain_batches: Vec::new(),
+            merkle_batch_id: None,
+        };
+
+        if worker.job.cancel_requested {
+            worker.finish(IMPORT_CANCELLED, None).await?;
+            return Ok(None);
+        }
+
+        let now = Utc::now().naive_utc();
+        let mut job: import_job::ActiveModel = worker.job.clone().into();
+        job.status = Set(IMPORT_RUNNING.to_string());
+        job.started_at = Set(Some(now));
+        job.updated_at = Set(now);
+        worker.job = job.update(db).await.context("Failed to start import job")?;
+
+        Ok(Some(worker))
+    }
+
+    async fn run(&mut self, input: ImportInput) -> Result<()> {
+        let ImportInput {
+            rows,
+            registration_mode,
+            existing_user_mode,
+            target,
+        } = input;
+
+        // Checked here rather than in the request, since it calls the contract per student
+        let mut existing = check_existing(self.db, &target, &rows, existing_user_mode).await?;
+
+        let mut students = Vec::new();
+        let mut cancelled = false;
+        for (index, parsed) in rows.into_iter().enumerate() {
+            if index > 0 && index % PROGRESS_INTERVAL == 0 && self.save_progress().await? {
+                cancelled = true;
+                break;
+            }
+
+            if let Some(issues) = existing.issues.remove(&parsed.row) {
+                self.fail_row(
+                    ImportRowResult::failed(parsed.row, parsed.user.email, issues.join("; ")),
+                    parsed.cells,
+                );
+                continue;
+            }
+
+            if let Some(matched) = existing.matches.remove(&parsed.row) {
+                match apply_existing_user(
+                    self.db,
+                    &parsed.user,
+                    &parsed.major_ids,
+                    &matched,
+                    existing_user_mode,
+                )
+                .await
+                {
+                    Ok(student) => {
+                        let status = match existing_user_mode {
+                            ExistingUserMode::Update => ImportRowStatus::Updated,
+                            _ => ImportRowStatus::Skipped,
+                        };
+                        self.results.push(ImportRowResult {
+                            row: parsed.row,
+                            email: parsed.user.email.clone(),
+                            status,
+                            user_id: Some(matched.user_id),
+                            error: None,
+                        });
+                        if let Some(leaf) = student {
+                            students.push(CreatedStudent {
+                                row: parsed.row,
+                                leaf,
+                                cells: parsed.cells,
+                            });
+                        }
+                    }
+                    Err(error) => self.fail_row(
+                        ImportRowResult::failed(parsed.row, parsed.user.email, error),
+                        parsed.cells,
+                    ),
+                }
+                continue;
+            }
+
+            match create_row_user(self.db, &target, &parsed.user, &parsed.major_ids).await {
+                Ok(created) => {
+                    self.results.push(ImportRowResult {
+                        row: parsed.row,
+                        email: parsed.user.email.clone(),
+                        status: ImportRowStatus::Created,
+                        user_id: Some(created.user_id),
+                        error: None,
+                    });
+                    if let Some(leaf) = created.student {
+                        students.push(CreatedStudent {
+                            row: parsed.row,
+                            leaf,
+                            cells: parsed.cells,
+                        });
+                    }
+                }
+                Err(error) => self.fail_row(
+                    ImportRowResult::failed(parsed.row, parsed.user.email, error),
+                    parsed.cells,
+                ),
+            }
+        }
+
+        // Students saved before a cancellation are still registered, so the
+        // database and the chain agree
+        self.save_progress().await?;
+        if !students.is_empty() {
+            match registration_mode {
+                RegistrationMode::Batch => self.register_batches(&target, &students).await?,
+                RegistrationMode::Merkle => self.anchor_students(&target, &students).await?,
+            }
+        }
+
+        let status = if cancelled {
+            IMPORT_CANCELLED
+        } else {
+            IMPORT_COMPLETED
+        };
+        self.finish(status, None).await
+    }
+
+    fn fail_row(&mut self, result: ImportRowResult, cells: ImportRow) {
+        self.failed_rows.insert(result.row, cells);
+        self.results.push(result);
+    }
+
+    /// Mark a saved student's row failed because it is not registered on-chain;
+    /// re-uploading the row with `on_existing=skip` retries the registration
+    fn fail_registration(&mut self, student: &CreatedStudent, error: String) {
+        if let Some(result) = self.results.iter_mut().find(|r| r.row == student.row) {
+            result.status = ImportRowStatus::Failed;
+            result.error = Some(format!(
+                "Account saved but on-chain registration failed: {}",
+                error
+            ));
+        }
+        self.failed_rows.insert(student.row, student.cells.clone());
+    }
+
+    /// Register students with `registerStudentsBatch`, queueing batches while the
+    /// node is unavailable. A batch the contract rejects is retried one student at
+    /// a time, so only the rows it rejects are marked failed. A batch whose
+    /// transaction is still unsettled stays queued by its hash instead: sending its
+    /// students again could register them twice.
+    async fn register_batches(
+        &mut self,
+        target: &ChainTarget,
+        students: &[CreatedStudent],
+    ) -> Result<()> {
+        for chunk in students.chunks(CHAIN_BATCH_SIZE) {
+            let operation = ChainOperation::RegisterStudentsBatch {
+                wallet_addresses: chunk
+                    .iter()
+                    .map(|s| s.leaf.wallet_address.clone())
+                    .collect(),
+                student_codes: chunk.iter().map(|s| s.leaf.student_code.clone()).collect(),
+                full_names: chunk.iter().map(|s| s.leaf.full_name.clone()).collect(),
+                emails: chunk.iter().map(|s| s.leaf.email.clone()).collect(),
+            };
+
+            let mut batch = ImportChainBatch {
+                rows: chunk.iter().map(|s| s.row).collect(),
+                student_count: chunk.len(),
+                status: ChainBatchStatus::Sent,
+                tx_hash: None,
+                chain_job_id: None,
+                error: None,
+            };
+            let mut rejected = false;
+            match send_or_enqueue(self.db, target, ChainSigner::Admin, operation).await {
+                Ok(write) => {
+                    if write.status == ChainStatus::Queued {
+                        batch.status = ChainBatchStatus::Queued;
+                    }
+                    batch.tx_hash = write.tx_hash;
+                    batch.chain_job_id = write.job_id;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to register batch on blockchain: {}", e);
+                    batch.status = ChainBatchStatus::Failed;
+                    batch.error = Some(e.to_string());
+                    rejected = e.downcast_ref::<ContractRevertError>().is_some();
+                    if !rejected {
+                        for student in chunk {
+                            self.fail_registration(student, e.to_string());
+                        }
+                    }
+                }
+            }
+
+            self.chain_batches.push(batch);
+            if rejected {
+                for student in chunk {
+                    self.register_student(target, student).await;
+                }
+            }
+            self.save_progress().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn register_student(&mut self, target: &ChainTarget, student: &CreatedStudent) {
+        let operation = ChainOperation::RegisterStudent {
+            wallet_address: student.leaf.wallet_address.clone(),
+            student_code: student.leaf.student_code.clone(),
+            full_name: student.leaf.full_name.clone(),
+            email: student.leaf.email.clone(),
+        };
+
+        let mut batch = ImportChainBatch {
+            rows: vec![student.row],
+            student_count: 1,
+            status: ChainBatchStatus::Sent,
+            tx_hash: None,
+            chain_job_id: None,
+            error: None,
+        };
+        match send_or_enqueue(self.db, target, ChainSigner::Admin, operation).await {
+            Ok(write) => {
+                if write.status == ChainStatus::Queued {
+                    batch.status = ChainBatchStatus::Queued;
+                }
+                batch.tx_hash = write.tx_hash;
+                batch.chain_job_id = write.job_id;
+            }
+            Err(e) => {
+                batch.status = ChainBatchStatus::Failed;
+                batch.error = Some(e.to_string());
+                self.fail_registration(student, e.to_string());
+            }
+        }
+
+        self.chain_batches.push(batch);
+    }
+
+    /// Store the students' Merkle proofs and anchor a single root for all of them
+    ///
+    /// The proofs are stored either way, so a failed anchor leaves the rows created;
+    /// it can be retried with `POST /api/v1/merkle/batches/{batch_id}/anchor`.
+    async fn anchor_students(
+        &mut self,
+        target: &ChainTarget,
+        students: &[CreatedStudent],
+    ) -> Result<()> {
+        let leaves: Vec<StudentLeaf> = students.iter().map(|s| s.leaf.clone()).collect();
+        let mut batch = ImportChainBatch {
+            rows: students.iter().map(|s| s.row).collect(),
+            student_count: students.len(),
+            status: ChainBatchStatus::Sent,
+            tx_hash: None,
+            chain_job_id: None,
+            error: None,
+        };
+
+        let merkle = create_merkle_batch(self.db, target, &leaves).await?;
+        self.merkle_batch_id = Some(merkle.batch_id);
+        match anchor_merkle_batch(self.db, &merkle).await {
+            Ok(ChainStatus::Queued) => batch.status = ChainBatchStatus::Queued,
+            Ok(_) => {
+                batch.tx_hash = merkle_batch::Entity::find_by_id(merkle.batch_id)
+                    .one(self.db)
+                    .await
+                    .context("Failed to query Merkle batch")?
+                    .and_then(|merkle| merkle.tx_hash);
+            }
+            Err(e) => {
+                tracing::error!("Failed to anchor Merkle root on blockchain: {}", e);
+                batch.status = ChainBatchStatus::Failed;
+                batch.error = Some(e.to_string());
+            }
+        }
+
+        self.chain_batches.push(batch);
+        self.save_progress().await?;
+
+        Ok(())
+    }
+
+    fn active_model(&self) -> Result<import_job::ActiveModel> {
+        let successful = self
+            .results
+            .iter()
+            .filter(|result| result.status != ImportRowStatus::Failed)
+            .count();
+
+        let mut job: import_job::ActiveModel = self.job.clone().into();
+        job.processed_rows = Set(self.results.len() as i32);
+        job.successful = Set(successful as i32);
+        job.failed = Set((self.results.len() - successful) as i32);
+        job.results =
+            Set(serde_json::to_value(&self.results).context("Failed to encode row results")?);
+        job.chain_batches =
+            Set(serde_json::to_value(&self.chain_batches)
+                .context("Failed to encode chain batches")?);
+        job.failed_rows =
+            Set(serde_json::to_value(&self.failed_rows).context("Failed to encode failed rows")?);
+        job.merkle_batch_id = Set(self.merkle_batch_id);
+        job.updated_at = Set(Utc::now().naive_utc());
+
+        Ok(job)
+    }
+
+    /// Store progress, returning whether cancellation was requested meanwhile
+    /// (`cancel_requested` is left unchanged, so the update returns its current value)
+    async fn save_progress(&mut self) -> Result<bool> {
+        self.job = self
+            .active_model()?
+            .update(self.db)
+            .await
+            .context("Failed to update import job")?;
+
+        Ok(self.job.cancel_requested)
+    }
+
+    async fn finish(&mut self, status: &str, error: Option<String>) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let mut job = self.active_model()?;
+        job.status = Set(status.to_string());
+        job.error = Set(error);
+        job.finished_at = Set(Some(now));
+        self.job = job
+            .update(self.db)
+            .await
+            .context("Failed to finish import job")?;
+
+        Ok(())
+    }
+}
+
+struct CreatedUser {
+    user_id: Uuid,
+    /// Set for students with a student code, to be registered on-chain
+    student: Option<StudentLeaf>,
+}
+
+/// Create a row's user, wallet and majors in one transaction, returning the error
+/// to report for the row
+async fn create_row_user(
+    db: &DatabaseConnection,
+    target: &ChainTarget,
+    user_data: &ExcelUserRow,
+    major_ids: &[Uuid],
+) -> Result<CreatedUser, String> {
+    let role = user_data.parse_role()?;
+    let (wallet_address, wallet_private_key) = BlockchainService::generate_wallet()
+        .map_err(|e| format!("Failed to generate wallet: {}", e))?;
+
+    // bcrypt is deliberately slow; keep it off the async workers
+    let password = user_data.password.clone();
+    let hashed_password =
+        tokio::task::spawn_blocking(move || bcrypt::hash(password, bcrypt::DEFAULT_COST))
+            .await
+            .map_err(|e| format!("Failed to hash password: {}", e))?
+            .map_err(|e| format!("Failed to hash password: {}", e))?;
+
+    let user_id = Uuid::new_v4();
+    let now = Utc::now().naive_utc();
+
+    // A failed row must not leave a user without a wallet or majors behind
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    user::ActiveModel {
+        user_id: Set(user_id),
+        first_name: Set(user_data.first_name.clone()),
+        last_name: Set(user_data.last_name.clone()),
+        address: Set(user_data.address.clone()),
+        email: Set(user_data.email.clone()),
+        password: Set(hashed_password),
+        is_priority: Set(false),
+        cccd: Set(user_data.cccd.clone()),
+        phone_number: Set(user_data.phone_number.clone()),
+        is_first_login: Set(true),
+        create_at: Set(now),
+        update_at: Set(now),
+        role: Set(role.clone()),
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| format!("Failed to create user: {}", e))?;
+
+    wallet::ActiveModel {
+        wallet_id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        address: Set(wallet_address.clone()),
+        private_key: Set(wallet_private_key),
+        chain_type: Set("ethereum".to_string()),
+        public_key: Set(wallet_address.clone()),
+        status: Set("active".to_string()),
+        network_id: Set("1".to_string()),
+        last_used_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        is_contract_owner: Set(false),
+        chain_deployment_id: Set(target.deployment_id),
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    assign_majors(&txn, user_id, major_ids).await?;
+
+    txn.commit()
+        .await
+        .map_err(|e| format!("Failed to commit user: {}", e))?;
+
+    let student = match (&role, &user_data.student_code) {
+        (RoleEnum::Student, Some(student_code)) => Some(StudentLeaf {
+            user_id,
+            wallet_address,
+            student_code: student_code.clone(),
+            full_name: format!("{} {}", user_data.first_name, user_data.last_name),
+            email: user_data.email.clone(),
+        }),
+        _ => None,
+    };
+
+    Ok(CreatedUser { user_id, student })
+}
+
+/// Add the user to `major_ids`
+async fn assign_majors<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    major_ids: &[Uuid],
+) -> Result<(), String> {
+    let now = Utc::now().naive_utc();
+    for major_id in major_ids {
+        user_major::ActiveModel {
+            user_id: Set(user_id),
+            major_id: Set(*major_id),
+            create_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .map_err(|e| format!("Failed to assign major: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Skip or update the existing user a row matched, returning its student leaf if
+/// the row's student code still has to be registered on-chain. Updating replaces
+/// the user's majors when the row names any.
+async fn apply_existing_user(
+    db: &DatabaseConnection,
+    user_data: &ExcelUserRow,
+    major_ids: &[Uuid],
+    existing: &ExistingUser,
+    mode: ExistingUserMode,
+) -> Result<Option<StudentLeaf>, String> {
+    let mut user = user::Entity::find_by_id(existing.user_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to load existing user: {}", e))?
+        .ok_or_else(|| "Existing user not found".to_string())?;
+
+    if mode == ExistingUserMode::Update {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut active_model: user::ActiveModel = user.into();
+        active_model.first_name = Set(user_data.first_name.clone());
+        active_model.last_name = Set(user_data.last_name.clone());
+        active_model.address = Set(user_data.address.clone());
+        active_model.email = Set(user_data.email.clone());
+        active_model.cccd = Set(user_data.cccd.clone());
+        active_model.phone_number = Set(user_data.phone_number.clone());
+        active_model.update_at = Set(Utc::now().naive_utc());
+        user = active_model
+            .update(&txn)
+            .await
+            .map_err(|e| format!("Failed to update user: {}", e))?;
+
+        if !major_ids.is_empty() {
+            user_major::Entity::delete_many()
+                .filter(user_major::Column::UserId.eq(user.user_id))
+                .exec(&txn)
+                .await
+                .map_err(|e| format!("Failed to update majors: {}", e))?;
+            assign_majors(&txn, user.user_id, major_ids).await?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit user: {}", e))?;
+    }
+
+    let student = match (&user_data.student_code, existing.needs_registration) {
+        (Some(student_code), true) => Some(StudentLeaf {
+            user_id: user.user_id,
+            wallet_address: existing.wallet_address.clone(),
+            student_code: student_code.clone(),
+            full_name: format!("{} {}", user.first_name, user.last_name),
+            email: user.email,
+        }),
+        _ => None,
+    };
+
+    Ok(student)
+}
@@ -0,0 +1,204 @@
+use anyhow::Context;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+use super::dto::ExcelUserRow;
+use crate::blockchain::ChainTarget;
+use crate::entities::{chain_deployment, department, major};
+use crate::utils::text::fold_diacritics;
+
+/// Major named by a row, either existing or one the import creates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MajorRef {
+    Existing(Uuid),
+    /// Index into [`MajorResolver::new_majors`]
+    New(usize),
+}
+
+/// Major named in the file but missing from the database
+#[derive(Debug, Clone)]
+pub struct NewMajor {
+    pub name: String,
+    /// Department of the first row naming the major
+    pub department_id: Option<Uuid>,
+}
+
+/// Resolves the `majors` and `department` columns of the bulk import against the
+/// majors and departments loaded once per file
+pub struct MajorResolver {
+    majors: Vec<major::Model>,
+    departments: Vec<department::Model>,
+    /// Active deployments by ID
+    deployments: HashMap<Uuid, chain_deployment::Model>,
+    create_missing: bool,
+    /// Majors to create before the rows are imported (`create_missing_majors=true`)
+    pub new_majors: Vec<NewMajor>,
+}
+
+impl MajorResolver {
+    pub async fn load(db: &DatabaseConnection, create_missing: bool) -> anyhow::Result<Self> {
+        let majors = major::Entity::find()
+            .all(db)
+            .await
+            .context("Failed to query majors")?;
+        let departments = department::Entity::find()
+            .all(db)
+            .await
+            .context("Failed to query departments")?;
+        let deployments = chain_deployment::Entity::find()
+            .filter(chain_deployment::Column::IsActive.eq(true))
+            .all(db)
+            .await
+            .context("Failed to query chain deployments")?
+            .into_iter()
+            .map(|deployment| (deployment.deployment_id, deployment))
+            .collect();
+
+        Ok(Self {
+            majors,
+            departments,
+            deployments,
+            create_missing,
+            new_majors: Vec::new(),
+        })
+    }
+
+    /// Resolve a row's majors, by ID or by name within the row's department if it
+    /// names one. Names compare without case or diacritics.
+    pub fn resolve(&mut self, user: &ExcelUserRow) -> Result<Vec<MajorRef>, String> {
+        let department_id = match &user.department {
+            Some(name) => Some(
+                self.departments
+                    .iter()
+                    .find(|department| same_name(&department.name, name))
+                    .map(|department| department.department_id)
+                    .ok_or_else(|| format!("Unknown department '{}'", name))?,
+            ),
+            None => None,
+        };
+
+        let mut refs = Vec::new();
+        for name in &user.majors {
+            let major = match Uuid::parse_str(name) {
+                Ok(major_id) => self
+                    .majors
+                    .iter()
+                    .find(|major| major.major_id == major_id)
+                    .map(|major| MajorRef::Existing(major.major_id))
+                    .ok_or_else(|| format!("Unknown major '{}'", name))?,
+                Err(_) => self.resolve_name(name, department_id)?,
+            };
+            if !refs.contains(&major) {
+                refs.push(major);
+            }
+        }
+
+        Ok(refs)
+    }
+
+    fn resolve_name(
+        &mut self,
+        name: &str,
+        department_id: Option<Uuid>,
+    ) -> Result<MajorRef, String> {
+        let found: Vec<&major::Model> = self
+            .majors
+            .iter()
+            .filter(|major| same_name(&major.name, name))
+            .filter(|major| department_id.is_none_or(|id| major.department_id == Some(id)))
+            .collect();
+        match found.as_slice() {
+            [major] => return Ok(MajorRef::Existing(major.major_id)),
+            [] => {}
+            _ => {
+                return Err(format!(
+                    "Major '{}' exists in several departments; add a department column",
+                    name
+                ));
+            }
+        }
+
+        if !self.create_missing {
+            return Err(format!("Unknown major '{}'", name));
+        }
+
+        let index = match self.new_majors.iter().position(|major| {
+            same_name(&major.name, name)
+                && department_id.is_none_or(|id| major.department_id == Some(id))
+        }) {
+            Some(index) => index,
+            None => {
+                self.new_majors.push(NewMajor {
+                    name: name.to_string(),
+                    department_id,
+                });
+                self.new_majors.len() - 1
+            }
+        };
+        Ok(MajorRef::New(index))
+    }
+
+    /// Deployment of the majors' departments, as for `POST /api/v1/users`;
+    /// `None` means the default deployment
+    pub fn deployment(&self, refs: &[MajorRef]) -> Option<ChainTarget> {
+        refs.iter()
+            .filter_map(|major| match major {
+                MajorRef::Existing(major_id) => self
+                    .majors
+                    .iter()
+                    .find(|m| m.major_id == *major_id)
+                    .and_then(|m| m.department_id),
+                MajorRef::New(index) => self.new_majors[*index].department_id,
+            })
+            .filter_map(|department_id| {
+                self.departments
+                    .iter()
+                    .find(|d| d.department_id == department_id)?
+                    .chain_deployment_id
+            })
+            .filter_map(|deployment_id| self.deployments.get(&deployment_id))
+            .min_by(|a, b| a.name.cmp(&b.name))
+            .cloned()
+            .map(ChainTarget::from)
+    }
+
+    /// Create the new majors at `indexes`, returning their IDs by index
+    pub async fn create_new_majors(
+        &self,
+        db: &DatabaseConnection,
+        indexes: &BTreeSet<usize>,
+    ) -> anyhow::Result<HashMap<usize, Uuid>> {
+        let now = Utc::now().naive_utc();
+        let mut major_ids = HashMap::new();
+        for index in indexes {
+            let new_major = &self.new_majors[*index];
+            let major = major::ActiveModel {
+                major_id: Set(Uuid::new_v4()),
+                name: Set(new_major.name.clone()),
+                founding_date: Set(now),
+                department_id: Set(new_major.department_id),
+                create_at: Set(now),
+                update_at: Set(now),
+            }
+            .insert(db)
+            .await
+            .with_context(|| format!("Failed to create major '{}'", new_major.name))?;
+            major_ids.insert(*index, major.major_id);
+        }
+
+        Ok(major_ids)
+    }
+}
+
+/// Compare names ignoring case, diacritics and repeated whitespace
+fn same_name(a: &str, b: &str) -> bool {
+    let normalize = |name: &str| {
+        fold_diacritics(name)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    normalize(a) == normalize(b)
+}
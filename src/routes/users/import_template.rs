@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use rust_xlsxwriter::{
+    DataValidation, DataValidationErrorStyle, Format, Formula, Workbook, Worksheet,
+};
+use sea_orm::{ActiveEnum, Iterable};
+
+use super::import::ImportField;
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::utils::spreadsheet::{SpreadsheetFormat, write_table};
+
+/// Data rows of the Users sheet covered by the dropdowns
+const TEMPLATE_ROWS: u32 = 1000;
+
+/// General notes listed under the columns on the Instructions sheet
+const NOTES: &[&str] = &[
+    "Columns may be in any order; headers are matched ignoring case, accents and spaces.",
+    "Replace the example rows with your own before uploading.",
+    "Upload the file to POST /api/v1/users/bulk; use dry_run=true to check it first.",
+    "Existing users fail the row by default; send on_existing=skip or on_existing=update instead.",
+    "Majors missing from the system fail the row unless create_missing_majors=true is sent.",
+];
+
+/// Build the bulk import template from [`ImportField`], the definition the importer
+/// reads files with. The workbook adds dropdowns for role, majors and department
+/// and an Instructions sheet; the CSV has the header and example rows only.
+pub fn build_template(
+    format: SpreadsheetFormat,
+    majors: &[String],
+    departments: &[String],
+) -> Result<Vec<u8>> {
+    let header: Vec<String> = ImportField::ALL
+        .into_iter()
+        .map(|field| field.key().to_string())
+        .collect();
+    let examples: Vec<Vec<String>> = (0..2)
+        .map(|index| {
+            ImportField::ALL
+                .into_iter()
+                .map(|field| match field {
+                    // Only the student example gets a major, and only a real one
+                    ImportField::Majors if index == 0 => {
+                        majors.first().cloned().unwrap_or_default()
+                    }
+                    _ => field.examples()[index].to_string(),
+                })
+                .collect()
+        })
+        .collect();
+
+    match format {
+        SpreadsheetFormat::Xlsx => write_template_workbook(&header, &examples, majors, departments),
+        SpreadsheetFormat::Csv => write_table(format, "Users", &header, &examples),
+    }
+}
+
+fn write_template_workbook(
+    header: &[String],
+    examples: &[Vec<String>],
+    majors: &[String],
+    departments: &[String],
+) -> Result<Vec<u8>> {
+    let bold = Format::new().set_bold();
+    // Typed CCCDs, phone numbers and codes keep their leading zeros
+    let text = Format::new().set_num_format("@");
+
+    let mut users = Worksheet::new();
+    users.set_name("Users").context("Invalid worksheet name")?;
+    for (col, (field, name)) in ImportField::ALL.into_iter().zip(header).enumerate() {
+        let col = col as u16;
+        users
+            .set_column_width(col, 22)
+            .context("Failed to size column")?;
+        users
+            .set_column_format(col, &text)
+            .context("Failed to format column")?;
+        users
+            .write_string_with_format(0, col, name, &bold)
+            .context("Failed to write header")?;
+        for (row, example) in examples.iter().enumerate() {
+            if !example[col as usize].is_empty() {
+                users
+                    .write_string_with_format(row as u32 + 1, col, &example[col as usize], &text)
+                    .context("Failed to write example")?;
+            }
+        }
+
+        if let Some(validation) = column_validation(field, majors.len(), departments.len())? {
+            users
+                .add_data_validation(1, col, TEMPLATE_ROWS, col, &validation)
+                .context("Failed to add dropdown")?;
+        }
+    }
+    users
+        .set_freeze_panes(1, 0)
+        .context("Failed to freeze header")?;
+
+    let mut instructions = Worksheet::new();
+    instructions
+        .set_name("Instructions")
+        .context("Invalid worksheet name")?;
+    for (col, (title, width)) in [
+        ("Column", 16),
+        ("Required", 10),
+        ("Also accepted as", 40),
+        ("Description", 60),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        instructions
+            .set_column_width(col as u16, width)
+            .context("Failed to size column")?;
+        instructions
+            .write_string_with_format(0, col as u16, title, &bold)
+            .context("Failed to write header")?;
+    }
+    for (row, field) in ImportField::ALL.into_iter().enumerate() {
+        let row = row as u32 + 1;
+        let required = if field.is_required() { "yes" } else { "no" };
+        let aliases = field.aliases().join(", ");
+        let cells = [field.key(), required, aliases.as_str(), field.description()];
+        for (col, value) in cells.into_iter().enumerate() {
+            instructions
+                .write_string(row, col as u16, value)
+                .context("Failed to write instructions")?;
+        }
+    }
+    let notes_row = ImportField::ALL.len() as u32 + 2;
+    for (index, note) in NOTES.iter().enumerate() {
+        instructions
+            .write_string(notes_row + index as u32, 0, *note)
+            .context("Failed to write instructions")?;
+    }
+
+    // Dropdown values live on a hidden sheet, since an inline list is limited
+    // to 255 characters
+    let mut lists = Worksheet::new();
+    lists.set_name("Lists").context("Invalid worksheet name")?;
+    lists.set_hidden(true);
+    for (col, (title, values)) in [("Majors", majors), ("Departments", departments)]
+        .into_iter()
+        .enumerate()
+    {
+        lists
+            .write_string(0, col as u16, title)
+            .context("Failed to write list")?;
+        for (row, value) in values.iter().enumerate() {
+            lists
+                .write_string(row as u32 + 1, col as u16, value)
+                .context("Failed to write list")?;
+        }
+    }
+
+    let mut workbook = Workbook::new();
+    workbook.push_worksheet(users);
+    workbook.push_worksheet(instructions);
+    workbook.push_worksheet(lists);
+    workbook
+        .save_to_buffer()
+        .context("Failed to build workbook")
+}
+
+/// Dropdown for a column of the Users sheet, listing what the importer accepts
+fn column_validation(
+    field: ImportField,
+    major_count: usize,
+    department_count: usize,
+) -> Result<Option<DataValidation>> {
+    let validation = match field {
+        ImportField::Role => {
+            let roles: Vec<String> = RoleEnum::iter().map(|role| role.to_value()).collect();
+            DataValidation::new()
+                .allow_list_strings(&roles)
+                .context("Failed to list roles")?
+        }
+        // Several majors can be typed separated by `;`, so other values only warn
+        ImportField::Majors if major_count > 0 => DataValidation::new()
+            .allow_list_formula(Formula::new(format!("=Lists!$A$2:$A${}", major_count + 1)))
+            .set_error_style(DataValidationErrorStyle::Information),
+        ImportField::Department if department_count > 0 => DataValidation::new()
+            .allow_list_formula(Formula::new(format!(
+                "=Lists!$B$2:$B${}",
+                department_count + 1
+            ))),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(validation))
+}
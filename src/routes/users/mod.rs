@@ -1,4 +1,12 @@
+pub mod batch;
+pub mod detail;
 pub mod dto;
+pub mod export;
+pub mod import;
+pub mod import_job;
+pub mod import_majors;
+pub mod import_template;
+pub mod query;
 pub mod route;
 
 pub use route::create_route;
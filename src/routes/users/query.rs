@@ -0,0 +1,213 @@
+use anyhow::Result;
+use chrono::{Days, NaiveTime};
+use do_an_lib::structs::token_claims::{TokenClaims, UserRole};
+use sea_orm::sea_query::{Alias, Expr, Func, Query, SelectStatement, SimpleExpr};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder, Select,
+};
+
+use super::dto::{SortOrder, UserQueryParams, UserSortColumn};
+use crate::blockchain::deployment::get_active_targets;
+use crate::entities::sea_orm_active_enums::RoleEnum;
+use crate::entities::{major, merkle_proof, student_chain_status, user, user_major, wallet};
+use crate::utils::text::{diacritic_translation, fold_diacritics};
+
+/// Largest `page_size` of `GET /api/v1/users`
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Users matching `params` that the caller may list, in the requested order;
+/// managers only see students.
+///
+/// Student codes and on-chain status are matched against the copy kept in
+/// `student_chain_status`, so listing users never waits for a node.
+pub async fn user_list_query(
+    db: &DatabaseConnection,
+    auth_claims: &TokenClaims,
+    params: &UserQueryParams,
+) -> Result<Select<user::Entity>> {
+    let mut query = user::Entity::find();
+
+    // If manager, only show students
+    if auth_claims.role == UserRole::MANAGER {
+        query = query.filter(user::Column::Role.eq(RoleEnum::Student));
+    }
+
+    if let Some(role) = &params.role {
+        query = query.filter(user::Column::Role.eq(role.clone()));
+    }
+    if let Some(is_first_login) = params.is_first_login {
+        query = query.filter(user::Column::IsFirstLogin.eq(is_first_login));
+    }
+    if let Some(is_priority) = params.is_priority {
+        query = query.filter(user::Column::IsPriority.eq(is_priority));
+    }
+    if let Some(from) = params.created_from {
+        query = query.filter(user::Column::CreateAt.gte(from.and_time(NaiveTime::MIN)));
+    }
+    if let Some(to) = params
+        .created_to
+        .and_then(|to| to.checked_add_days(Days::new(1)))
+    {
+        query = query.filter(user::Column::CreateAt.lt(to.and_time(NaiveTime::MIN)));
+    }
+
+    if let Some(major_id) = params.major_id {
+        query = query.filter(
+            user::Column::UserId.in_subquery(
+                Query::select()
+                    .column(user_major::Column::UserId)
+                    .from(user_major::Entity)
+                    .and_where(user_major::Column::MajorId.eq(major_id))
+                    .to_owned(),
+            ),
+        );
+    }
+    if let Some(department_id) = params.department_id {
+        query = query.filter(
+            user::Column::UserId.in_subquery(
+                Query::select()
+                    .column((user_major::Entity, user_major::Column::UserId))
+                    .from(user_major::Entity)
+                    .inner_join(
+                        major::Entity,
+                        Expr::col((major::Entity, major::Column::MajorId))
+                            .equals((user_major::Entity, user_major::Column::MajorId)),
+                    )
+                    .and_where(
+                        Expr::col((major::Entity, major::Column::DepartmentId)).eq(department_id),
+                    )
+                    .to_owned(),
+            ),
+        );
+    }
+
+    if let Some(search) = params.search.as_deref().map(str::trim)
+        && !search.is_empty()
+    {
+        query = query.filter(search_condition(search));
+    }
+
+    if let Some(active) = params.on_chain_active {
+        let contracts: Vec<String> = get_active_targets(db)
+            .await?
+            .into_iter()
+            .map(|target| target.contract_address.to_lowercase())
+            .collect();
+        let active_wallets = Query::select()
+            .column(student_chain_status::Column::WalletAddress)
+            .from(student_chain_status::Entity)
+            .and_where(student_chain_status::Column::IsActive.eq(true))
+            .and_where(student_chain_status::Column::ContractAddress.is_in(contracts))
+            .to_owned();
+
+        query = query.filter(user::Column::Role.eq(RoleEnum::Student));
+        query = query.filter(if active {
+            Condition::all().add(with_wallet_in(active_wallets))
+        } else {
+            Condition::all().not().add(with_wallet_in(active_wallets))
+        });
+    }
+
+    let order = match params.sort_order {
+        SortOrder::Asc => Order::Asc,
+        SortOrder::Desc => Order::Desc,
+    };
+    // User ID last, so pages do not overlap on equal values
+    Ok(query
+        .order_by(sort_column(params.sort_by), order)
+        .order_by_asc(user::Column::UserId))
+}
+
+fn sort_column(column: UserSortColumn) -> user::Column {
+    match column {
+        UserSortColumn::CreatedAt => user::Column::CreateAt,
+        UserSortColumn::UpdatedAt => user::Column::UpdateAt,
+        UserSortColumn::FirstName => user::Column::FirstName,
+        UserSortColumn::LastName => user::Column::LastName,
+        UserSortColumn::Email => user::Column::Email,
+        UserSortColumn::PhoneNumber => user::Column::PhoneNumber,
+        UserSortColumn::Cccd => user::Column::Cccd,
+        UserSortColumn::Address => user::Column::Address,
+        UserSortColumn::Role => user::Column::Role,
+        UserSortColumn::IsPriority => user::Column::IsPriority,
+        UserSortColumn::IsFirstLogin => user::Column::IsFirstLogin,
+    }
+}
+
+/// Every word of `search` in one of the searched columns or the student code of
+/// an on-chain or Merkle-registered student, compared without case or diacritics
+fn search_condition(search: &str) -> Condition {
+    let (from, to) = diacritic_translation();
+    let folded = |column: SimpleExpr| -> SimpleExpr {
+        Func::lower(
+            Func::cust(Alias::new("translate"))
+                .arg(column)
+                .arg(from.as_str())
+                .arg(to.as_str()),
+        )
+        .into()
+    };
+
+    let mut condition = Condition::all();
+    for word in fold_diacritics(search).split_whitespace() {
+        let pattern = format!("%{}%", escape_like(word));
+        let mut any = Condition::any();
+        for column in [
+            user::Column::FirstName,
+            user::Column::LastName,
+            user::Column::Email,
+            user::Column::PhoneNumber,
+            user::Column::Cccd,
+        ] {
+            any = any
+                .add(Expr::expr(folded(Expr::col((user::Entity, column)).into())).like(&pattern));
+        }
+        any = any.add(
+            user::Column::UserId.in_subquery(
+                Query::select()
+                    .column(merkle_proof::Column::UserId)
+                    .from(merkle_proof::Entity)
+                    .and_where(
+                        Expr::expr(Func::lower(Expr::col(merkle_proof::Column::StudentCode)))
+                            .like(&pattern),
+                    )
+                    .to_owned(),
+            ),
+        );
+        any = any.add(with_wallet_in(
+            Query::select()
+                .column(student_chain_status::Column::WalletAddress)
+                .from(student_chain_status::Entity)
+                .and_where(
+                    Expr::expr(Func::lower(Expr::col(
+                        student_chain_status::Column::StudentCode,
+                    )))
+                    .like(&pattern),
+                )
+                .to_owned(),
+        ));
+        condition = condition.add(any);
+    }
+    condition
+}
+
+/// Escape the LIKE wildcards of a search word
+fn escape_like(word: &str) -> String {
+    word.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Users whose wallet address, compared in lowercase, is one of the (lowercase)
+/// addresses selected by `addresses`
+fn with_wallet_in(addresses: SelectStatement) -> SimpleExpr {
+    user::Column::UserId.in_subquery(
+        Query::select()
+            .column(wallet::Column::UserId)
+            .from(wallet::Entity)
+            .and_where(
+                Expr::expr(Func::lower(Expr::col(wallet::Column::Address))).in_subquery(addresses),
+            )
+            .to_owned(),
+    )
+}
@@ -1,31 +1,58 @@
 use axum::{
     Json, Router,
     extract::{Multipart, Path, Query},
-    http::StatusCode,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
     routing::{get, post},
 };
-use calamine::{DataType, Reader, Xlsx, open_workbook_from_rs};
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
-use std::io::Cursor;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait};
+use std::collections::{BTreeSet, HashMap};
 use uuid::Uuid;
 use do_an_lib::structs::token_claims::UserRole;
 
+use super::batch::{
+    BATCH_MAX_USERS, apply_batch, delete_user_records, load_batch_users, replace_user_majors,
+    user_role,
+};
+use super::detail::{load_user_detail, load_user_details};
 use super::dto::{
-    BulkUserError, BulkUserResponse, CreateUserRequest, ExcelUserRow,
-    UpdateUserRequest, UserDetailResponse, UserListResponse, UserQueryParams, UserResponse
+    BatchOperation, BatchUserRequest, BatchUserResponse, BatchUserResult, BulkImportParams,
+    BulkImportReport, BulkRowReport, CreateUserRequest, ExistingUserMode, ImportTemplateParams,
+    RegistrationMode, UpdateUserRequest, UserDetailResponse, UserExportParams, UserListResponse,
+    UserQueryParams, UserResponse,
+};
+use super::export::{EXPORT_PAGE_SIZE, ExportColumn, UserExporter};
+use super::import::{
+    ColumnMapping, ImportField, ImportFormat, ImportRow, ParsedRow, check_existing,
+    find_file_duplicates,
+};
+use super::import_job::{
+    ImportInput, ImportRowResult, RejectedRow, create_import_job, spawn_import_job,
+};
+use super::import_majors::{MajorRef, MajorResolver};
+use super::import_template::build_template;
+use super::query::{MAX_PAGE_SIZE, user_list_query};
+use crate::blockchain::{
+    BlockchainService, ChainOperation, ChainSigner, ChainStatus, ChainTarget, enqueue_claimed,
+    get_default_deployment, map_blockchain_error, resolve_deployment_for_majors, send_claimed,
 };
-use crate::blockchain::{BlockchainService, get_admin_blockchain_service, get_user_private_key};
+use crate::config::APP_CONFIG;
 use crate::entities::sea_orm_active_enums::RoleEnum;
-use crate::entities::{user, user_major, wallet};
+use crate::entities::{chain_job, department, major, user, wallet};
 use crate::extractor::AuthClaims;
 use crate::middleware::permission;
+use crate::routes::import_jobs::dto::ImportJobResponse;
 use crate::static_service::DATABASE_CONNECTION;
+use crate::utils::spreadsheet::write_table;
 
 pub fn create_route() -> Router {
     Router::new()
         .route("/api/v1/users", post(create_user).get(get_all_users))
         .route("/api/v1/users/bulk", post(create_users_bulk))
+        .route("/api/v1/users/bulk/template", get(get_import_template))
+        .route("/api/v1/users/export", get(export_users))
+        .route("/api/v1/users/batch", post(batch_users))
         .route(
             "/api/v1/users/{user_id}",
             get(get_user_by_id)
@@ -41,7 +68,10 @@ pub fn create_route() -> Router {
     request_body = CreateUserRequest,
     responses(
         (status = 201, description = "User created successfully", body = UserResponse),
+        (status = 202, description = "User created; the on-chain write is queued because the blockchain node is down or its transaction is not mined yet (`chain_status: queued`)", body = UserResponse),
         (status = 400, description = "Bad request"),
+        (status = 403, description = "Forbidden - Admin/Manager only, managers can only create students, or rejected by the smart contract"),
+        (status = 409, description = "Student already registered on-chain"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Users"
@@ -50,6 +80,9 @@ pub async fn create_user(
     AuthClaims(auth_claims): AuthClaims,
     Json(payload): Json<CreateUserRequest>,
 ) -> Result<(StatusCode, Json<UserResponse>), (StatusCode, String)> {
+    permission::is_admin_or_manager(&auth_claims)?;
+    permission::can_modify_user(&auth_claims, &user_role(&payload.role))?;
+
     let db = DATABASE_CONNECTION
         .get()
         .expect("DATABASE_CONNECTION not set");
@@ -59,21 +92,30 @@ pub async fn create_user(
             format!("Invalid user_id: {}", e),
         )
     })?;
-    let user_private_key = get_user_private_key(db, &user_uuid).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Invalid user_id: {}", e),
-        )
-    })?;
-    let blockchain = BlockchainService::new(&user_private_key)
+
+    // Validate everything before the first write
+    let student_code = match payload.role {
+        RoleEnum::Student => Some(payload.student_code.clone().ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                "Student code is required for students".to_string(),
+            )
+        })?),
+        _ => None,
+    };
+    let mut major_ids = payload.major_ids.clone().unwrap_or_default();
+    major_ids.sort();
+    major_ids.dedup();
+    check_major_ids(db, &major_ids).await?;
+
+    let chain_target = resolve_deployment_for_majors(db, &major_ids)
         .await
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Internal server error: {}", e),
+                format!("Failed to resolve chain deployment: {}", e),
             )
         })?;
-
     let hashed_password = bcrypt::hash(&payload.password, bcrypt::DEFAULT_COST).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -89,10 +131,55 @@ pub async fn create_user(
             )
         })?;
 
+    // Register on blockchain (queued if the node is unavailable)
+    let (operation, error_context) = match (&payload.role, student_code) {
+        (RoleEnum::Student, Some(student_code)) => {
+            let operation = ChainOperation::RegisterStudent {
+                wallet_address: wallet_address.clone(),
+                student_code,
+                full_name: format!("{} {}", payload.first_name, payload.last_name),
+                email: payload.email.clone(),
+            };
+            (operation, "Failed to register student on blockchain")
+        }
+        (RoleEnum::Manager, _) => {
+            // Use addManager instead of assignRole for managers
+            let operation = ChainOperation::AddManager {
+                manager_address: wallet_address.clone(),
+            };
+            (operation, "Failed to add manager on blockchain")
+        }
+        _ => {
+            // For Teacher and Admin, use assignRole (requires owner)
+            let role_code = match payload.role {
+                RoleEnum::Admin => 3,
+                RoleEnum::Teacher => 2,
+                _ => 0,
+            };
+
+            let operation = ChainOperation::AssignRole {
+                user_address: wallet_address.clone(),
+                role: role_code,
+            };
+            (
+                operation,
+                "Failed to assign role on blockchain (you need to be contract owner)",
+            )
+        }
+    };
+
     let user_id = Uuid::new_v4();
     let wallet_id = Uuid::new_v4();
     let now = Utc::now().naive_utc();
 
+    // User, wallet and majors are saved together or not at all
+    let txn = db.begin().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
     let user_model = user::ActiveModel {
         user_id: Set(user_id),
         first_name: Set(payload.first_name.clone()),
@@ -109,7 +196,7 @@ pub async fn create_user(
         role: Set(payload.role.clone()),
     };
 
-    let user = user_model.insert(db).await.map_err(|e| {
+    let user = user_model.insert(&txn).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to create user: {}", e),
@@ -128,84 +215,68 @@ pub async fn create_user(
         last_used_at: Set(None),
         created_at: Set(now),
         updated_at: Set(now),
+        is_contract_owner: Set(false),
+        chain_deployment_id: Set(chain_target.deployment_id),
     };
 
-    wallet_model.insert(db).await.map_err(|e| {
+    wallet_model.insert(&txn).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to create wallet: {}", e),
         )
     })?;
 
-    // Register on blockchain
-    match payload.role {
-        RoleEnum::Student => {
-            let student_code = payload.student_code.ok_or_else(|| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    "Student code is required for students".to_string(),
-                )
-            })?;
-
-            let full_name = format!("{} {}", payload.first_name, payload.last_name);
-
-            blockchain
-                .register_student(&wallet_address, &student_code, &full_name, &payload.email)
-                .await
-                .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Failed to register student on blockchain: {}", e),
-                    )
-                })?;
-        }
-        RoleEnum::Manager => {
-            // Use addManager instead of assignRole for managers
-            blockchain.add_manager(&wallet_address).await.map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to add manager on blockchain: {}", e),
-                )
-            })?;
-        }
-        RoleEnum::Teacher | RoleEnum::Admin => {
-            // For Teacher and Admin, use assignRole (requires owner)
-            let role_code = match payload.role {
-                RoleEnum::Admin => 3,
-                RoleEnum::Teacher => 2,
-                _ => 0,
-            };
+    // Create user-major relationships
+    replace_user_majors(&txn, &[user_id], &major_ids)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create user-major relationship: {}", e),
+            )
+        })?;
 
-            blockchain
-                .assign_role(&wallet_address, role_code)
-                .await
-                .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Failed to assign role on blockchain (you need to be contract owner): {}", e),
-                    )
-                })?;
-        }
-    }
+    // The chain write is queued with the user (transactional outbox) and only
+    // sent once they are committed, so no transaction is held open while it is
+    // mined and nothing is written on-chain for a user that was not saved
+    let job_id = enqueue_claimed(
+        &txn,
+        &chain_target,
+        ChainSigner::User(user_uuid),
+        &operation,
+    )
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to queue chain write: {}", e),
+        )
+    })?;
 
-    // Create user-major relationships
-    if let Some(major_ids) = payload.major_ids {
-        for major_id in major_ids.iter() {
-            let relationship_model = user_major::ActiveModel {
-                user_id: Set(user_id),
-                major_id: Set(*major_id),
-                create_at: Set(now),
-                updated_at: Set(now),
-            };
+    txn.commit().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create user: {}", e),
+        )
+    })?;
 
-            relationship_model.insert(db).await.map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to create user-major relationship: {}", e),
-                )
-            })?;
+    // Transactions that were sent but not mined in time stay queued by their hash
+    // with the user kept, since they may still be mined
+    let write = match send_claimed(db, job_id).await {
+        Ok(write) => write,
+        Err(e) => {
+            // Rejected on-chain: undo the user so the request can be sent again
+            if let Err(undo) = discard_created_user(db, user_id, job_id).await {
+                tracing::error!(
+                    "User {} ({}) was rejected on-chain but could not be removed: {}",
+                    user_id,
+                    wallet_address,
+                    undo
+                );
+            }
+            return Err(map_blockchain_error(error_context, e));
         }
-    }
+    };
 
     let response = UserResponse {
         user_id: user.user_id,
@@ -217,38 +288,96 @@ pub async fn create_user(
         wallet_private_key,
         is_first_login: user.is_first_login,
         created_at: user.create_at,
+        chain_status: write.status,
+        tx_hash: write.tx_hash,
+        chain_job_id: write.job_id,
     };
 
-    Ok((StatusCode::CREATED, Json(response)))
+    let status_code = match write.status {
+        ChainStatus::Queued => StatusCode::ACCEPTED,
+        _ => StatusCode::CREATED,
+    };
+    Ok((status_code, Json(response)))
 }
 
+/// Bulk create users from a spreadsheet (`file` field)
+///
+/// Accepts `.xlsx`, `.xls`, `.ods` and UTF-8 CSV (with or without BOM), detected
+/// from the file contents. Columns are matched by header name in any order, including
+/// Vietnamese headers such as `Họ`, `Tên` or `Mã sinh viên`; an optional `mapping`
+/// field maps other headers to fields, e.g. `{"Mã SV": "student_code"}`.
+///
+/// Students are registered on-chain with `registerStudentsBatch` by default. With
+/// `registration_mode=merkle` only a Merkle root of all students is anchored and each
+/// student's proof is stored for `GET /api/v1/merkle/proofs/{student_code}`.
+///
+/// Rows repeating an email, CCCD or student code of another row, an existing user or
+/// an on-chain student are rejected. Rows for an existing user (same email, or else
+/// same student code) can instead be skipped or updated with `on_existing=skip` or
+/// `on_existing=update`; either way, existing students whose code is not on-chain
+/// yet are registered, so re-uploading a partly failed file finishes it. Managers
+/// can only import students; rows with another role are rejected.
+///
+/// Optional `majors` (names or IDs, separated by `;`) and `department` columns assign
+/// majors to the imported users. Majors missing from the database are rejected, or
+/// created in the row's department with `create_missing_majors=true`. Like a single
+/// user, the students are registered on their majors' department deployment; rows
+/// whose majors belong to another deployment than the file's first such row are
+/// rejected.
+///
+/// With `dry_run=true` the per-row report is returned without creating any account
+/// or sending any transaction.
+///
+/// Otherwise the import runs as a background job; follow its progress, per-row
+/// results and chain transactions at `GET /api/v1/import-jobs/{job_id}`, and
+/// download the failed rows for fixing from `GET /api/v1/import-jobs/{job_id}/errors`.
 #[utoipa::path(
     post,
     path = "/api/v1/users/bulk",
+    params(BulkImportParams),
     request_body(content = String, content_type = "multipart/form-data"),
     responses(
-        (status = 201, description = "Bulk user creation completed", body = BulkUserResponse),
+        (status = 200, description = "Dry run report, nothing was created", body = BulkImportReport),
+        (status = 202, description = "Import job started", body = ImportJobResponse),
         (status = 400, description = "Bad request"),
+        (status = 403, description = "Forbidden - Admin/Manager only"),
         (status = 500, description = "Internal server error")
     ),
+    security(("bearer_auth" = [])),
     tag = "Users"
 )]
 pub async fn create_users_bulk(
+    AuthClaims(auth_claims): AuthClaims,
+    Query(params): Query<BulkImportParams>,
     mut multipart: Multipart,
-) -> Result<(StatusCode, Json<BulkUserResponse>), (StatusCode, String)> {
+) -> Result<Response, (StatusCode, String)> {
+    permission::is_admin_or_manager(&auth_claims)?;
+
     // Get DB and blockchain from global state
     let db = DATABASE_CONNECTION
         .get()
         .expect("DATABASE_CONNECTION not set");
-    let blockchain = get_admin_blockchain_service().await.map_err(|e| {
+    let user_uuid = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid user_id: {}", e),
+        )
+    })?;
+    let default_target = get_default_deployment(db).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to initialize blockchain service: {}", e),
+            format!("Failed to resolve chain deployment: {}", e),
         )
     })?;
     let mut file_data: Option<Vec<u8>> = None;
-
-    // Extract file from multipart
+    let mut file_name: Option<String> = None;
+    let mut file_content_type: Option<String> = None;
+    let mut registration_mode = RegistrationMode::default();
+    let mut existing_user_mode = ExistingUserMode::default();
+    let mut create_missing_majors = false;
+    let mut column_overrides: HashMap<String, ImportField> = HashMap::new();
+
+    // Extract file and options from multipart
     while let Some(field) = multipart.next_field().await.map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
@@ -258,6 +387,8 @@ pub async fn create_users_bulk(
         let name = field.name().unwrap_or("").to_string();
 
         if name == "file" {
+            file_name = field.file_name().map(|s| s.to_string());
+            file_content_type = field.content_type().map(|s| s.to_string());
             let data = field.bytes().await.map_err(|e| {
                 (
                     StatusCode::BAD_REQUEST,
@@ -265,233 +396,421 @@ pub async fn create_users_bulk(
                 )
             })?;
             file_data = Some(data.to_vec());
-            break;
+        } else if name == "registration_mode" {
+            let value = field.text().await.map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to read registration_mode: {}", e),
+                )
+            })?;
+            registration_mode = value.parse().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        } else if name == "on_existing" {
+            let value = field.text().await.map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to read on_existing: {}", e),
+                )
+            })?;
+            existing_user_mode = value.parse().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        } else if name == "create_missing_majors" {
+            let value = field.text().await.map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to read create_missing_majors: {}", e),
+                )
+            })?;
+            create_missing_majors = value.trim().parse().map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "Invalid create_missing_majors '{}', expected true or false",
+                        value
+                    ),
+                )
+            })?;
+        } else if name == "mapping" {
+            let value = field.text().await.map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to read mapping: {}", e),
+                )
+            })?;
+            column_overrides = serde_json::from_str(&value)
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid mapping: {}", e)))?;
         }
     }
 
     let file_data =
         file_data.ok_or_else(|| (StatusCode::BAD_REQUEST, "No file provided".to_string()))?;
 
-    // Parse Excel file
-    let cursor = Cursor::new(file_data);
-    let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor).map_err(|e| {
+    // Parse the spreadsheet (.xlsx, .xls, .ods or CSV)
+    let format = ImportFormat::detect(
+        file_content_type.as_deref(),
+        file_name.as_deref(),
+        &file_data,
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let rows = format
+        .read_rows(file_data)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    // Map columns by header name before reading any data row
+    let header = rows.first().ok_or_else(|| {
         (
             StatusCode::BAD_REQUEST,
-            format!("Failed to open Excel file: {}", e),
+            "File has no header row".to_string(),
         )
     })?;
+    let columns = ColumnMapping::from_header(header, &column_overrides)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
 
-    let sheet_names = workbook.sheet_names().to_owned();
-    let first_sheet = sheet_names.first().ok_or_else(|| {
-        (
-            StatusCode::BAD_REQUEST,
-            "Excel file has no sheets".to_string(),
-        )
-    })?;
+    let mut parsed_rows: Vec<ParsedRow> = Vec::new();
+    let mut reports: Vec<BulkRowReport> = Vec::new();
+    let mut rejected_cells: HashMap<usize, ImportRow> = HashMap::new();
 
-    let range = workbook.worksheet_range(first_sheet).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Failed to read sheet: {}", e),
-        )
-    })?;
-
-    let mut users_data: Vec<ExcelUserRow> = Vec::new();
-    let mut errors: Vec<BulkUserError> = Vec::new();
-
-    // Parse rows (skip header row)
-    for (idx, row) in range.rows().enumerate().skip(1) {
+    // Parse rows (skip header row and blank lines)
+    for (idx, row) in rows.iter().enumerate().skip(1) {
         let row_num = idx + 1;
+        if row.iter().all(Option::is_none) {
+            continue;
+        }
 
-        let parse_result: Result<ExcelUserRow, String> = (|| {
-            let get_cell = |col: usize| -> Result<String, String> {
-                row.get(col)
-                    .ok_or_else(|| format!("Missing column {}", col))?
-                    .as_string()
-                    .map(|s| s.to_string())
-                    .ok_or_else(|| format!("Invalid data in column {}", col))
-            };
-
-            let user_row = ExcelUserRow {
-                first_name: get_cell(0)?,
-                last_name: get_cell(1)?,
-                address: get_cell(2)?,
-                email: get_cell(3)?,
-                password: get_cell(4)?,
-                cccd: get_cell(5)?,
-                phone_number: get_cell(6)?,
-                role: get_cell(7)?,
-                student_code: row
-                    .get(8)
-                    .and_then(|cell| cell.as_string())
-                    .map(|s| s.to_string())
-                    .filter(|s| !s.is_empty()),
-            };
-
-            user_row.validate()?;
-            Ok(user_row)
-        })();
-
-        match parse_result {
-            Ok(user_row) => users_data.push(user_row),
+        // Managers can only import students
+        let parsed = columns.parse_row(row).and_then(|user| {
+            permission::can_modify_user(&auth_claims, &user_role(&user.parse_role()?))
+                .map_err(|(_, message)| message)?;
+            Ok(user)
+        });
+        match parsed {
+            Ok(user) => parsed_rows.push(ParsedRow {
+                row: row_num,
+                user,
+                cells: columns.redact(row),
+                major_ids: Vec::new(),
+            }),
             Err(error) => {
-                errors.push(BulkUserError {
+                reports.push(BulkRowReport {
                     row: row_num,
-                    email: row
-                        .get(3)
-                        .and_then(|c| c.as_string())
-                        .unwrap_or("unknown".to_string())
-                        .to_string(),
-                    error,
+                    email: columns
+                        .get(row, ImportField::Email)
+                        .unwrap_or("unknown".to_string()),
+                    student_code: columns.get(row, ImportField::StudentCode),
+                    valid: false,
+                    existing_user_id: None,
+                    errors: vec![error],
                 });
+                rejected_cells.insert(row_num, columns.redact(row));
             }
         }
     }
 
-    let total_records = users_data.len() + errors.len();
-    let mut successful = 0;
-
-    // Prepare data for batch blockchain registration
-    let mut student_addresses = Vec::new();
-    let mut student_codes = Vec::new();
-    let mut student_names = Vec::new();
-    let mut student_emails = Vec::new();
-
-    // Process each user
-    for user_data in users_data.iter() {
-        // Generate wallet
-        let (wallet_address, wallet_private_key) = match BlockchainService::generate_wallet() {
-            Ok(wallet) => wallet,
-            Err(e) => {
-                errors.push(BulkUserError {
-                    row: 0,
-                    email: user_data.email.clone(),
-                    error: format!("Failed to generate wallet: {}", e),
-                });
-                continue;
-            }
-        };
+    // Reject rows that clash with each other before writing anything
+    let mut issues = find_file_duplicates(&parsed_rows);
 
-        let user_id = Uuid::new_v4();
-        let wallet_id = Uuid::new_v4();
-        let now = Utc::now().naive_utc();
-
-        let hashed_password = match bcrypt::hash(&user_data.password, bcrypt::DEFAULT_COST) {
-            Ok(hash) => hash,
-            Err(e) => {
-                errors.push(BulkUserError {
-                    row: 0,
-                    email: user_data.email.clone(),
-                    error: format!("Failed to hash password: {}", e),
-                });
-                continue;
-            }
-        };
-
-        let role = match user_data.parse_role() {
-            Ok(r) => r,
-            Err(e) => {
-                errors.push(BulkUserError {
-                    row: 0,
-                    email: user_data.email.clone(),
-                    error: e,
-                });
-                continue;
+    // Resolve majors; the file is imported into the deployment of the first row
+    // naming majors, or the default one
+    let mut majors = MajorResolver::load(db, create_missing_majors)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to load majors: {}", e),
+            )
+        })?;
+    let mut row_majors: HashMap<usize, Vec<MajorRef>> = HashMap::new();
+    let mut chain_target: Option<ChainTarget> = None;
+    for parsed in &parsed_rows {
+        match majors.resolve(&parsed.user) {
+            Ok(refs) if refs.is_empty() => {}
+            Ok(refs) => {
+                let row_target = majors
+                    .deployment(&refs)
+                    .unwrap_or_else(|| default_target.clone());
+                let target = chain_target.get_or_insert_with(|| row_target.clone());
+                if *target == row_target {
+                    row_majors.insert(parsed.row, refs);
+                } else {
+                    issues.entry(parsed.row).or_default().push(format!(
+                        "Majors belong to chain deployment '{}' but the file is imported into '{}'; import these rows separately",
+                        row_target.name, target.name
+                    ));
+                }
             }
-        };
-
-        // Insert user into database
-        let user_model = user::ActiveModel {
-            user_id: Set(user_id),
-            first_name: Set(user_data.first_name.clone()),
-            last_name: Set(user_data.last_name.clone()),
-            address: Set(user_data.address.clone()),
-            email: Set(user_data.email.clone()),
-            password: Set(hashed_password),
-            is_priority: Set(false),
-            cccd: Set(user_data.cccd.clone()),
-            phone_number: Set(user_data.phone_number.clone()),
-            is_first_login: Set(true),
-            create_at: Set(now),
-            update_at: Set(now),
-            role: Set(role.clone()),
-        };
-
-        if let Err(e) = user_model.insert(db).await {
-            errors.push(BulkUserError {
-                row: 0,
-                email: user_data.email.clone(),
-                error: format!("Failed to create user: {}", e),
-            });
-            continue;
+            Err(error) => issues.entry(parsed.row).or_default().push(error),
         }
+    }
+    let chain_target = chain_target.unwrap_or(default_target);
 
-        // Create wallet record
-        let wallet_model = wallet::ActiveModel {
-            wallet_id: Set(wallet_id),
-            user_id: Set(user_id),
-            address: Set(wallet_address.clone()),
-            private_key: Set(wallet_private_key.clone()),
-            chain_type: Set("ethereum".to_string()),
-            public_key: Set(wallet_address.clone()),
-            status: Set("active".to_string()),
-            network_id: Set("1".to_string()),
-            last_used_at: Set(None),
-            created_at: Set(now),
-            updated_at: Set(now),
-        };
-
-        if let Err(e) = wallet_model.insert(db).await {
-            errors.push(BulkUserError {
-                row: 0,
-                email: user_data.email.clone(),
-                error: format!("Failed to create wallet: {}", e),
-            });
-            continue;
+    if params.dry_run {
+        let existing = check_existing(db, &chain_target, &parsed_rows, existing_user_mode)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to check existing users: {}", e),
+                )
+            })?;
+        for (row, mut row_issues) in existing.issues {
+            issues.entry(row).or_default().append(&mut row_issues);
         }
 
-        // Collect student data for batch registration
-        if role == RoleEnum::Student {
-            if let Some(student_code) = &user_data.student_code {
-                student_addresses.push(wallet_address.clone());
-                student_codes.push(student_code.clone());
-                student_names.push(format!("{} {}", user_data.first_name, user_data.last_name));
-                student_emails.push(user_data.email.clone());
+        let mut new_major_indexes = BTreeSet::new();
+        for parsed in parsed_rows {
+            let row_issues = issues.remove(&parsed.row).unwrap_or_default();
+            if row_issues.is_empty() {
+                new_major_indexes.extend(new_majors_of(row_majors.get(&parsed.row)));
             }
+            let existing_user_id = existing
+                .matches
+                .get(&parsed.row)
+                .map(|existing| existing.user_id);
+            reports.push(BulkRowReport {
+                row: parsed.row,
+                email: parsed.user.email,
+                student_code: parsed.user.student_code,
+                valid: row_issues.is_empty(),
+                existing_user_id,
+                errors: row_issues,
+            });
         }
+        reports.sort_by_key(|report| report.row);
+
+        let valid = reports.iter().filter(|report| report.valid).count();
+        let report = BulkImportReport {
+            total_records: reports.len(),
+            valid,
+            invalid: reports.len() - valid,
+            rows: reports,
+            unmapped_columns: columns.unmapped,
+            new_majors: new_major_indexes
+                .into_iter()
+                .map(|index| majors.new_majors[index].name.clone())
+                .collect(),
+            chain_status: existing.chain_status,
+        };
+        return Ok((StatusCode::OK, Json(report)).into_response());
+    }
 
-        successful += 1;
+    // Rows that already failed are recorded up front; the worker checks the rest
+    // against existing users and the chain
+    let mut rejected: Vec<RejectedRow> = reports
+        .into_iter()
+        .map(|report| RejectedRow {
+            cells: rejected_cells.remove(&report.row).unwrap_or_default(),
+            result: ImportRowResult::failed(report.row, report.email, report.errors.join("; ")),
+        })
+        .collect();
+    let mut valid_rows = Vec::new();
+    for parsed in parsed_rows {
+        match issues.remove(&parsed.row) {
+            Some(row_issues) => rejected.push(RejectedRow {
+                result: ImportRowResult::failed(
+                    parsed.row,
+                    parsed.user.email,
+                    row_issues.join("; "),
+                ),
+                cells: parsed.cells,
+            }),
+            None => valid_rows.push(parsed),
+        }
     }
 
-    // Batch register students on blockchain (max 50 at a time)
-    if !student_addresses.is_empty() {
-        for chunk in 0..(student_addresses.len() + 49) / 50 {
-            let start = chunk * 50;
-            let end = std::cmp::min(start + 50, student_addresses.len());
-
-            if let Err(e) = blockchain
-                .register_students_batch(
-                    student_addresses[start..end].to_vec(),
-                    student_codes[start..end].to_vec(),
-                    student_names[start..end].to_vec(),
-                    student_emails[start..end].to_vec(),
-                )
-                .await
-            {
-                tracing::error!("Failed to register batch on blockchain: {}", e);
-                // Don't fail the entire operation, just log the error
-            }
+    // Only majors named by rows that will be imported are created
+    let new_major_indexes: BTreeSet<usize> = valid_rows
+        .iter()
+        .flat_map(|parsed| new_majors_of(row_majors.get(&parsed.row)))
+        .collect();
+    let new_major_ids = majors
+        .create_new_majors(db, &new_major_indexes)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create majors: {}", e),
+            )
+        })?;
+    for parsed in &mut valid_rows {
+        if let Some(refs) = row_majors.remove(&parsed.row) {
+            parsed.major_ids = refs
+                .into_iter()
+                .map(|major| match major {
+                    MajorRef::Existing(major_id) => major_id,
+                    MajorRef::New(index) => new_major_ids[&index],
+                })
+                .collect();
         }
     }
 
-    let response = BulkUserResponse {
-        total_records,
-        successful,
-        failed: errors.len(),
-        errors,
+    let input = ImportInput {
+        rows: valid_rows,
+        registration_mode,
+        existing_user_mode,
+        target: chain_target,
     };
+    let job = create_import_job(
+        db,
+        user_uuid,
+        file_name,
+        header,
+        &columns.unmapped,
+        rejected,
+        &input,
+    )
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create import job: {}", e),
+        )
+    })?;
+
+    spawn_import_job(db, job.clone(), input);
+
+    Ok((StatusCode::ACCEPTED, Json(ImportJobResponse::from(job))).into_response())
+}
+
+/// Download a bulk import template (Admin/Manager only)
+///
+/// Generated from the columns the importer reads. The workbook has dropdowns for
+/// role, majors and department, example rows and an Instructions sheet describing
+/// each column and its accepted headers; `format=csv` has the header and example
+/// rows only.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/bulk/template",
+    params(ImportTemplateParams),
+    responses(
+        (status = 200, description = "Import template", content(
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (String = "text/csv")
+        )),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Users"
+)]
+pub async fn get_import_template(
+    AuthClaims(auth_claims): AuthClaims,
+    Query(params): Query<ImportTemplateParams>,
+) -> Result<(StatusCode, HeaderMap, Vec<u8>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    permission::is_admin_or_manager(&auth_claims)?;
+
+    let majors: Vec<String> = major::Entity::find()
+        .select_only()
+        .column(major::Column::Name)
+        .order_by_asc(major::Column::Name)
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get majors: {}", e),
+            )
+        })?;
+    let departments: Vec<String> = department::Entity::find()
+        .select_only()
+        .column(department::Column::Name)
+        .order_by_asc(department::Column::Name)
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get departments: {}", e),
+            )
+        })?;
+
+    let data = build_template(params.format, &majors, &departments).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to build template: {}", e),
+        )
+    })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(params.format.content_type()),
+    );
+    let disposition = format!(
+        "attachment; filename=\"users-import-template.{}\"",
+        params.format.extension()
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&disposition).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid file name: {}", e),
+            )
+        })?,
+    );
+
+    Ok((StatusCode::OK, headers, data))
+}
+
+/// Delete a user whose chain write was rejected, with the failed chain job so it
+/// cannot be retried for a user that no longer exists
+async fn discard_created_user(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    job_id: Uuid,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    delete_user_records(&txn, user_id).await?;
+    chain_job::Entity::delete_by_id(job_id).exec(&txn).await?;
+    txn.commit().await
+}
+
+/// Reject major IDs missing from the database
+async fn check_major_ids(
+    db: &DatabaseConnection,
+    major_ids: &[Uuid],
+) -> Result<(), (StatusCode, String)> {
+    if major_ids.is_empty() {
+        return Ok(());
+    }
+    let found: Vec<Uuid> = major::Entity::find()
+        .filter(major::Column::MajorId.is_in(major_ids.to_vec()))
+        .all(db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?
+        .into_iter()
+        .map(|major| major.major_id)
+        .collect();
+
+    let unknown: Vec<String> = major_ids
+        .iter()
+        .filter(|major_id| !found.contains(major_id))
+        .map(|major_id| major_id.to_string())
+        .collect();
+    if !unknown.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Unknown major IDs: {}", unknown.join(", ")),
+        ));
+    }
+    Ok(())
+}
 
-    Ok((StatusCode::CREATED, Json(response)))
+/// Indexes of the majors a row names that the import has to create
+fn new_majors_of(refs: Option<&Vec<MajorRef>>) -> impl Iterator<Item = usize> + '_ {
+    refs.into_iter().flatten().filter_map(|major| match major {
+        MajorRef::New(index) => Some(*index),
+        MajorRef::Existing(_) => None,
+    })
 }
 
 /// Get all users with pagination and filtering  
@@ -502,6 +821,7 @@ pub async fn create_users_bulk(
     params(UserQueryParams),
     responses(
         (status = 200, description = "Users retrieved successfully", body = UserListResponse),
+        (status = 400, description = "Invalid page or page_size"),
         (status = 403, description = "Forbidden"),
         (status = 500, description = "Internal server error")
     ),
@@ -519,28 +839,27 @@ pub async fn get_all_users(
     // Check permission: Admin or Manager
     permission::is_admin_or_manager(&auth_claims)?;
 
-    let mut query = user::Entity::find();
-
-    // If manager, only show students
-    if auth_claims.role == UserRole::MANAGER {
-        query = query.filter(user::Column::Role.eq(RoleEnum::Student));
+    if params.page == 0 || params.page_size == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "page and page_size must be greater than 0".to_string(),
+        ));
     }
-
-    // Filter by role if provided
-    if let Some(role) = &params.role {
-        query = query.filter(user::Column::Role.eq(role.clone()));
+    if params.page_size > MAX_PAGE_SIZE {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("page_size must be at most {}", MAX_PAGE_SIZE),
+        ));
     }
 
-    // Search by name or email
-    if let Some(search) = &params.search {
-        let search_pattern = format!("%{}%", search);
-        query = query.filter(
-            user::Column::FirstName
-                .contains(&search_pattern)
-                .or(user::Column::LastName.contains(&search_pattern))
-                .or(user::Column::Email.contains(&search_pattern)),
-        );
-    }
+    let query = user_list_query(db, &auth_claims, &params)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to filter users: {}", e),
+            )
+        })?;
 
     // Get total count
     let total = query
@@ -555,9 +874,10 @@ pub async fn get_all_users(
         })?;
 
     // Apply pagination
-    let offset = (params.page - 1) * params.page_size;
+    let offset = (params.page - 1)
+        .checked_mul(params.page_size)
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "page is too large".to_string()))?;
     let users = query
-        .order_by_desc(user::Column::CreateAt)
         .limit(params.page_size as u64)
         .offset(offset as u64)
         .all(db)
@@ -569,55 +889,12 @@ pub async fn get_all_users(
             )
         })?;
 
-    // Convert to response DTOs
-    let mut user_responses = Vec::new();
-    for user_model in users {
-        // Get wallet info
-        let wallet_info = wallet::Entity::find()
-            .filter(wallet::Column::UserId.eq(user_model.user_id))
-            .one(db)
-            .await
-            .map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Database error: {}", e),
-                )
-            })?;
-
-        // Get major IDs
-        let major_relationships = user_major::Entity::find()
-            .filter(user_major::Column::UserId.eq(user_model.user_id))
-            .all(db)
-            .await
-            .map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Database error: {}", e),
-                )
-            })?;
-
-        let major_ids = major_relationships
-            .into_iter()
-            .map(|m| m.major_id)
-            .collect();
-
-        user_responses.push(UserDetailResponse {
-            user_id: user_model.user_id,
-            first_name: user_model.first_name,
-            last_name: user_model.last_name,
-            address: user_model.address,
-            email: user_model.email,
-            cccd: user_model.cccd,
-            phone_number: user_model.phone_number,
-            role: user_model.role,
-            is_priority: user_model.is_priority,
-            is_first_login: user_model.is_first_login,
-            wallet_address: wallet_info.map(|w| w.address),
-            major_ids,
-            created_at: user_model.create_at,
-            updated_at: user_model.update_at,
-        });
-    }
+    let user_responses = load_user_details(db, users).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
 
     Ok((
         StatusCode::OK,
@@ -630,6 +907,125 @@ pub async fn get_all_users(
     ))
 }
 
+/// Export users as a spreadsheet (Admin/Manager only)
+///
+/// Takes the same filters and sorting as `GET /api/v1/users`, without pagination:
+/// every matching user is exported, read from the database in pages, up to
+/// `EXPORT_MAX_ROWS` users. The file is built in memory before it is sent (XLSX
+/// workbooks can only be written whole), so that cap also bounds the memory one
+/// export takes; larger exports must be narrowed with filters. Columns include
+/// the wallet address, majors, departments and, for students, the student code and
+/// on-chain status (`active`, `inactive`, `merkle`, `not_registered`, or
+/// `unavailable` while the node is down). Managers get no CCCD column.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/export",
+    params(UserQueryParams, UserExportParams),
+    responses(
+        (status = 200, description = "Matching users", content(
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (String = "text/csv")
+        )),
+        (status = 400, description = "More users match than one export may contain"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Users"
+)]
+pub async fn export_users(
+    AuthClaims(auth_claims): AuthClaims,
+    Query(params): Query<UserQueryParams>,
+    Query(export): Query<UserExportParams>,
+) -> Result<(StatusCode, HeaderMap, Vec<u8>), (StatusCode, String)> {
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+
+    permission::is_admin_or_manager(&auth_claims)?;
+
+    let columns = ExportColumn::visible_to(&auth_claims.role);
+    let titles: Vec<String> = columns
+        .iter()
+        .map(|column| column.header().to_string())
+        .collect();
+
+    let mut exporter = UserExporter::new(db).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to export users: {}", e),
+        )
+    })?;
+    let mut paginator = user_list_query(db, &auth_claims, &params)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to filter users: {}", e),
+            )
+        })?
+        .paginate(db, EXPORT_PAGE_SIZE);
+    let total = paginator.num_items().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+    if total > APP_CONFIG.export_max_rows {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "{} users match, more than the {} one export may contain; narrow the filters",
+                total, APP_CONFIG.export_max_rows
+            ),
+        ));
+    }
+
+    let mut rows = Vec::with_capacity(total as usize);
+    while let Some(users) = paginator.fetch_and_next().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })? {
+        rows.extend(exporter.rows(users, &columns).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to export users: {}", e),
+            )
+        })?);
+    }
+
+    let data = write_table(export.format, "Users", &titles, &rows).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to build export file: {}", e),
+        )
+    })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(export.format.content_type()),
+    );
+    let disposition = format!(
+        "attachment; filename=\"users-{}.{}\"",
+        Utc::now().format("%Y%m%d"),
+        export.format.extension()
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&disposition).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid file name: {}", e),
+            )
+        })?,
+    );
+
+    Ok((StatusCode::OK, headers, data))
+}
+
 /// Get user by ID
 /// Admin can see all, Manager can see students, users can see themselves
 #[utoipa::path(
@@ -688,51 +1084,12 @@ pub async fn get_user_by_id(
         }
     }
 
-    // Get wallet info
-    let wallet_info = wallet::Entity::find()
-        .filter(wallet::Column::UserId.eq(user_id))
-        .one(db)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Database error: {}", e),
-            )
-        })?;
-
-    // Get major IDs
-    let major_relationships = user_major::Entity::find()
-        .filter(user_major::Column::UserId.eq(user_id))
-        .all(db)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Database error: {}", e),
-            )
-        })?;
-
-    let major_ids = major_relationships
-        .into_iter()
-        .map(|m| m.major_id)
-        .collect();
-
-    let response = UserDetailResponse {
-        user_id: target_user.user_id,
-        first_name: target_user.first_name,
-        last_name: target_user.last_name,
-        address: target_user.address,
-        email: target_user.email,
-        cccd: target_user.cccd,
-        phone_number: target_user.phone_number,
-        role: target_user.role,
-        is_priority: target_user.is_priority,
-        is_first_login: target_user.is_first_login,
-        wallet_address: wallet_info.map(|w| w.address),
-        major_ids,
-        created_at: target_user.create_at,
-        updated_at: target_user.update_at,
-    };
+    let response = load_user_detail(db, target_user).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
 
     Ok((StatusCode::OK, Json(response)))
 }
@@ -839,10 +1196,7 @@ pub async fn update_user(
 
     // Update major relationships if provided
     if let Some(major_ids) = payload.major_ids {
-        // Delete existing relationships
-        user_major::Entity::delete_many()
-            .filter(user_major::Column::UserId.eq(user_id))
-            .exec(db)
+        replace_user_majors(db, &[user_id], &major_ids)
             .await
             .map_err(|e| {
                 (
@@ -850,68 +1204,15 @@ pub async fn update_user(
                     format!("Failed to update majors: {}", e),
                 )
             })?;
-
-        // Create new relationships
-        for major_id in major_ids.iter() {
-            let relationship = user_major::ActiveModel {
-                user_id: Set(user_id),
-                major_id: Set(*major_id),
-                create_at: Set(now),
-                updated_at: Set(now),
-            };
-            relationship.insert(db).await.map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to create major relationship: {}", e),
-                )
-            })?;
-        }
     }
 
     // Get updated user with full details
-    let wallet_info = wallet::Entity::find()
-        .filter(wallet::Column::UserId.eq(user_id))
-        .one(db)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Database error: {}", e),
-            )
-        })?;
-
-    let major_relationships = user_major::Entity::find()
-        .filter(user_major::Column::UserId.eq(user_id))
-        .all(db)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Database error: {}", e),
-            )
-        })?;
-
-    let major_ids = major_relationships
-        .into_iter()
-        .map(|m| m.major_id)
-        .collect();
-
-    let response = UserDetailResponse {
-        user_id: updated_user.user_id,
-        first_name: updated_user.first_name,
-        last_name: updated_user.last_name,
-        address: updated_user.address,
-        email: updated_user.email,
-        cccd: updated_user.cccd,
-        phone_number: updated_user.phone_number,
-        role: updated_user.role,
-        is_priority: updated_user.is_priority,
-        is_first_login: updated_user.is_first_login,
-        wallet_address: wallet_info.map(|w| w.address),
-        major_ids,
-        created_at: updated_user.create_at,
-        updated_at: updated_user.update_at,
-    };
+    let response = load_user_detail(db, updated_user).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
 
     Ok((StatusCode::OK, Json(response)))
 }
@@ -963,47 +1264,146 @@ pub async fn delete_user(
     // Check permission
     permission::can_modify_user(&auth_claims, &target_role)?;
 
-    // Delete user major relationships first (foreign key constraint)
-    user_major::Entity::delete_many()
-        .filter(user_major::Column::UserId.eq(user_id))
-        .exec(db)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to delete user majors: {}", e),
-            )
-        })?;
+    delete_user_records(db, user_id).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete user: {}", e),
+        )
+    })?;
 
-    // Delete wallet
-    wallet::Entity::delete_many()
-        .filter(wallet::Column::UserId.eq(user_id))
-        .exec(db)
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "message": "User deleted successfully",
+            "user_id": user_id
+        })),
+    ))
+}
+
+/// Apply one operation to many users (Admin/Manager only)
+///
+/// Each user is checked like a single update or delete, so managers can only
+/// modify students; users failing the check are reported without stopping the
+/// others. Deactivate/activate apply to students only, change_role to staff only.
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/batch",
+    request_body = BatchUserRequest,
+    responses(
+        (status = 200, description = "Operation applied; see `results` for users it failed for", body = BatchUserResponse),
+        (status = 202, description = "Blockchain node unavailable, some chain writes were queued", body = BatchUserResponse),
+        (status = 400, description = "Bad request"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Users"
+)]
+pub async fn batch_users(
+    AuthClaims(auth_claims): AuthClaims,
+    Json(payload): Json<BatchUserRequest>,
+) -> Result<(StatusCode, Json<BatchUserResponse>), (StatusCode, String)> {
+    permission::is_admin_or_manager(&auth_claims)?;
+
+    let db = DATABASE_CONNECTION
+        .get()
+        .expect("DATABASE_CONNECTION not set");
+    let caller_id = Uuid::parse_str(&auth_claims.user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid user_id: {}", e),
+        )
+    })?;
+
+    let mut user_ids = Vec::with_capacity(payload.user_ids.len());
+    for user_id in payload.user_ids {
+        if !user_ids.contains(&user_id) {
+            user_ids.push(user_id);
+        }
+    }
+    if user_ids.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "user_ids is empty".to_string()));
+    }
+    if user_ids.len() > BATCH_MAX_USERS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("At most {} users can be changed at once", BATCH_MAX_USERS),
+        ));
+    }
+
+    let operation = payload.operation;
+    match &operation {
+        BatchOperation::ChangeRole { role } => {
+            // Only admin can change roles
+            if auth_claims.role != UserRole::ADMIN {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    "Only admin can change user roles".to_string(),
+                ));
+            }
+            if *role == RoleEnum::Student {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Users cannot be changed to students".to_string(),
+                ));
+            }
+        }
+        BatchOperation::ResetPassword { password } if password.len() < 6 => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Password must be at least 6 characters".to_string(),
+            ));
+        }
+        BatchOperation::AssignMajors { major_ids } => check_major_ids(db, major_ids).await?,
+        _ => {}
+    }
+
+    let (users, mut rejected) = load_batch_users(db, &auth_claims, &user_ids, &operation)
         .await
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to delete wallet: {}", e),
+                format!("Database error: {}", e),
             )
         })?;
-
-    // Delete user
-    user::Entity::delete_by_id(user_id)
-        .exec(db)
+    let applied = apply_batch(db, ChainSigner::User(caller_id), users, &operation)
         .await
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to delete user: {}", e),
+                format!("Failed to apply {}: {}", operation.as_str(), e),
             )
         })?;
 
-    Ok((
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "message": "User deleted successfully",
-            "user_id": user_id
-        })),
-    ))
-}
+    // Report in request order
+    rejected.extend(applied);
+    let mut by_user: HashMap<Uuid, BatchUserResult> = rejected
+        .into_iter()
+        .map(|result| (result.user_id, result))
+        .collect();
+    let results: Vec<BatchUserResult> = user_ids
+        .iter()
+        .filter_map(|user_id| by_user.remove(user_id))
+        .collect();
+
+    let succeeded = results.iter().filter(|result| result.success).count();
+    let chain_status = if results
+        .iter()
+        .any(|result| result.chain_status == Some(ChainStatus::Queued))
+    {
+        ChainStatus::Queued
+    } else {
+        ChainStatus::Available
+    };
 
+    let response = BatchUserResponse {
+        operation: operation.as_str().to_string(),
+        total: results.len(),
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+        chain_status,
+    };
+
+    Ok((chain_status.write_status_code(), Json(response)))
+}
@@ -1 +1,3 @@
+pub mod spreadsheet;
+pub mod text;
 pub mod tracing;
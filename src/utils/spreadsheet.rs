@@ -0,0 +1,116 @@
+use anyhow::{Context, Result};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use utoipa::ToSchema;
+
+/// File format of a generated spreadsheet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SpreadsheetFormat {
+    #[default]
+    Xlsx,
+    Csv,
+}
+
+impl SpreadsheetFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            SpreadsheetFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+            SpreadsheetFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            SpreadsheetFormat::Xlsx => "xlsx",
+            SpreadsheetFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Write a single-sheet table. Every cell is written as text, so CCCDs and phone
+/// numbers keep their leading zeros.
+pub fn write_table(
+    format: SpreadsheetFormat,
+    sheet_name: &str,
+    header: &[String],
+    rows: &[Vec<String>],
+) -> Result<Vec<u8>> {
+    match format {
+        SpreadsheetFormat::Xlsx => write_xlsx(sheet_name, header, rows),
+        SpreadsheetFormat::Csv => write_csv(header, rows),
+    }
+}
+
+fn write_xlsx(sheet_name: &str, header: &[String], rows: &[Vec<String>]) -> Result<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet
+        .set_name(sheet_name)
+        .context("Invalid worksheet name")?;
+
+    let bold = Format::new().set_bold();
+    for (col, value) in header.iter().enumerate() {
+        sheet
+            .write_string_with_format(0, col as u16, value, &bold)
+            .context("Failed to write header")?;
+    }
+    for (row, values) in rows.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            if !value.is_empty() {
+                sheet
+                    .write_string(row as u32 + 1, col as u16, value)
+                    .context("Failed to write cell")?;
+            }
+        }
+    }
+    sheet
+        .set_freeze_panes(1, 0)
+        .context("Failed to freeze header")?;
+
+    workbook
+        .save_to_buffer()
+        .context("Failed to build workbook")
+}
+
+/// UTF-8 with a BOM, so Excel shows Vietnamese names correctly
+fn write_csv(header: &[String], rows: &[Vec<String>]) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(b"\xef\xbb\xbf".to_vec());
+    writer
+        .write_record(header)
+        .context("Failed to write header")?;
+    for row in rows {
+        writer
+            .write_record(row.iter().map(|cell| csv_text(cell)))
+            .context("Failed to write row")?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to build CSV: {}", e))
+}
+
+/// First characters that make spreadsheet apps evaluate a cell as a formula
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Prefix cells spreadsheet apps would evaluate as formulas with `'`, so user
+/// data such as `=HYPERLINK(...)` is shown as text when the CSV is opened
+fn csv_text(cell: &str) -> Cow<'_, str> {
+    if cell.starts_with(FORMULA_PREFIXES) {
+        Cow::Owned(format!("'{}", cell))
+    } else {
+        Cow::Borrowed(cell)
+    }
+}
+
+/// Undo [`csv_text`] for a cell read back from a CSV, so files this service
+/// generated (e.g. an import's error file) can be uploaded again as they are
+pub fn csv_value(cell: &str) -> &str {
+    match cell.strip_prefix('\'') {
+        Some(value) if value.starts_with(FORMULA_PREFIXES) => value,
+        _ => cell,
+    }
+}
@@ -0,0 +1,44 @@
+/// Precomposed Vietnamese letters and the ASCII letter they fold to
+const VIETNAMESE_LETTERS: &[(&str, char)] = &[
+    ("àáạảãâầấậẩẫăằắặẳẵ", 'a'),
+    ("èéẹẻẽêềếệểễ", 'e'),
+    ("ìíịỉĩ", 'i'),
+    ("òóọỏõôồốộổỗơờớợởỡ", 'o'),
+    ("ùúụủũưừứựửữ", 'u'),
+    ("ỳýỵỷỹ", 'y'),
+    ("đ", 'd'),
+];
+
+/// Lowercase `value` and strip Vietnamese diacritics, in precomposed (NFC) or
+/// decomposed (NFD) form, so "Mã Sinh Viên" and "ma sinh vien" compare equal
+pub fn fold_diacritics(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        // Combining diacritical marks left over from decomposed input
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .map(|c| {
+            VIETNAMESE_LETTERS
+                .iter()
+                .find(|(letters, _)| letters.contains(c))
+                .map_or(c, |(_, ascii)| *ascii)
+        })
+        .collect()
+}
+
+/// Arguments for SQL `translate(value, from, to)` folding the Vietnamese letters,
+/// lower and upper case, like [`fold_diacritics`]; wrap the result in `lower()`
+/// for the ASCII letters
+pub fn diacritic_translation() -> (String, String) {
+    let mut from = String::new();
+    let mut to = String::new();
+    for (letters, ascii) in VIETNAMESE_LETTERS {
+        for letter in letters.chars() {
+            for c in std::iter::once(letter).chain(letter.to_uppercase()) {
+                from.push(c);
+                to.push(*ascii);
+            }
+        }
+    }
+    (from, to)
+}
@@ -0,0 +1,8 @@
+//! Route tests against a local Anvil chain, one module per route
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod managers;
+mod students;
+mod users;
@@ -0,0 +1,79 @@
+use crate::common::TestApp;
+use http::StatusCode;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil, solc and TEST_DATABASE_URL"]
+async fn add_and_remove_manager() {
+    let app = TestApp::spawn().await;
+
+    // Creating a manager user adds their wallet as a contract manager
+    let (status, body) = app
+        .create_user("manager@example.com", "manager", None)
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let manager_address = body["wallet_address"].as_str().unwrap().to_string();
+
+    let (status, body) = app.json("GET", "/api/v1/managers", None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total_count"], 1);
+    assert_eq!(body["managers"][0], manager_address.as_str());
+
+    // Adding the same manager twice maps the revert to 409
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/managers",
+            Some(json!({ "manager_address": manager_address })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+
+    let (status, body) = app
+        .json(
+            "DELETE",
+            "/api/v1/managers",
+            Some(json!({ "manager_address": manager_address })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/managers/check",
+            Some(json!({ "address": manager_address })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["is_manager"], false);
+
+    let (status, body) = app
+        .json(
+            "DELETE",
+            "/api/v1/managers",
+            Some(json!({ "manager_address": manager_address })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{}", body);
+
+    // Re-adding through the endpoint restores the manager
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/managers",
+            Some(json!({ "manager_address": manager_address })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/managers/check",
+            Some(json!({ "address": manager_address })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["is_manager"], true);
+}
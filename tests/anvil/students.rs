@@ -0,0 +1,68 @@
+use crate::common::TestApp;
+use http::StatusCode;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil, solc and TEST_DATABASE_URL"]
+async fn deactivate_and_activate_student() {
+    let app = TestApp::spawn().await;
+
+    let (status, body) = app
+        .create_user("student@example.com", "student", Some("SV001"))
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let wallet_address = body["wallet_address"].as_str().unwrap().to_string();
+
+    let check_active = || async {
+        let (status, body) = app
+            .json(
+                "POST",
+                "/api/v1/students/check-active",
+                Some(json!({ "address": wallet_address })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        body["is_active"].as_bool().unwrap()
+    };
+
+    assert!(check_active().await);
+    let student_id = app.student_id(&wallet_address).await;
+
+    let (status, body) = app
+        .json(
+            "PUT",
+            &format!("/api/v1/students/{}/deactivate", student_id),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["is_active"], false);
+    assert!(!check_active().await);
+
+    let (status, body) = app
+        .json("GET", &format!("/api/v1/students/{}", student_id), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["is_active"], false);
+
+    let (status, body) = app
+        .json(
+            "PUT",
+            &format!("/api/v1/students/{}/activate", student_id),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["is_active"], true);
+    assert!(check_active().await);
+
+    // Unknown student IDs revert in simulation and map to 404
+    let (status, body) = app
+        .json(
+            "PUT",
+            &format!("/api/v1/students/{}/deactivate", student_id + 1),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{}", body);
+}
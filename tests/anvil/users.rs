@@ -0,0 +1,193 @@
+use crate::common::{TestApp, users_workbook};
+use http::StatusCode;
+use serde_json::json;
+
+const XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil, solc and TEST_DATABASE_URL"]
+async fn create_user_and_bulk_import_register_students_on_chain() {
+    let app = TestApp::spawn().await;
+
+    // Single student is registered on chain
+    let (status, body) = app
+        .create_user("student1@example.com", "student", Some("SV001"))
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let wallet_address = body["wallet_address"].as_str().unwrap().to_string();
+
+    let student_id = app.student_id(&wallet_address).await;
+    let (status, body) = app
+        .json("GET", &format!("/api/v1/students/{}", student_id), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["student_code"], "SV001");
+
+    // Reusing a student code reverts in simulation and maps to 409
+    let (status, body) = app
+        .create_user("student2@example.com", "student", Some("SV001"))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+
+    // The rejected registration leaves no user behind
+    let (status, body) = app
+        .json("GET", "/api/v1/users?search=student2@example.com", None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 0, "{}", body);
+
+    // Unknown majors are rejected before anything is written
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/users",
+            Some(json!({
+                "first_name": "Pham",
+                "last_name": "Van D",
+                "address": "Hanoi",
+                "email": "student3@example.com",
+                "password": "password123",
+                "cccd": "001200000003",
+                "phone_number": "0912345677",
+                "role": "student",
+                "student_code": "SV003",
+                "major_ids": [uuid::Uuid::new_v4()],
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    // Teachers get their role assigned by the owner
+    let (status, body) = app
+        .create_user("teacher@example.com", "teacher", None)
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+
+    // Bulk import: two valid students and one invalid row
+    let workbook = users_workbook(&[
+        [
+            "Tran",
+            "Thi B",
+            "Hanoi",
+            "bulk1@example.com",
+            "password123",
+            "001200000002",
+            "0912345679",
+            "student",
+            "SV101",
+        ],
+        [
+            "Le",
+            "Van C",
+            "Hanoi",
+            "bulk2@example.com",
+            "password123",
+            "001200000003",
+            "0912345680",
+            "student",
+            "SV102",
+        ],
+        [
+            "Pham",
+            "Van D",
+            "Hanoi",
+            "not-an-email",
+            "password123",
+            "001200000004",
+            "0912345681",
+            "student",
+            "SV103",
+        ],
+    ]);
+    let (status, body) = app
+        .upload("/api/v1/users/bulk", "users.xlsx", XLSX, &workbook)
+        .await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+    assert_eq!(body["total_rows"], 3);
+
+    let job = app
+        .wait_for_import_job(body["job_id"].as_str().unwrap())
+        .await;
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert_eq!(job["processed_rows"], 3);
+    assert_eq!(job["successful"], 2);
+    assert_eq!(job["failed"], 1);
+    assert_eq!(job["results"][2]["row"], 4);
+    assert_eq!(job["results"][2]["status"], "failed");
+    assert_eq!(job["chain_batches"][0]["student_count"], 2);
+    assert!(job["chain_batches"][0]["tx_hash"].is_string(), "{}", job);
+
+    // The failed row comes back with its cells and the reason, password cleared
+    let (status, body) = app
+        .json(
+            "GET",
+            &format!(
+                "/api/v1/import-jobs/{}/errors?format=csv",
+                job["job_id"].as_str().unwrap()
+            ),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let csv = body.as_str().unwrap();
+    assert!(csv.contains("student_code,Error"), "{}", csv);
+    assert!(
+        csv.contains("Pham,Van D,Hanoi,not-an-email,,001200000004"),
+        "{}",
+        csv
+    );
+    assert!(csv.contains("Valid email is required"), "{}", csv);
+
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/students/by-code",
+            Some(json!({ "student_code": "SV102" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, body) = app
+        .json(
+            "GET",
+            &format!("/api/v1/students/{}", body["student_id"]),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["email"], "bulk2@example.com");
+
+    let (status, body) = app.json("GET", "/api/v1/students", None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 3);
+    assert_eq!(body["students"][2]["email"], "bulk2@example.com");
+
+    // Re-uploading the same file with on_existing=skip leaves existing users alone
+    let (status, body) = app
+        .upload_with_fields(
+            "/api/v1/users/bulk",
+            "users.xlsx",
+            XLSX,
+            &workbook,
+            &[("on_existing", "skip")],
+        )
+        .await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+
+    let job = app
+        .wait_for_import_job(body["job_id"].as_str().unwrap())
+        .await;
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert_eq!(job["existing_user_mode"], "skip");
+    assert_eq!(job["skipped"], 2, "{}", job);
+    assert_eq!(job["created"], 0);
+    assert_eq!(job["failed"], 1);
+    assert!(
+        job["chain_batches"].as_array().unwrap().is_empty(),
+        "{}",
+        job
+    );
+
+    let (status, body) = app.json("GET", "/api/v1/students", None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 3);
+}
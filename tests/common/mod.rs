@@ -0,0 +1,499 @@
+//! Shared harness for the integration tests
+//!
+//! `APP_CONFIG` and the database connection are process-wide, so every test of a
+//! binary shares one app, started lazily on its own runtime (pooled connections
+//! stop working once the runtime that opened them shuts down, and each
+//! `#[tokio::test]` has its own). Tests take turns: each one migrates the scratch
+//! database in `TEST_DATABASE_URL` from scratch (all tables are dropped and
+//! re-created) and gets a fresh contract.
+//!
+//! Binaries run on a single chain backend: `tests/anvil` spawns an Anvil node per
+//! test and deploys `tests/contracts/DataStorage.sol` (requires `anvil` and `solc`
+//! on `PATH`), `tests/memory` uses the in-memory backend.
+//!
+//! Run with `cargo test -- --ignored`.
+
+#![allow(dead_code)]
+
+use auth_service::app::create_app;
+use auth_service::blockchain::{InMemoryBackend, clear_cache};
+use auth_service::bootstrap::initialize_admin_user;
+use auth_service::config::{Config, set_app_config};
+use auth_service::static_service::get_database_connection;
+use axum::Router;
+use axum::body::{Body, Bytes, to_bytes};
+use clap::Parser;
+use ethers::contract::ContractFactory;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::solc::Solc;
+use ethers::types::Address;
+use ethers::utils::{Anvil, AnvilInstance, get_contract_address};
+use http::{Request, StatusCode, header};
+use migration::{Migrator, MigratorTrait};
+use once_cell::sync::Lazy;
+use serde_json::{Value, json};
+use std::future::Future;
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::sync::{Mutex, MutexGuard, OnceCell};
+use tower::ServiceExt;
+
+pub const ADMIN_EMAIL: &str = "admin@system.local";
+pub const ADMIN_PASSWORD: &str = "Admin@123456";
+
+/// App shared by the tests of this binary
+static SERVER: OnceCell<Server> = OnceCell::const_new();
+/// Held by the running test, which owns the database and the chain meanwhile
+static TURN: Mutex<()> = Mutex::const_new(());
+/// Port every Anvil node of this binary listens on, so the configured RPC URL
+/// stays valid across tests
+static ANVIL_PORT: Lazy<u16> = Lazy::new(|| {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("Failed to find a free port for Anvil")
+        .port()
+});
+/// Owner of the in-memory contract
+static MEMORY_ADMIN: Lazy<LocalWallet> = Lazy::new(|| LocalWallet::new(&mut rand::thread_rng()));
+
+/// Chain settings of the shared app, identical for every test of a binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChainConfig {
+    backend: &'static str,
+    rpc_url: String,
+    contract_address: Address,
+    admin_private_key: String,
+}
+
+struct Server {
+    runtime: Runtime,
+    router: Router,
+    chain: ChainConfig,
+}
+
+impl Server {
+    async fn start(chain: ChainConfig) -> Self {
+        let database_url = std::env::var("TEST_DATABASE_URL")
+            .expect("TEST_DATABASE_URL must point at a scratch Postgres database");
+
+        let config = Config::parse_from([
+            "auth_service",
+            "--log-level",
+            "info",
+            "--database-url",
+            &database_url,
+            "--blockchain-backend",
+            chain.backend,
+            "--blockchain-rpc-url",
+            &chain.rpc_url,
+            "--data-storage-contract-address",
+            &format!("{:?}", chain.contract_address),
+            "--admin-private-key",
+            &chain.admin_private_key,
+            "--gas-confirmation-timeout-secs",
+            "30",
+        ]);
+        set_app_config(config).expect("APP_CONFIG already initialized");
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to start the app runtime");
+        let router = join(runtime.spawn(async {
+            get_database_connection().await;
+            create_app().await.expect("Failed to build app")
+        }))
+        .await;
+
+        Self {
+            runtime,
+            router,
+            chain,
+        }
+    }
+
+    /// Run `future` on the app's runtime
+    async fn run<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        join(self.runtime.spawn(future)).await
+    }
+}
+
+async fn join<T>(handle: tokio::task::JoinHandle<T>) -> T {
+    handle
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+}
+
+pub struct TestApp {
+    pub admin_token: String,
+    pub contract_address: Address,
+    server: &'static Server,
+    // Killed on drop, before the next test takes its turn; None in memory
+    _anvil: Option<AnvilInstance>,
+    _turn: MutexGuard<'static, ()>,
+}
+
+impl TestApp {
+    /// Spawn Anvil, deploy DataStorage, migrate a fresh database and log in as the
+    /// bootstrap admin (the contract owner)
+    pub async fn spawn() -> Self {
+        let turn = TURN.lock().await;
+
+        wait_for_free_port(*ANVIL_PORT).await;
+        let anvil = Anvil::new().port(*ANVIL_PORT).spawn();
+        let admin_key = anvil.keys()[0].clone();
+        let admin_private_key = format!("0x{}", hex::encode(admin_key.to_bytes()));
+        let contract_address = deploy_data_storage(&anvil, LocalWallet::from(admin_key)).await;
+
+        let chain = ChainConfig {
+            backend: "ethers",
+            rpc_url: anvil.endpoint(),
+            contract_address,
+            admin_private_key,
+        };
+        Self::start(chain, Some(anvil), turn).await
+    }
+
+    /// Like [`TestApp::spawn`], but on the in-memory chain backend
+    /// (`BLOCKCHAIN_BACKEND=memory`), for tests that only need DataStorage semantics
+    pub async fn spawn_in_memory() -> Self {
+        let turn = TURN.lock().await;
+
+        let chain = ChainConfig {
+            backend: "memory",
+            rpc_url: "http://127.0.0.1:8545".to_string(),
+            contract_address: Address::from_low_u64_be(0xda7a),
+            admin_private_key: format!("0x{}", hex::encode(MEMORY_ADMIN.signer().to_bytes())),
+        };
+        Self::start(chain, None, turn).await
+    }
+
+    async fn start(
+        chain: ChainConfig,
+        anvil: Option<AnvilInstance>,
+        turn: MutexGuard<'static, ()>,
+    ) -> Self {
+        let server = SERVER.get_or_init(|| Server::start(chain.clone())).await;
+        assert_eq!(
+            server.chain, chain,
+            "every test of a binary must run on the same chain backend"
+        );
+
+        // Fresh database and contract state; the ethers contract is new already
+        server
+            .run(async {
+                let db = get_database_connection().await;
+                Migrator::fresh(db)
+                    .await
+                    .expect("Failed to migrate test database");
+                initialize_admin_user(db)
+                    .await
+                    .expect("Failed to create admin user");
+                clear_cache();
+                InMemoryBackend::reset_shared();
+            })
+            .await;
+
+        let mut app = Self {
+            admin_token: String::new(),
+            contract_address: chain.contract_address,
+            server,
+            _anvil: anvil,
+            _turn: turn,
+        };
+
+        let (status, body) = app
+            .json(
+                "POST",
+                "/api/v1/auth/login",
+                Some(json!({ "email": ADMIN_EMAIL, "password": ADMIN_PASSWORD })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "admin login failed: {}", body);
+        app.admin_token = body["access_token"]
+            .as_str()
+            .expect("login response has no access_token")
+            .to_string();
+
+        app
+    }
+
+    /// Send a request as the admin and decode the response body
+    pub async fn json(&self, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let mut request = Request::builder().method(method).uri(uri).header(
+            header::AUTHORIZATION,
+            format!("Bearer {}", self.admin_token),
+        );
+
+        let body = match body {
+            Some(body) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(body.to_string())
+            }
+            None => Body::empty(),
+        };
+
+        self.send(request.body(body).unwrap()).await
+    }
+
+    /// Upload a file as the `file` field of a multipart form
+    pub async fn upload(
+        &self,
+        uri: &str,
+        file_name: &str,
+        content_type: &str,
+        data: &[u8],
+    ) -> (StatusCode, Value) {
+        self.upload_with_fields(uri, file_name, content_type, data, &[])
+            .await
+    }
+
+    /// Upload a file as the `file` field of a multipart form, with extra text fields
+    pub async fn upload_with_fields(
+        &self,
+        uri: &str,
+        file_name: &str,
+        content_type: &str,
+        data: &[u8],
+        fields: &[(&str, &str)],
+    ) -> (StatusCode, Value) {
+        const BOUNDARY: &str = "integration-test-boundary";
+
+        let mut body = Vec::new();
+        for (name, value) in fields {
+            body.extend_from_slice(
+                format!(
+                    "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend_from_slice(
+            format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: {content_type}\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(data);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(
+                header::AUTHORIZATION,
+                format!("Bearer {}", self.admin_token),
+            )
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+
+        self.send(request).await
+    }
+
+    pub async fn send(&self, request: Request<Body>) -> (StatusCode, Value) {
+        let router = self.server.router.clone();
+        let (status, bytes): (StatusCode, Bytes) = self
+            .server
+            .run(async move {
+                let response = router.oneshot(request).await.expect("Request failed");
+                let status = response.status();
+                let bytes = to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .expect("Failed to read response body");
+                (status, bytes)
+            })
+            .await;
+
+        let body = serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+
+        (status, body)
+    }
+
+    /// Create a user through `POST /api/v1/users`, returning the response body
+    pub async fn create_user(
+        &self,
+        email: &str,
+        role: &str,
+        student_code: Option<&str>,
+    ) -> (StatusCode, Value) {
+        self.json(
+            "POST",
+            "/api/v1/users",
+            Some(json!({
+                "first_name": "Nguyen",
+                "last_name": "Van A",
+                "address": "123 Main St, Hanoi",
+                "email": email,
+                "password": "password123",
+                "cccd": "001200000001",
+                "phone_number": "0912345678",
+                "role": role,
+                "student_code": student_code,
+            })),
+        )
+        .await
+    }
+
+    /// Create a student, returning the response body
+    pub async fn create_student(&self, email: &str, student_code: &str) -> Value {
+        let (status, body) = self.create_user(email, "student", Some(student_code)).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        body
+    }
+
+    /// Import a CSV through `POST /api/v1/users/bulk` with extra form fields and
+    /// wait for the job, returning its final state
+    pub async fn import_csv(&self, csv: &str, fields: &[(&str, &str)]) -> Value {
+        let (status, body) = self
+            .upload_with_fields(
+                "/api/v1/users/bulk",
+                "users.csv",
+                "text/csv",
+                csv.as_bytes(),
+                fields,
+            )
+            .await;
+        assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+
+        self.wait_for_import_job(body["job_id"].as_str().unwrap())
+            .await
+    }
+
+    /// Validate a CSV through `POST /api/v1/users/bulk?dry_run=true`, returning the report
+    pub async fn dry_run_csv(&self, csv: &str, fields: &[(&str, &str)]) -> Value {
+        let (status, body) = self
+            .upload_with_fields(
+                "/api/v1/users/bulk?dry_run=true",
+                "users.csv",
+                "text/csv",
+                csv.as_bytes(),
+                fields,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        body
+    }
+
+    /// On-chain student ID of a wallet, through `POST /api/v1/students/by-address`
+    pub async fn student_id(&self, wallet_address: &str) -> u64 {
+        let (status, body) = self
+            .json(
+                "POST",
+                "/api/v1/students/by-address",
+                Some(json!({ "address": wallet_address })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        body["student_id"]
+            .as_u64()
+            .expect("by-address response has no student_id")
+    }
+
+    /// Poll `GET /api/v1/import-jobs/{job_id}` until the import finishes
+    pub async fn wait_for_import_job(&self, job_id: &str) -> Value {
+        for _ in 0..300 {
+            let (status, body) = self
+                .json("GET", &format!("/api/v1/import-jobs/{}", job_id), None)
+                .await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+            if body["status"] != "pending" && body["status"] != "running" {
+                return body;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        panic!("Import job {} did not finish", job_id);
+    }
+}
+
+async fn deploy_data_storage(anvil: &AnvilInstance, wallet: LocalWallet) -> Address {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/contracts/DataStorage.sol");
+    let output = Solc::default()
+        .compile_source(&source)
+        .expect("Failed to run solc");
+    assert!(
+        !output.has_error(),
+        "DataStorage.sol failed to compile: {:?}",
+        output.errors
+    );
+
+    let (abi, bytecode, _) = output
+        .find("DataStorage")
+        .expect("DataStorage missing from solc output")
+        .into_parts_or_default();
+
+    let wallet_address = wallet.address();
+    let provider = Provider::<Http>::try_from(anvil.endpoint()).expect("Invalid Anvil endpoint");
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.with_chain_id(anvil.chain_id()),
+    ));
+
+    let contract = ContractFactory::new(abi, bytecode, client)
+        .deploy(())
+        .expect("Failed to build deployment")
+        .send()
+        .await
+        .expect("Failed to deploy DataStorage");
+
+    // Every node starts from the same accounts, so the contract address, and with
+    // it the shared app's config, is the same for each test
+    assert_eq!(contract.address(), get_contract_address(wallet_address, 0));
+    contract.address()
+}
+
+/// Wait for the previous test's Anvil node to release `port`
+async fn wait_for_free_port(port: u16) {
+    for _ in 0..50 {
+        if TcpListener::bind(("127.0.0.1", port)).is_ok() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Port {} is still in use", port);
+}
+
+/// Build a bulk import workbook in the column order expected by `POST /api/v1/users/bulk`
+pub fn users_workbook(rows: &[[&str; 9]]) -> Vec<u8> {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let sheet = workbook.add_worksheet();
+
+    let header = [
+        "first_name",
+        "last_name",
+        "address",
+        "email",
+        "password",
+        "cccd",
+        "phone_number",
+        "role",
+        "student_code",
+    ];
+    for (col, value) in header.iter().enumerate() {
+        sheet.write_string(0, col as u16, *value).unwrap();
+    }
+    for (row, values) in rows.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            if !value.is_empty() {
+                sheet
+                    .write_string(row as u32 + 1, col as u16, *value)
+                    .unwrap();
+            }
+        }
+    }
+
+    workbook.save_to_buffer().expect("Failed to build workbook")
+}
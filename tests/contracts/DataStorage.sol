@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// Minimal DataStorage implementation matching the ABI in src/blockchain/contract.rs,
+/// deployed to a local Anvil node by the integration tests
+contract DataStorage {
+    struct Student {
+        uint256 id;
+        address walletAddress;
+        string studentCode;
+        string fullName;
+        string email;
+        bool isActive;
+        uint256 registeredAt;
+    }
+
+    address public owner;
+    address public factoryContract;
+
+    address[] private managers;
+    mapping(address => bool) public isManager;
+    mapping(address => bool) public authorizedContracts;
+    mapping(address => uint8) public userRoles;
+    mapping(address => bool) public isRegistered;
+
+    uint256 private studentCount;
+    mapping(uint256 => Student) private students;
+    mapping(address => uint256) private studentIdByAddress;
+    mapping(string => uint256) private studentIdByCode;
+
+    event OwnerChanged(address indexed oldOwner, address indexed newOwner);
+    event ManagerAdded(address indexed manager);
+    event ManagerRemoved(address indexed manager);
+    event StudentRegistered(uint256 indexed studentId, address indexed studentAddress, string studentCode);
+    event StudentDeactivated(uint256 indexed studentId);
+    event RoleAssigned(address indexed user, uint8 role);
+    event ContractAuthorized(address indexed contractAddress);
+    event ContractUnauthorized(address indexed contractAddress);
+
+    modifier onlyOwner() {
+        require(msg.sender == owner, "Only owner can call this function");
+        _;
+    }
+
+    modifier onlyManager() {
+        require(msg.sender == owner || isManager[msg.sender], "Only manager can call this function");
+        _;
+    }
+
+    constructor() {
+        owner = msg.sender;
+        factoryContract = msg.sender;
+    }
+
+    function changeOwner(address _newOwner) external onlyOwner {
+        require(_newOwner != address(0), "Invalid owner address");
+        emit OwnerChanged(owner, _newOwner);
+        owner = _newOwner;
+    }
+
+    function authorizeContract(address _contract) external onlyOwner {
+        authorizedContracts[_contract] = true;
+        emit ContractAuthorized(_contract);
+    }
+
+    function unauthorizeContract(address _contract) external onlyOwner {
+        authorizedContracts[_contract] = false;
+        emit ContractUnauthorized(_contract);
+    }
+
+    function addManager(address _manager) external onlyOwner {
+        require(!isManager[_manager], "Manager already exists");
+        isManager[_manager] = true;
+        managers.push(_manager);
+        emit ManagerAdded(_manager);
+    }
+
+    function removeManager(address _manager) external onlyOwner {
+        require(isManager[_manager], "Manager does not exist");
+        isManager[_manager] = false;
+        for (uint256 i = 0; i < managers.length; i++) {
+            if (managers[i] == _manager) {
+                managers[i] = managers[managers.length - 1];
+                managers.pop();
+                break;
+            }
+        }
+        emit ManagerRemoved(_manager);
+    }
+
+    function getAllManagers() external view returns (address[] memory) {
+        return managers;
+    }
+
+    function getManagerCount() external view returns (uint256) {
+        return managers.length;
+    }
+
+    function registerStudent(
+        address _studentAddress,
+        string memory _studentCode,
+        string memory _fullName,
+        string memory _email
+    ) public onlyManager returns (uint256) {
+        require(!isRegistered[_studentAddress], "Student already registered");
+        require(studentIdByCode[_studentCode] == 0, "Student code already exists");
+
+        studentCount++;
+        students[studentCount] = Student(
+            studentCount,
+            _studentAddress,
+            _studentCode,
+            _fullName,
+            _email,
+            true,
+            block.timestamp
+        );
+        studentIdByAddress[_studentAddress] = studentCount;
+        studentIdByCode[_studentCode] = studentCount;
+        isRegistered[_studentAddress] = true;
+        userRoles[_studentAddress] = 1;
+
+        emit StudentRegistered(studentCount, _studentAddress, _studentCode);
+        return studentCount;
+    }
+
+    function registerStudentsBatch(
+        address[] memory _addresses,
+        string[] memory _studentCodes,
+        string[] memory _fullNames,
+        string[] memory _emails
+    ) external onlyManager {
+        require(
+            _addresses.length == _studentCodes.length &&
+                _addresses.length == _fullNames.length &&
+                _addresses.length == _emails.length,
+            "Array lengths mismatch"
+        );
+        for (uint256 i = 0; i < _addresses.length; i++) {
+            registerStudent(_addresses[i], _studentCodes[i], _fullNames[i], _emails[i]);
+        }
+    }
+
+    function deactivateStudent(uint256 _studentId) external onlyManager {
+        require(_studentId > 0 && _studentId <= studentCount, "Student not found");
+        students[_studentId].isActive = false;
+        emit StudentDeactivated(_studentId);
+    }
+
+    function activateStudent(uint256 _studentId) external onlyManager {
+        require(_studentId > 0 && _studentId <= studentCount, "Student not found");
+        students[_studentId].isActive = true;
+    }
+
+    function getStudent(uint256 _studentId)
+        external
+        view
+        returns (
+            uint256 id,
+            address walletAddress,
+            string memory studentCode,
+            string memory fullName,
+            string memory email,
+            bool isActive,
+            uint256 registeredAt
+        )
+    {
+        require(_studentId > 0 && _studentId <= studentCount, "Student not found");
+        Student storage s = students[_studentId];
+        return (s.id, s.walletAddress, s.studentCode, s.fullName, s.email, s.isActive, s.registeredAt);
+    }
+
+    function getStudentIdByAddress(address _address) external view returns (uint256) {
+        return studentIdByAddress[_address];
+    }
+
+    function getStudentIdByCode(string memory _studentCode) external view returns (uint256) {
+        return studentIdByCode[_studentCode];
+    }
+
+    function isActiveStudent(address _address) external view returns (bool) {
+        uint256 studentId = studentIdByAddress[_address];
+        return studentId != 0 && students[studentId].isActive;
+    }
+
+    function assignRole(address _user, uint8 _role) external onlyOwner {
+        userRoles[_user] = _role;
+        emit RoleAssigned(_user, _role);
+    }
+
+    function getUserRole(address _user) external view returns (uint8) {
+        return userRoles[_user];
+    }
+
+    function hasRole(address _user, uint8 _role) external view returns (bool) {
+        return userRoles[_user] == _role;
+    }
+
+    function isTeacherOrAdmin(address _user) external view returns (bool) {
+        return userRoles[_user] == 2 || userRoles[_user] == 3;
+    }
+
+    function getTotalStudents() external view returns (uint256) {
+        return studentCount;
+    }
+
+    function getContractInfo()
+        external
+        view
+        returns (address currentOwner, uint256 studentCount_, uint256 managerCount)
+    {
+        return (owner, studentCount, managers.length);
+    }
+}
@@ -0,0 +1,6 @@
+//! Route tests on the in-memory chain backend, one module per route
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod users;
@@ -0,0 +1,440 @@
+use crate::common::TestApp;
+use http::StatusCode;
+use serde_json::{Value, json};
+
+const USERS_CSV_HEADER: &str =
+    "first_name,last_name,address,email,password,cccd,phone_number,role,student_code";
+
+/// On-chain status of a wallet, through `POST /api/v1/students/check-active`
+async fn is_active(app: &TestApp, wallet_address: &str) -> bool {
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/students/check-active",
+            Some(json!({ "address": wallet_address })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    body["is_active"].as_bool().unwrap()
+}
+
+/// Student record of a student code, through the by-code and by-ID endpoints
+async fn student_by_code(app: &TestApp, student_code: &str) -> Value {
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/students/by-code",
+            Some(json!({ "student_code": student_code })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = app
+        .json(
+            "GET",
+            &format!("/api/v1/students/{}", body["student_id"]),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    body
+}
+
+/// Send a batch operation, returning the report
+async fn batch(app: &TestApp, request: Value) -> Value {
+    let (status, body) = app.json("POST", "/api/v1/users/batch", Some(request)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    body
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn bulk_import_accepts_csv_with_bom_and_semicolons() {
+    let app = TestApp::spawn_in_memory().await;
+
+    let csv = format!(
+        "\u{feff}{}\n\
+        Nguyễn;Thị Hồng;Hà Nội;csv1@example.com;password123;001200000010;0912345690;student;SV201\n\
+        ;;;;;;;;\n\
+        Trần;Văn Đức;Đà Nẵng;csv2@example.com;password123;001200000011;0912345691;teacher;\n",
+        USERS_CSV_HEADER.replace(',', ";")
+    );
+    let job = app.import_csv(&csv, &[]).await;
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert_eq!(job["total_rows"], 2, "{}", job);
+    assert_eq!(job["successful"], 2);
+
+    let student = student_by_code(&app, "SV201").await;
+    assert_eq!(student["full_name"], "Nguyễn Thị Hồng");
+
+    let (status, body) = app
+        .upload(
+            "/api/v1/users/bulk",
+            "users.csv",
+            "text/csv",
+            &[0xff, 0xfe, 0x00],
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn bulk_import_assigns_majors() {
+    let app = TestApp::spawn_in_memory().await;
+
+    let (status, body) = app
+        .json(
+            "POST",
+            "/api/v1/majors",
+            Some(json!({
+                "name": "Công nghệ thông tin",
+                "founding_date": "2020-09-01T00:00:00"
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let major_id = body["major_id"].as_str().unwrap().to_string();
+
+    let csv = format!(
+        "{},Ngành\n\
+        Nguyen,Van A,Hanoi,major1@example.com,password123,001200000020,0912345700,student,SV301,cong nghe thong tin\n\
+        Tran,Thi B,Hanoi,major2@example.com,password123,001200000021,0912345701,student,SV302,\"Công nghệ thông tin; Khoa học dữ liệu\"\n",
+        USERS_CSV_HEADER
+    );
+
+    // Unknown majors are rejected unless they may be created
+    let report = app.dry_run_csv(&csv, &[]).await;
+    assert_eq!(report["valid"], 1, "{}", report);
+    assert_eq!(
+        report["rows"][1]["errors"][0],
+        "Unknown major 'Khoa học dữ liệu'"
+    );
+
+    let create_missing = [("create_missing_majors", "true")];
+    let report = app.dry_run_csv(&csv, &create_missing).await;
+    assert_eq!(report["valid"], 2, "{}", report);
+    assert_eq!(report["new_majors"], json!(["Khoa học dữ liệu"]));
+
+    let job = app.import_csv(&csv, &create_missing).await;
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert_eq!(job["created"], 2, "{}", job);
+
+    let user_id = job["results"][0]["user_id"].as_str().unwrap();
+    let (status, body) = app
+        .json("GET", &format!("/api/v1/users/{}", user_id), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["major_ids"], json!([major_id]));
+
+    let user_id = job["results"][1]["user_id"].as_str().unwrap();
+    let (status, body) = app
+        .json("GET", &format!("/api/v1/users/{}", user_id), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["major_ids"].as_array().unwrap().len(), 2, "{}", body);
+
+    let (status, body) = app.json("GET", "/api/v1/majors", None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn import_template_is_accepted_by_the_importer() {
+    let app = TestApp::spawn_in_memory().await;
+
+    let (status, _) = app.json("GET", "/api/v1/users/bulk/template", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app
+        .json("GET", "/api/v1/users/bulk/template?format=csv", None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let csv = body.as_str().unwrap();
+    assert!(
+        csv.contains("role,student_code,majors,department"),
+        "{}",
+        csv
+    );
+
+    // The example rows pass validation as they are
+    let report = app.dry_run_csv(csv, &[]).await;
+    assert_eq!(report["total_records"], 2);
+    assert_eq!(report["valid"], 2, "{}", report);
+    assert_eq!(report["unmapped_columns"], json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn bulk_import_error_file_can_be_uploaded_again() {
+    let app = TestApp::spawn_in_memory().await;
+
+    let csv = format!(
+        "{}\n\
+        Le,Van E,Hanoi,roundtrip-at-example.com,password123,001200000040,+84912345678,teacher,\n",
+        USERS_CSV_HEADER
+    );
+    let job = app.import_csv(&csv, &[]).await;
+    assert_eq!(job["failed"], 1, "{}", job);
+
+    // Cells starting with a formula character are written with a `'` prefix
+    let (status, body) = app
+        .json(
+            "GET",
+            &format!(
+                "/api/v1/import-jobs/{}/errors?format=csv",
+                job["job_id"].as_str().unwrap()
+            ),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let csv = body.as_str().unwrap();
+    assert!(csv.contains(",'+84912345678,"), "{}", csv);
+
+    // and read back without it once the row is corrected
+    let csv = csv.replace(
+        "roundtrip-at-example.com,,",
+        "roundtrip@example.com,password123,",
+    );
+    let job = app.import_csv(&csv, &[]).await;
+    assert_eq!(job["created"], 1, "{}", job);
+
+    let user_id = job["results"][0]["user_id"].as_str().unwrap();
+    let (status, body) = app
+        .json("GET", &format!("/api/v1/users/{}", user_id), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["phone_number"], "+84912345678");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn bulk_import_reports_rows_matching_the_same_user() {
+    let app = TestApp::spawn_in_memory().await;
+    let student = app.create_student("same1@example.com", "SV401").await;
+
+    // The first row matches the student by email, the second by student code
+    let csv = format!(
+        "{}\n\
+        Nguyen,Van A,Hanoi,same1@example.com,password123,001200000001,0912345678,student,SV402\n\
+        Nguyen,Van A,Hanoi,same2@example.com,password123,001200000030,0912345678,student,SV401\n",
+        USERS_CSV_HEADER
+    );
+    let report = app.dry_run_csv(&csv, &[("on_existing", "update")]).await;
+    assert_eq!(report["valid"], 1, "{}", report);
+    assert_eq!(report["rows"][0]["existing_user_id"], student["user_id"]);
+    assert_eq!(
+        report["rows"][1]["errors"],
+        json!(["Same existing user as row 2"])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn export_users_with_chain_status() {
+    let app = TestApp::spawn_in_memory().await;
+
+    let body = app.create_student("export1@example.com", "SV401").await;
+    let wallet_address = body["wallet_address"].as_str().unwrap().to_string();
+
+    let (status, body) = app
+        .create_user("export2@example.com", "teacher", None)
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+
+    let (status, body) = app
+        .json("GET", "/api/v1/users/export?format=csv&role=student", None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let csv = body.as_str().unwrap();
+    assert!(csv.contains("cccd,address,role,student_code"), "{}", csv);
+    assert!(csv.contains("export1@example.com"), "{}", csv);
+    assert!(!csv.contains("export2@example.com"), "{}", csv);
+    assert!(
+        csv.contains(&format!("SV401,,,{},active", wallet_address)),
+        "{}",
+        csv
+    );
+
+    let (status, _) = app.json("GET", "/api/v1/users/export", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The list carries each user's own wallet
+    let (status, body) = app.json("GET", "/api/v1/users", None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let users = body["users"].as_array().unwrap();
+    let student = users
+        .iter()
+        .find(|user| user["email"] == "export1@example.com")
+        .unwrap();
+    assert_eq!(student["wallet_address"], wallet_address.as_str());
+    assert!(users.iter().all(|user| user["wallet_address"].is_string()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn batch_operations_report_per_user() {
+    let app = TestApp::spawn_in_memory().await;
+
+    let mut student_ids = Vec::new();
+    let mut wallet_addresses = Vec::new();
+    for (email, code) in [
+        ("batch1@example.com", "SV501"),
+        ("batch2@example.com", "SV502"),
+    ] {
+        let body = app.create_student(email, code).await;
+        student_ids.push(body["user_id"].as_str().unwrap().to_string());
+        wallet_addresses.push(body["wallet_address"].as_str().unwrap().to_string());
+    }
+    let (status, body) = app.create_user("batch3@example.com", "teacher", None).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let teacher_id = body["user_id"].as_str().unwrap().to_string();
+    let missing_id = uuid::Uuid::new_v4().to_string();
+
+    // Students are deactivated on-chain; the teacher and the unknown ID fail alone
+    let report = batch(
+        &app,
+        json!({
+            "operation": "deactivate",
+            "user_ids": [student_ids[0], student_ids[1], teacher_id, missing_id],
+        }),
+    )
+    .await;
+    assert_eq!(report["succeeded"], 2, "{}", report);
+    assert_eq!(report["failed"], 2, "{}", report);
+    assert_eq!(report["results"][3]["error"], "User not found");
+
+    for address in &wallet_addresses {
+        assert!(!is_active(&app, address).await);
+    }
+
+    let report = batch(
+        &app,
+        json!({ "operation": "change_role", "role": "manager", "user_ids": [teacher_id] }),
+    )
+    .await;
+    assert_eq!(report["succeeded"], 1, "{}", report);
+
+    let (status, body) = app
+        .json("GET", &format!("/api/v1/users/{}", teacher_id), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["role"], "manager");
+
+    let report = batch(
+        &app,
+        json!({ "operation": "delete", "user_ids": student_ids }),
+    )
+    .await;
+    assert_eq!(report["succeeded"], 2, "{}", report);
+
+    let (status, _) = app
+        .json("GET", &format!("/api/v1/users/{}", student_ids[0]), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn list_users_filters_sorts_and_searches() {
+    let app = TestApp::spawn_in_memory().await;
+
+    let mut user_ids = Vec::new();
+    for (first_name, last_name, email, code) in [
+        ("Nguyễn", "Thị Hoa", "hoa@example.com", "SV601"),
+        ("Trần", "Văn Bình", "binh@example.com", "SV602"),
+    ] {
+        let (status, body) = app
+            .json(
+                "POST",
+                "/api/v1/users",
+                Some(json!({
+                    "first_name": first_name,
+                    "last_name": last_name,
+                    "address": "Hanoi",
+                    "email": email,
+                    "password": "password123",
+                    "cccd": "001200000060",
+                    "phone_number": "0912345660",
+                    "role": "student",
+                    "student_code": code,
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        user_ids.push(body["user_id"].as_str().unwrap().to_string());
+    }
+
+    // Words match across columns without case or diacritics
+    let (status, body) = app
+        .json("GET", "/api/v1/users?search=NGUYEN%20hoa", None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["users"][0]["email"], "hoa@example.com");
+
+    // Student codes are matched against the stored on-chain records
+    let (status, body) = app.json("GET", "/api/v1/users?search=SV602", None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["users"][0]["email"], "binh@example.com");
+
+    let (status, body) = app
+        .json(
+            "GET",
+            "/api/v1/users?role=student&sort_by=email&sort_order=asc",
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["users"][0]["email"], "binh@example.com");
+    assert_eq!(body["users"][1]["email"], "hoa@example.com");
+
+    batch(
+        &app,
+        json!({ "operation": "deactivate", "user_ids": [user_ids[0]] }),
+    )
+    .await;
+
+    let (status, body) = app
+        .json("GET", "/api/v1/users?on_chain_active=true", None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["users"][0]["user_id"], user_ids[1].as_str());
+
+    let (status, body) = app
+        .json("GET", "/api/v1/users?on_chain_active=false", None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["users"][0]["user_id"], user_ids[0].as_str());
+
+    // Batch writes store the new status as soon as they are sent
+    batch(
+        &app,
+        json!({ "operation": "activate", "user_ids": [user_ids[0]] }),
+    )
+    .await;
+
+    let (status, body) = app
+        .json("GET", "/api/v1/users?on_chain_active=true", None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 2, "{}", body);
+
+    let (status, body) = app
+        .json(
+            "GET",
+            "/api/v1/users?role=student&is_first_login=true&created_from=2000-01-01&page_size=100",
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 2, "{}", body);
+
+    let (status, _) = app.json("GET", "/api/v1/users?page_size=101", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,288 @@
+//! `InMemoryBackend` against the DataStorage semantics it mimics; needs no node
+//! or database.
+
+use auth_service::blockchain::memory::{DataStorageState, ROLE_NONE, ROLE_STUDENT, ROLE_TEACHER};
+use auth_service::blockchain::{ChainBackend, ContractRevertError, InMemoryBackend, RevertKind};
+use ethers::signers::{LocalWallet, Signer};
+use http::StatusCode;
+use std::sync::{Arc, Mutex};
+
+struct Account {
+    private_key: String,
+    address: String,
+}
+
+fn account() -> Account {
+    let wallet = LocalWallet::new(&mut rand::thread_rng());
+    Account {
+        private_key: format!("0x{}", hex::encode(wallet.signer().to_bytes())),
+        address: format!("{:?}", wallet.address()),
+    }
+}
+
+/// A fresh contract and a backend signing as its owner
+fn deploy() -> (Arc<Mutex<DataStorageState>>, InMemoryBackend) {
+    let owner = account();
+    let state = Arc::new(Mutex::new(DataStorageState::new(&owner.address)));
+    let backend = InMemoryBackend::new(state.clone(), &owner.private_key).unwrap();
+    (state, backend)
+}
+
+fn revert_of(error: anyhow::Error) -> ContractRevertError {
+    error
+        .downcast::<ContractRevertError>()
+        .expect("expected a contract revert")
+}
+
+#[tokio::test]
+async fn registers_and_looks_up_students() {
+    let (_, owner) = deploy();
+    let student = account();
+
+    let student_id = owner
+        .register_student(&student.address, "SV001", "Nguyen Van A", "a@example.com")
+        .await
+        .unwrap();
+    assert_eq!(student_id, 1);
+
+    // Lookups accept any address casing
+    assert_eq!(
+        owner
+            .get_student_id_by_address(&student.address.to_uppercase().replace("0X", "0x"))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(owner.get_student_id_by_code("SV001").await.unwrap(), 1);
+    assert_eq!(owner.get_student_id_by_code("SV002").await.unwrap(), 0);
+    assert!(owner.is_active_student(&student.address).await.unwrap());
+    assert_eq!(
+        owner.get_user_role(&student.address).await.unwrap(),
+        ROLE_STUDENT
+    );
+
+    let record = owner.get_student(1).await.unwrap();
+    assert_eq!(record.wallet_address, student.address);
+    assert_eq!(record.student_code, "SV001");
+    assert_eq!(
+        revert_of(owner.get_student(2).await.unwrap_err()).kind,
+        RevertKind::StudentNotFound
+    );
+    assert_eq!(
+        revert_of(owner.get_student(0).await.unwrap_err()).kind,
+        RevertKind::StudentNotFound
+    );
+
+    let duplicate = owner
+        .register_student(&student.address, "SV002", "Nguyen Van A", "a@example.com")
+        .await
+        .unwrap_err();
+    let duplicate = revert_of(duplicate);
+    assert_eq!(duplicate.kind, RevertKind::AlreadyRegistered);
+    assert_eq!(duplicate.status_code(), StatusCode::CONFLICT);
+
+    let taken_code = owner
+        .register_student(&account().address, "SV001", "Tran Thi B", "b@example.com")
+        .await
+        .unwrap_err();
+    assert_eq!(revert_of(taken_code).kind, RevertKind::AlreadyRegistered);
+    assert_eq!(owner.get_total_students().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn batch_registration_is_all_or_nothing() {
+    let (_, owner) = deploy();
+    let existing = account();
+    owner
+        .register_student(&existing.address, "SV001", "Nguyen Van A", "a@example.com")
+        .await
+        .unwrap();
+
+    let fresh = account();
+    let error = owner
+        .register_students_batch(
+            vec![fresh.address.clone(), existing.address.clone()],
+            vec!["SV002".to_string(), "SV003".to_string()],
+            vec!["Tran Thi B".to_string(), "Le Van C".to_string()],
+            vec!["b@example.com".to_string(), "c@example.com".to_string()],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(revert_of(error).kind, RevertKind::AlreadyRegistered);
+    assert_eq!(
+        owner
+            .get_student_id_by_address(&fresh.address)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(owner.get_total_students().await.unwrap(), 1);
+
+    let mismatch = owner
+        .register_students_batch(
+            vec![fresh.address.clone()],
+            vec!["SV002".to_string(), "SV003".to_string()],
+            vec!["Tran Thi B".to_string()],
+            vec!["b@example.com".to_string()],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(revert_of(mismatch).kind, RevertKind::Other);
+
+    let other = account();
+    let tx_hash = owner
+        .register_students_batch(
+            vec![fresh.address.clone(), other.address.clone()],
+            vec!["SV002".to_string(), "SV003".to_string()],
+            vec!["Tran Thi B".to_string(), "Le Van C".to_string()],
+            vec!["b@example.com".to_string(), "c@example.com".to_string()],
+        )
+        .await
+        .unwrap();
+    assert!(tx_hash.starts_with("0x"));
+    assert_eq!(owner.get_student_id_by_code("SV003").await.unwrap(), 3);
+
+    let page = owner.get_students(2, 5, 3).await.unwrap();
+    let codes: Vec<_> = page.iter().map(|s| s.student_code.as_str()).collect();
+    assert_eq!(codes, ["SV002", "SV003"]);
+}
+
+#[tokio::test]
+async fn enforces_owner_and_manager_modifiers() {
+    let (state, owner) = deploy();
+    let manager = account();
+    let manager_backend = InMemoryBackend::new(state.clone(), &manager.private_key).unwrap();
+    let outsider = InMemoryBackend::new(state, &account().private_key).unwrap();
+
+    let error = outsider
+        .register_student(&account().address, "SV001", "Nguyen Van A", "a@example.com")
+        .await
+        .unwrap_err();
+    let error = revert_of(error);
+    assert_eq!(error.kind, RevertKind::NotManager);
+    assert_eq!(error.status_code(), StatusCode::FORBIDDEN);
+
+    owner.add_manager(&manager.address).await.unwrap();
+    assert!(owner.is_manager(&manager.address).await.unwrap());
+    assert_eq!(
+        revert_of(owner.add_manager(&manager.address).await.unwrap_err()).kind,
+        RevertKind::AlreadyRegistered
+    );
+    assert_eq!(
+        owner.get_all_managers().await.unwrap(),
+        [manager.address.clone()]
+    );
+
+    // Managers register students but cannot manage roles or other managers
+    let student = account();
+    manager_backend
+        .register_student(&student.address, "SV001", "Nguyen Van A", "a@example.com")
+        .await
+        .unwrap();
+    let error = manager_backend
+        .assign_role(&student.address, ROLE_TEACHER)
+        .await
+        .unwrap_err();
+    assert_eq!(revert_of(error).kind, RevertKind::NotOwner);
+    let error = manager_backend
+        .add_manager(&account().address)
+        .await
+        .unwrap_err();
+    assert_eq!(revert_of(error).kind, RevertKind::NotOwner);
+
+    owner
+        .assign_role(&student.address, ROLE_TEACHER)
+        .await
+        .unwrap();
+    assert!(owner.is_teacher_or_admin(&student.address).await.unwrap());
+    owner
+        .assign_role(&student.address, ROLE_NONE)
+        .await
+        .unwrap();
+    assert!(
+        !owner
+            .has_role(&student.address, ROLE_TEACHER)
+            .await
+            .unwrap()
+    );
+
+    owner.remove_manager(&manager.address).await.unwrap();
+    assert_eq!(owner.get_manager_count().await.unwrap(), 0);
+    assert_eq!(
+        revert_of(owner.remove_manager(&manager.address).await.unwrap_err()).kind,
+        RevertKind::StudentNotFound
+    );
+    assert_eq!(
+        revert_of(manager_backend.deactivate_student(1).await.unwrap_err()).kind,
+        RevertKind::NotManager
+    );
+}
+
+#[tokio::test]
+async fn deactivates_and_activates_students() {
+    let (_, owner) = deploy();
+    let student = account();
+    owner
+        .register_student(&student.address, "SV001", "Nguyen Van A", "a@example.com")
+        .await
+        .unwrap();
+
+    owner.deactivate_student(1).await.unwrap();
+    assert!(!owner.is_active_student(&student.address).await.unwrap());
+    assert!(!owner.get_student(1).await.unwrap().is_active);
+
+    owner.activate_student(1).await.unwrap();
+    assert!(owner.is_active_student(&student.address).await.unwrap());
+
+    assert_eq!(
+        revert_of(owner.deactivate_student(2).await.unwrap_err()).kind,
+        RevertKind::StudentNotFound
+    );
+    assert!(!owner.is_active_student(&account().address).await.unwrap());
+
+    let (_, students, managers) = owner.get_contract_info().await.unwrap();
+    assert_eq!((students, managers), (1, 0));
+}
+
+#[tokio::test]
+async fn finds_anchored_merkle_roots() {
+    let (_, owner) = deploy();
+    let root = [7u8; 32];
+
+    let tx_hash = owner.anchor_merkle_root(root).await.unwrap();
+    assert_eq!(
+        owner.find_root_anchor(&tx_hash, root).await.unwrap(),
+        Some(owner.sender_address())
+    );
+    assert_eq!(
+        owner
+            .find_root_anchor(&tx_hash.to_uppercase().replace("0X", "0x"), root)
+            .await
+            .unwrap(),
+        Some(owner.sender_address())
+    );
+    assert_eq!(
+        owner.find_root_anchor(&tx_hash, [8u8; 32]).await.unwrap(),
+        None
+    );
+
+    // Anchoring the same root again is a separate transaction
+    assert_ne!(owner.anchor_merkle_root(root).await.unwrap(), tx_hash);
+}
+
+#[test]
+fn classifies_revert_reasons() {
+    assert_eq!(
+        RevertKind::from_reason("Only owner can call this function"),
+        RevertKind::NotOwner
+    );
+    assert_eq!(
+        RevertKind::from_reason("Only manager can call this function"),
+        RevertKind::NotManager
+    );
+    assert_eq!(
+        ContractRevertError::new("transferOwnership", "Invalid owner address".to_string())
+            .status_code(),
+        StatusCode::BAD_REQUEST
+    );
+}